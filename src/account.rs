@@ -0,0 +1,174 @@
+// Copyright 2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::{
+  file_uri_path,
+  trace::{self, CallSpan},
+  ParentWindowArg, PortalError, Results,
+};
+
+use dbus::{
+  arg::{PropMap, Variant},
+  blocking::{self, stdintf::org_freedesktop_dbus},
+  Path,
+};
+use std::path::PathBuf;
+
+const INTERFACE: &str = "org.freedesktop.portal.Account";
+
+/// Implementation of the `org.freedesktop.portal.Account` Portal API.
+/// See also https://flatpak.github.io/xdg-desktop-portal/#gdbus-org.freedesktop.portal.Account
+pub trait Account {
+  /// Gets information about the user.
+  ///
+  /// The information is returned in the results of the `Response` signal,
+  /// use `parse_user_information()` to read it.
+  ///
+  /// - `parent_window`: Identifier for the application window, see crate comments for common conventions.
+  fn get_user_information(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    options: AccountOptions,
+  ) -> Result<Path<'static>, PortalError>;
+
+  /// Reads the "version" property for this D-Bus interface.
+  fn version(&self) -> Result<u32, PortalError>;
+}
+
+/// Optional arguments for the `Account::get_user_information()` method.
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AccountOptions {
+  handle_token: Option<String>,
+  reason: Option<String>,
+}
+
+impl AccountOptions {
+  /// Creates a new `AccountOptions` struct with no arguments set.
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  /// A string that will be used as the last element of the @handle. Must be a valid
+  /// object path element. See the #org.freedesktop.portal.Request documentation for
+  /// more information about the @handle.
+  pub fn handle_token(mut self, handle_token: impl Into<String>) -> Self {
+    self.handle_token = Some(handle_token.into());
+    self
+  }
+
+  /// A string that can be shown in the dialog to explain why the information is needed.
+  pub fn reason(mut self, reason: String) -> Self {
+    self.reason = Some(reason);
+    self
+  }
+}
+
+impl From<AccountOptions> for PropMap {
+  fn from(options: AccountOptions) -> Self {
+    let mut map = PropMap::new();
+    if let Some(handle_token) = options.handle_token {
+      map.insert("handle_token".to_string(), Variant(Box::new(handle_token)));
+    }
+    if let Some(reason) = options.reason {
+      map.insert("reason".to_string(), Variant(Box::new(reason)));
+    }
+    map
+  }
+}
+
+/// Information about the user, as returned by `Account::get_user_information()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UserInformation {
+  /// The user id.
+  pub id: String,
+  /// The user's real name.
+  pub name: String,
+  /// The uri of an image file for the user's avatar picture.
+  pub image: String,
+}
+
+impl UserInformation {
+  /// The local path of the avatar picture, with the percent-encoding of the `image` uri decoded.
+  /// Returns `None` if the `image` isn't a `file://` uri.
+  pub fn image_path(&self) -> Option<PathBuf> {
+    file_uri_path(&self.image)
+  }
+}
+
+/// Reads the `UserInformation` from the results of a successful `Response`.
+pub fn parse_user_information(results: &PropMap) -> Result<UserInformation, PortalError> {
+  Ok(UserInformation {
+    id: get_string(results, "id")?,
+    name: get_string(results, "name")?,
+    image: get_string(results, "image")?,
+  })
+}
+
+fn get_string(results: &PropMap, key: &str) -> Result<String, PortalError> {
+  Results::new(results)
+    .required(key, Results::get_str)
+    .map(ToString::to_string)
+}
+
+impl<'a, T: blocking::BlockingSender, C: std::ops::Deref<Target = T>> Account
+  for blocking::Proxy<'a, C>
+{
+  fn get_user_information(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    options: AccountOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    let parent_window: ParentWindowArg = parent_window.into();
+    let options = PropMap::from(options);
+    let _span = CallSpan::enter(
+      INTERFACE,
+      "GetUserInformation",
+      parent_window.as_str(),
+      trace::handle_token(&options),
+    );
+    self
+      .method_call(
+        INTERFACE,
+        "GetUserInformation",
+        (parent_window.as_str(), options),
+      )
+      .map(|r: (Path<'static>,)| trace::request_created(r.0))
+      .map_err(Into::into)
+  }
+
+  fn version(&self) -> Result<u32, PortalError> {
+    <Self as org_freedesktop_dbus::Properties>::get(self, INTERFACE, "version").map_err(Into::into)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::parse_user_information;
+  use dbus::arg::{PropMap, Variant};
+
+  #[test]
+  fn parse_user_information_results() {
+    let mut results = PropMap::new();
+    results.insert("id".to_string(), Variant(Box::new("jdoe".to_string())));
+    results.insert("name".to_string(), Variant(Box::new("J. Doe".to_string())));
+    results.insert(
+      "image".to_string(),
+      Variant(Box::new("file:///home/jdoe/.face".to_string())),
+    );
+
+    let info = parse_user_information(&results).unwrap();
+    assert_eq!(info.id, "jdoe");
+    assert_eq!(info.name, "J. Doe");
+    assert_eq!(info.image, "file:///home/jdoe/.face");
+    assert_eq!(
+      info.image_path().unwrap().to_str(),
+      Some("/home/jdoe/.face")
+    );
+
+    results.remove("name");
+    assert!(parse_user_information(&results).is_err());
+  }
+}
@@ -0,0 +1,191 @@
+// Copyright 2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::{
+  trace::{self, CallSpan},
+  ParentWindowArg, PortalError,
+};
+
+use dbus::{
+  arg::{PropMap, Variant},
+  blocking::{self, stdintf::org_freedesktop_dbus},
+  Path,
+};
+
+const INTERFACE: &str = "org.freedesktop.portal.Background";
+
+/// Implementation of the `org.freedesktop.portal.Background` Portal API.
+/// See also https://flatpak.github.io/xdg-desktop-portal/#gdbus-org.freedesktop.portal.Background
+pub trait Background {
+  /// Requests that the application is allowed to run in the background.
+  ///
+  /// Whether the application may run in the `background`, and whether it will be started
+  /// automatically (`autostart`), is returned in the results of the `Response` signal.
+  ///
+  /// - `parent_window`: Identifier for the application window, see crate comments for common conventions.
+  fn request_background(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    options: BackgroundOptions,
+  ) -> Result<Path<'static>, PortalError>;
+
+  /// Sets the status of the application running in background.
+  ///
+  /// The SetStatus method was introduced in version 2 of the interface.
+  #[cfg(feature = "spec-v2")]
+  fn set_status(&self, options: StatusOptions) -> Result<(), PortalError>;
+
+  /// Reads the "version" property for this D-Bus interface.
+  fn version(&self) -> Result<u32, PortalError>;
+}
+
+/// Optional arguments for the `Background::request_background()` method.
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BackgroundOptions {
+  handle_token: Option<String>,
+  reason: Option<String>,
+  autostart: Option<bool>,
+  commandline: Option<Vec<String>>,
+  dbus_activatable: Option<bool>,
+}
+
+impl BackgroundOptions {
+  /// Creates a new `BackgroundOptions` struct with no arguments set.
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  /// A string that will be used as the last element of the @handle. Must be a valid
+  /// object path element. See the #org.freedesktop.portal.Request documentation for
+  /// more information about the @handle.
+  pub fn handle_token(mut self, handle_token: impl Into<String>) -> Self {
+    self.handle_token = Some(handle_token.into());
+    self
+  }
+
+  /// User-visible reason for the request.
+  pub fn reason(mut self, reason: String) -> Self {
+    self.reason = Some(reason);
+    self
+  }
+
+  /// Whether to autostart the application at login. Default is no.
+  pub fn autostart(mut self, autostart: bool) -> Self {
+    self.autostart = Some(autostart);
+    self
+  }
+
+  /// Commandline to use when autostarting at login.
+  /// If this is not specified, the `Exec` line from the desktop file will be used.
+  pub fn commandline(mut self, commandline: Vec<String>) -> Self {
+    self.commandline = Some(commandline);
+    self
+  }
+
+  /// If true, use D-Bus activation for autostart. Default is no.
+  pub fn dbus_activatable(mut self, dbus_activatable: bool) -> Self {
+    self.dbus_activatable = Some(dbus_activatable);
+    self
+  }
+}
+
+impl From<BackgroundOptions> for PropMap {
+  fn from(options: BackgroundOptions) -> Self {
+    let mut map = PropMap::new();
+    if let Some(handle_token) = options.handle_token {
+      map.insert("handle_token".to_string(), Variant(Box::new(handle_token)));
+    }
+    if let Some(reason) = options.reason {
+      map.insert("reason".to_string(), Variant(Box::new(reason)));
+    }
+    if let Some(autostart) = options.autostart {
+      map.insert("autostart".to_string(), Variant(Box::new(autostart)));
+    }
+    if let Some(commandline) = options.commandline {
+      map.insert("commandline".to_string(), Variant(Box::new(commandline)));
+    }
+    if let Some(dbus_activatable) = options.dbus_activatable {
+      map.insert(
+        "dbus-activatable".to_string(),
+        Variant(Box::new(dbus_activatable)),
+      );
+    }
+    map
+  }
+}
+
+/// Optional arguments for the `Background::set_status()` method.
+///
+/// The SetStatus method was introduced in version 2 of the interface.
+#[cfg(feature = "spec-v2")]
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StatusOptions {
+  message: Option<String>,
+}
+
+#[cfg(feature = "spec-v2")]
+impl StatusOptions {
+  /// Creates a new `StatusOptions` struct with no arguments set.
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  /// A string that will be used as the status message of the application.
+  /// Must be a single line, at most 96 characters long.
+  pub fn message(mut self, message: String) -> Self {
+    self.message = Some(message);
+    self
+  }
+}
+
+#[cfg(feature = "spec-v2")]
+impl From<StatusOptions> for PropMap {
+  fn from(options: StatusOptions) -> Self {
+    let mut map = PropMap::new();
+    if let Some(message) = options.message {
+      map.insert("message".to_string(), Variant(Box::new(message)));
+    }
+    map
+  }
+}
+
+impl<'a, T: blocking::BlockingSender, C: std::ops::Deref<Target = T>> Background
+  for blocking::Proxy<'a, C>
+{
+  fn request_background(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    options: BackgroundOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    let parent_window: ParentWindowArg = parent_window.into();
+    let options = PropMap::from(options);
+    let _span = CallSpan::enter(
+      INTERFACE,
+      "RequestBackground",
+      parent_window.as_str(),
+      trace::handle_token(&options),
+    );
+    self
+      .method_call(
+        INTERFACE,
+        "RequestBackground",
+        (parent_window.as_str(), options),
+      )
+      .map(|r: (Path<'static>,)| trace::request_created(r.0))
+      .map_err(Into::into)
+  }
+
+  #[cfg(feature = "spec-v2")]
+  fn set_status(&self, options: StatusOptions) -> Result<(), PortalError> {
+    self
+      .method_call(INTERFACE, "SetStatus", (PropMap::from(options),))
+      .map_err(Into::into)
+  }
+
+  fn version(&self) -> Result<u32, PortalError> {
+    <Self as org_freedesktop_dbus::Properties>::get(self, INTERFACE, "version").map_err(Into::into)
+  }
+}
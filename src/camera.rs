@@ -0,0 +1,97 @@
+// Copyright 2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::{
+  trace::{self, CallSpan},
+  PortalError,
+};
+
+use dbus::{
+  arg::{OwnedFd, PropMap, Variant},
+  blocking::{self, stdintf::org_freedesktop_dbus},
+  Path,
+};
+
+const INTERFACE: &str = "org.freedesktop.portal.Camera";
+
+/// Implementation of the `org.freedesktop.portal.Camera` Portal API.
+/// See also https://flatpak.github.io/xdg-desktop-portal/#gdbus-org.freedesktop.portal.Camera
+pub trait Camera {
+  /// Requests access to camera devices.
+  ///
+  /// Once access is granted, use `Camera::open_pipe_wire_remote()` to get access to the cameras.
+  fn access_camera(&self, options: CameraOptions) -> Result<Path<'static>, PortalError>;
+
+  /// Opens a file descriptor to the PipeWire remote where the camera nodes are available.
+  ///
+  /// - `options`: Vardict with optional further information. There are currently no options.
+  fn open_pipe_wire_remote(&self, options: PropMap) -> Result<OwnedFd, PortalError>;
+
+  /// Reads the "IsCameraPresent" property, a hint whether there are any cameras available.
+  fn is_camera_present(&self) -> Result<bool, PortalError>;
+
+  /// Reads the "version" property for this D-Bus interface.
+  fn version(&self) -> Result<u32, PortalError>;
+}
+
+/// Optional arguments for the `Camera::access_camera()` method.
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CameraOptions {
+  handle_token: Option<String>,
+}
+
+impl CameraOptions {
+  /// Creates a new `CameraOptions` struct with no arguments set.
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  /// A string that will be used as the last element of the @handle. Must be a valid
+  /// object path element. See the #org.freedesktop.portal.Request documentation for
+  /// more information about the @handle.
+  pub fn handle_token(mut self, handle_token: impl Into<String>) -> Self {
+    self.handle_token = Some(handle_token.into());
+    self
+  }
+}
+
+impl From<CameraOptions> for PropMap {
+  fn from(options: CameraOptions) -> Self {
+    let mut map = PropMap::new();
+    if let Some(handle_token) = options.handle_token {
+      map.insert("handle_token".to_string(), Variant(Box::new(handle_token)));
+    }
+    map
+  }
+}
+
+impl<'a, T: blocking::BlockingSender, C: std::ops::Deref<Target = T>> Camera
+  for blocking::Proxy<'a, C>
+{
+  fn access_camera(&self, options: CameraOptions) -> Result<Path<'static>, PortalError> {
+    let options = PropMap::from(options);
+    let _span = CallSpan::enter(INTERFACE, "AccessCamera", "", trace::handle_token(&options));
+    self
+      .method_call(INTERFACE, "AccessCamera", (options,))
+      .map(|r: (Path<'static>,)| trace::request_created(r.0))
+      .map_err(Into::into)
+  }
+
+  fn open_pipe_wire_remote(&self, options: PropMap) -> Result<OwnedFd, PortalError> {
+    self
+      .method_call(INTERFACE, "OpenPipeWireRemote", (options,))
+      .map(|r: (OwnedFd,)| r.0)
+      .map_err(Into::into)
+  }
+
+  fn is_camera_present(&self) -> Result<bool, PortalError> {
+    <Self as org_freedesktop_dbus::Properties>::get(self, INTERFACE, "IsCameraPresent")
+      .map_err(Into::into)
+  }
+
+  fn version(&self) -> Result<u32, PortalError> {
+    <Self as org_freedesktop_dbus::Properties>::get(self, INTERFACE, "version").map_err(Into::into)
+  }
+}
@@ -0,0 +1,480 @@
+// Copyright 2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+#[cfg(feature = "dbus")]
+use crate::SourceType;
+use crate::{trace, validate_options, PortalError};
+
+#[cfg(feature = "dbus")]
+use dbus::arg::{ArgType, PropMap, RefArg, Variant};
+#[cfg(feature = "dbus")]
+use dbus::blocking::{
+  stdintf::org_freedesktop_dbus::{Introspectable, Properties},
+  BlockingSender, Proxy,
+};
+use std::env;
+#[cfg(feature = "dbus")]
+use std::{ffi::OsString, ops::Deref, os::unix::ffi::OsStringExt, path::PathBuf};
+
+/// D-Bus errors meaning the portal service, or the interface on it, doesn't exist.
+#[cfg(feature = "dbus")]
+const UNAVAILABLE_ERRORS: &[&str] = &[
+  "org.freedesktop.DBus.Error.ServiceUnknown",
+  "org.freedesktop.DBus.Error.NameHasNoOwner",
+  "org.freedesktop.DBus.Error.UnknownMethod",
+  "org.freedesktop.DBus.Error.UnknownInterface",
+  "org.freedesktop.DBus.Error.UnknownObject",
+  "org.freedesktop.DBus.Error.UnknownProperty",
+  "org.freedesktop.DBus.Error.InvalidArgs",
+];
+
+/// Checks whether the portal `interface`, such as `org.freedesktop.portal.Screenshot`, is served by `proxy`.
+///
+/// Not every desktop ships every portal backend. This reads the "version" property of the interface,
+/// and treats the errors of a missing service or interface as `false`, including a portal service that isn't running
+/// and couldn't be started. Other errors are returned.
+#[cfg(feature = "dbus")]
+pub fn portal_available<'a, B: BlockingSender, C: Deref<Target = B>>(
+  proxy: &Proxy<'a, C>,
+  interface: &str,
+) -> Result<bool, PortalError> {
+  match Properties::get::<u32>(proxy, interface, "version") {
+    Ok(_) => Ok(true),
+    Err(err) if is_unavailable(&err) => Ok(false),
+    Err(err) => Err(err.into()),
+  }
+}
+
+/// A portal interface served on the portal object, see `enumerate_portals()`.
+#[cfg(feature = "dbus")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PortalInfo {
+  /// The name of the interface, such as `org.freedesktop.portal.Screenshot`.
+  pub name: String,
+  /// The version of the interface the portal implements.
+  pub version: u32,
+}
+
+/// Lists the portal interfaces served by `proxy`, with the version each one implements.
+///
+/// This introspects the portal object, and reads the "version" property of every `org.freedesktop.portal.*`
+/// interface on it. Meant for diagnostics such as bug reports, the `Portals::*_capabilities()` methods
+/// are a better fit for checking the features of a single portal.
+#[cfg(feature = "dbus")]
+pub fn enumerate_portals<'a, B: BlockingSender, C: Deref<Target = B>>(
+  proxy: &Proxy<'a, C>,
+) -> Result<Vec<PortalInfo>, PortalError> {
+  let xml = Introspectable::introspect(proxy)?;
+  portal_interfaces(&xml)
+    .into_iter()
+    .map(|name| {
+      let version = Properties::get::<u32>(proxy, &name, "version")?;
+      Ok(PortalInfo { name, version })
+    })
+    .collect()
+}
+
+/// The names of the `org.freedesktop.portal.*` interfaces in introspection `xml`.
+#[cfg(feature = "dbus")]
+fn portal_interfaces(xml: &str) -> Vec<String> {
+  xml
+    .split("<interface")
+    .skip(1)
+    .filter_map(|element| {
+      let name = element.trim_start().strip_prefix("name=")?;
+      let quote = name.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+      let name = &name[1..];
+      Some(name[..name.find(quote)?].to_string())
+    })
+    .filter(|name| name.starts_with("org.freedesktop.portal."))
+    .collect()
+}
+
+#[cfg(feature = "dbus")]
+fn is_unavailable(err: &dbus::Error) -> bool {
+  err.name().is_some_and(|name| {
+    UNAVAILABLE_ERRORS.contains(&name) || name.starts_with("org.freedesktop.DBus.Error.Spawn.")
+  })
+}
+
+/// A token for the xdg-activation protocol, which allows the application or dialog
+/// a portal shows to take focus without being blocked by focus-stealing prevention.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ActivationToken(pub String);
+
+impl ActivationToken {
+  /// Reads the token from the `XDG_ACTIVATION_TOKEN` environment variable,
+  /// which launchers set for the application they started, or else from the X11 `DESKTOP_STARTUP_ID`.
+  ///
+  /// The token is single use: the variable should be unset once it's consumed, so child processes don't inherit it.
+  pub fn from_env() -> Option<Self> {
+    Self::from_values(
+      env::var("XDG_ACTIVATION_TOKEN").ok(),
+      env::var("DESKTOP_STARTUP_ID").ok(),
+    )
+  }
+
+  /// Picks the first non-empty of the `XDG_ACTIVATION_TOKEN` and `DESKTOP_STARTUP_ID` values.
+  fn from_values(activation_token: Option<String>, startup_id: Option<String>) -> Option<Self> {
+    activation_token
+      .into_iter()
+      .chain(startup_id)
+      .find(|activation_token| !activation_token.is_empty())
+      .map(Self)
+  }
+}
+
+impl From<String> for ActivationToken {
+  fn from(activation_token: String) -> Self {
+    Self(activation_token)
+  }
+}
+
+impl From<&str> for ActivationToken {
+  fn from(activation_token: &str) -> Self {
+    Self(activation_token.to_string())
+  }
+}
+
+/// The options of a portal method, of which some were introduced in later versions of the interface.
+///
+/// The portal silently ignores the options it doesn't support. Check them against the version of the interface
+/// the portal implements, as read by the `version()` method of its Trait, with `VersionedOptions::validate_against()`
+/// to fail early, or `VersionedOptions::supported_by()` to drop them.
+pub trait VersionedOptions: Sized {
+  /// The name, the version of the interface that introduced it, and whether it's set,
+  /// of each option to check.
+  fn versioned_options(&self) -> Vec<(&'static str, u32, bool)>;
+
+  /// Unsets `option`, one of the `VersionedOptions::versioned_options()`.
+  fn unset_option(&mut self, option: &str);
+
+  /// Checks that the options set are supported by the `version` of the interface the portal implements.
+  /// Fails with `PortalError::UnsupportedOption` for the first one that isn't.
+  fn validate_against(&self, version: u32) -> Result<(), PortalError> {
+    validate_options(version, &self.versioned_options())
+  }
+
+  /// Unsets the options that aren't supported by the `version` of the interface the portal implements.
+  ///
+  /// This lets a single binary set newer options, and still make requests to portals implementing older versions
+  /// of the interface. Each option that is unset is logged, when the `tracing` feature is enabled.
+  fn supported_by(mut self, version: u32) -> Self {
+    while let Err(PortalError::UnsupportedOption {
+      option, required, ..
+    }) = self.validate_against(version)
+    {
+      trace::option_unset(option, required, version);
+      self.unset_option(option);
+    }
+    self
+  }
+}
+
+/// Inserts the `activation_token` option, if it's set.
+#[cfg(feature = "dbus")]
+pub(crate) fn insert_activation_token(
+  map: &mut PropMap,
+  activation_token: Option<ActivationToken>,
+) {
+  if let Some(ActivationToken(activation_token)) = activation_token {
+    map.insert(
+      "activation_token".to_string(),
+      Variant(Box::new(activation_token)),
+    );
+  }
+}
+
+/// The local path of a `file://` uri, with its percent-encoding decoded.
+/// Returns `None` for other uris, and for files on another host.
+#[cfg(feature = "dbus")]
+pub(crate) fn file_uri_path(uri: &str) -> Option<PathBuf> {
+  let rest = uri.strip_prefix("file://")?;
+  let path = rest.strip_prefix("localhost").unwrap_or(rest);
+  if !path.starts_with('/') {
+    return None;
+  }
+  let mut bytes = Vec::with_capacity(path.len());
+  let mut input = path.bytes();
+  while let Some(byte) = input.next() {
+    if byte == b'%' {
+      let hex = [input.next()?, input.next()?];
+      bytes.push(u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?);
+    } else {
+      bytes.push(byte);
+    }
+  }
+  Some(PathBuf::from(OsString::from_vec(bytes)))
+}
+
+/// A PipeWire stream of a screen cast, as returned by `parse_streams()`.
+#[cfg(feature = "dbus")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Stream {
+  /// The PipeWire node id of the stream.
+  pub node_id: u32,
+  /// The position of a monitor stream in the compositor coordinate space.
+  pub position: Option<(i32, i32)>,
+  /// The size of the stream in the compositor coordinate space.
+  pub size: Option<(i32, i32)>,
+  /// The type of content shared by the stream.
+  pub source_type: Option<SourceType>,
+  /// An opaque identifier of the stream, which stays the same when a session is restored.
+  pub id: Option<String>,
+}
+
+/// Reads the `a(ua{sv})` `streams` from the results of a successful `ScreenCast::start()`
+/// or `RemoteDesktop::start()` response.
+#[cfg(feature = "dbus")]
+pub fn parse_streams(results: &PropMap) -> Result<Vec<Stream>, PortalError> {
+  let streams = results
+    .get("streams")
+    .ok_or_else(|| PortalError::UnexpectedResponse("missing result `streams`".to_string()))?;
+  let mismatch = || PortalError::TypeMismatch {
+    key: "streams".to_string(),
+    expected: "a(ua{sv})",
+    found: streams.0.signature().to_string(),
+  };
+  let streams = streams.0.as_iter().ok_or_else(mismatch)?;
+  streams
+    .map(|stream| parse_stream(stream).ok_or_else(mismatch))
+    .collect()
+}
+
+#[cfg(feature = "dbus")]
+fn parse_stream(stream: &dyn RefArg) -> Option<Stream> {
+  let mut fields = stream.as_iter()?;
+  let node_id = fields.next()?.as_u64()? as u32;
+  let mut properties = fields.next()?.as_iter()?;
+  let mut stream = Stream {
+    node_id,
+    position: None,
+    size: None,
+    source_type: None,
+    id: None,
+  };
+  while let (Some(key), Some(value)) = (properties.next(), properties.next()) {
+    match key.as_str() {
+      Some("position") => stream.position = parse_pair(value),
+      Some("size") => stream.size = parse_pair(value),
+      Some("source_type") => {
+        stream.source_type = value
+          .as_u64()
+          .map(|bits| SourceType::from_bits(bits as u32))
+      }
+      Some("id") => stream.id = value.as_str().map(ToString::to_string),
+      _ => {}
+    }
+  }
+  Some(stream)
+}
+
+/// Reads a `(ii)` struct, possibly wrapped in a variant.
+#[cfg(feature = "dbus")]
+fn parse_pair(value: &dyn RefArg) -> Option<(i32, i32)> {
+  let mut value = value;
+  while value.arg_type() == ArgType::Variant {
+    value = value.as_iter()?.next()?;
+  }
+  let mut fields = value.as_iter()?;
+  let x = fields.next()?.as_i64()? as i32;
+  let y = fields.next()?.as_i64()? as i32;
+  Some((x, y))
+}
+
+#[cfg(test)]
+mod test {
+  #[test]
+  #[cfg(feature = "testing")]
+  fn portal_available_on_mock() {
+    use super::portal_available;
+    use crate::{
+      new_blocking,
+      testing::{MockPortal, TestBus},
+    };
+    use std::time::Duration;
+
+    let bus = TestBus::new().unwrap();
+    let conn = bus.connect().unwrap();
+    let portals = new_blocking(Duration::from_secs(2), &conn);
+    assert!(!portal_available(&portals, "org.freedesktop.portal.OpenURI").unwrap());
+
+    let _mock = MockPortal::start(&bus, 4).unwrap();
+    assert!(portal_available(&portals, "org.freedesktop.portal.OpenURI").unwrap());
+    assert!(!portal_available(&portals, "org.freedesktop.portal.Screenshot").unwrap());
+  }
+
+  #[test]
+  #[cfg(feature = "testing")]
+  fn portal_available_on_errors() {
+    use super::portal_available;
+    use crate::{new_blocking, testing::MockSender};
+    use std::time::Duration;
+
+    let sender = MockSender::new();
+    let portals = new_blocking(Duration::from_secs(2), &sender);
+    for name in [
+      "org.freedesktop.DBus.Error.NameHasNoOwner",
+      "org.freedesktop.DBus.Error.Spawn.ChildExited",
+      "org.freedesktop.DBus.Error.Spawn.ServiceNotFound",
+    ] {
+      sender.error("org.freedesktop.DBus.Properties", "Get", name, "message");
+      assert!(!portal_available(&portals, "org.freedesktop.portal.OpenURI").unwrap());
+    }
+    sender.error(
+      "org.freedesktop.DBus.Properties",
+      "Get",
+      "org.freedesktop.DBus.Error.AccessDenied",
+      "message",
+    );
+    assert!(portal_available(&portals, "org.freedesktop.portal.OpenURI").is_err());
+  }
+
+  #[test]
+  #[cfg(feature = "testing")]
+  fn enumerate_portals_on_mock() {
+    use super::{enumerate_portals, PortalInfo};
+    use crate::{
+      new_blocking,
+      testing::{MockPortal, TestBus},
+    };
+    use std::time::Duration;
+
+    let bus = TestBus::new().unwrap();
+    let conn = bus.connect().unwrap();
+    let portals = new_blocking(Duration::from_secs(2), &conn);
+    let _mock = MockPortal::start(&bus, 4).unwrap();
+    assert_eq!(
+      enumerate_portals(&portals).unwrap(),
+      [PortalInfo {
+        name: "org.freedesktop.portal.OpenURI".to_string(),
+        version: 4,
+      }]
+    );
+  }
+
+  #[test]
+  #[cfg(feature = "dbus")]
+  fn portal_interfaces_from_introspection() {
+    use super::portal_interfaces;
+
+    let xml = r#"<!DOCTYPE node PUBLIC "-//freedesktop//DTD D-BUS Object Introspection 1.0//EN"
+      "http://www.freedesktop.org/standards/dbus/1.0/introspect.dtd">
+      <node>
+        <interface name="org.freedesktop.DBus.Properties">
+          <method name="Get"/>
+        </interface>
+        <interface name="org.freedesktop.portal.OpenURI">
+          <property name="version" type="u" access="read"/>
+        </interface>
+        <interface name='org.freedesktop.portal.Screenshot'>
+        </interface>
+        <node name="request"/>
+      </node>"#;
+    assert_eq!(
+      portal_interfaces(xml),
+      [
+        "org.freedesktop.portal.OpenURI",
+        "org.freedesktop.portal.Screenshot"
+      ]
+    );
+  }
+
+  #[test]
+  #[cfg(feature = "dbus")]
+  fn insert_activation_token_if_set() {
+    use super::insert_activation_token;
+    use dbus::arg::{prop_cast, PropMap};
+
+    let mut map = PropMap::new();
+    insert_activation_token(&mut map, None);
+    assert!(map.is_empty());
+    insert_activation_token(&mut map, Some("token".into()));
+    assert_eq!(
+      prop_cast::<String>(&map, "activation_token").unwrap(),
+      "token"
+    );
+  }
+
+  #[test]
+  #[cfg(feature = "dbus")]
+  fn file_uri_path_decoded() {
+    use super::file_uri_path;
+
+    assert_eq!(
+      file_uri_path("file:///tmp/a%20b.txt").unwrap().to_str(),
+      Some("/tmp/a b.txt")
+    );
+    assert_eq!(
+      file_uri_path("file://localhost/tmp/a.txt")
+        .unwrap()
+        .to_str(),
+      Some("/tmp/a.txt")
+    );
+    assert!(file_uri_path("file://host/tmp/a.txt").is_none());
+    assert!(file_uri_path("file:///tmp/a%2").is_none());
+    assert!(file_uri_path("https://example.com/a.png").is_none());
+    assert!(file_uri_path("/tmp/a.txt").is_none());
+  }
+
+  #[test]
+  fn activation_token_from_values() {
+    use super::ActivationToken;
+
+    let token = |value: &str| Some(value.to_string());
+    assert_eq!(
+      ActivationToken::from_values(token("token"), token("startup")),
+      Some("token".into())
+    );
+    assert_eq!(
+      ActivationToken::from_values(token(""), token("startup")),
+      Some("startup".into())
+    );
+    assert_eq!(
+      ActivationToken::from_values(None, token("startup")),
+      Some("startup".into())
+    );
+    assert_eq!(ActivationToken::from_values(token(""), token("")), None);
+    assert_eq!(ActivationToken::from_values(None, None), None);
+  }
+
+  #[test]
+  #[cfg(feature = "dbus")]
+  fn parse_streams_results() {
+    use super::parse_streams;
+    use crate::{PortalError, SourceType};
+    use dbus::arg::{PropMap, Variant};
+
+    let mut properties = PropMap::new();
+    properties.insert("position".to_string(), Variant(Box::new((10i32, 20i32))));
+    properties.insert("size".to_string(), Variant(Box::new((1920i32, 1080i32))));
+    properties.insert("source_type".to_string(), Variant(Box::new(2u32)));
+    properties.insert("id".to_string(), Variant(Box::new("first".to_string())));
+    let mut results = PropMap::new();
+    results.insert(
+      "streams".to_string(),
+      Variant(Box::new(vec![(42u32, properties), (43u32, PropMap::new())])),
+    );
+
+    let streams = parse_streams(&results).unwrap();
+    assert_eq!(streams.len(), 2);
+    assert_eq!(streams[0].node_id, 42);
+    assert_eq!(streams[0].position, Some((10, 20)));
+    assert_eq!(streams[0].size, Some((1920, 1080)));
+    assert_eq!(streams[0].source_type, Some(SourceType::WINDOW));
+    assert_eq!(streams[0].id.as_deref(), Some("first"));
+    assert_eq!(streams[1].node_id, 43);
+    assert_eq!(streams[1].position, None);
+    assert_eq!(streams[1].source_type, None);
+
+    results.insert("streams".to_string(), Variant(Box::new(1u32)));
+    assert!(matches!(
+      parse_streams(&results),
+      Err(PortalError::TypeMismatch { .. })
+    ));
+  }
+}
@@ -0,0 +1,130 @@
+// Copyright 2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::{
+  trace::{self, CallSpan},
+  PortalError,
+};
+
+use dbus::{
+  arg::{PropMap, Variant},
+  blocking::{self, stdintf::org_freedesktop_dbus},
+  Path,
+};
+
+const INTERFACE: &str = "org.freedesktop.portal.Device";
+
+/// Implementation of the `org.freedesktop.portal.Device` Portal API.
+/// See also https://flatpak.github.io/xdg-desktop-portal/#gdbus-org.freedesktop.portal.Device
+///
+/// Named `DevicePortal`, so it doesn't clash with the `Device` type.
+pub trait DevicePortal {
+  /// Asks to grant another process access to devices.
+  ///
+  /// This portal is meant for services that act on behalf of a sandboxed application, such as a sound server,
+  /// rather than for the sandboxed application itself. Whether access was granted is returned by the
+  /// `Response` signal, there are no further results.
+  ///
+  /// - `pid`: The process id of the process to grant access to.
+  /// - `devices`: The devices to grant access to.
+  fn access_device(
+    &self,
+    pid: u32,
+    devices: &[Device],
+    options: AccessDeviceOptions,
+  ) -> Result<Path<'static>, PortalError>;
+
+  /// Reads the "version" property for this D-Bus interface.
+  fn version(&self) -> Result<u32, PortalError>;
+}
+
+/// A type of device, see `DevicePortal::access_device()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Device {
+  /// Audio input.
+  Microphone,
+  /// Audio output.
+  Speakers,
+  /// Video input.
+  Camera,
+}
+
+impl Device {
+  /// The name of the device type, as sent to the portal.
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Self::Microphone => "microphone",
+      Self::Speakers => "speakers",
+      Self::Camera => "camera",
+    }
+  }
+}
+
+/// Optional arguments for the `DevicePortal::access_device()` method.
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AccessDeviceOptions {
+  handle_token: Option<String>,
+}
+
+impl AccessDeviceOptions {
+  /// Creates a new `AccessDeviceOptions` struct with no arguments set.
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  /// A string that will be used as the last element of the @handle. Must be a valid
+  /// object path element. See the #org.freedesktop.portal.Request documentation for
+  /// more information about the @handle.
+  pub fn handle_token(mut self, handle_token: impl Into<String>) -> Self {
+    self.handle_token = Some(handle_token.into());
+    self
+  }
+}
+
+impl From<AccessDeviceOptions> for PropMap {
+  fn from(options: AccessDeviceOptions) -> Self {
+    let mut map = PropMap::new();
+    if let Some(handle_token) = options.handle_token {
+      map.insert("handle_token".to_string(), Variant(Box::new(handle_token)));
+    }
+    map
+  }
+}
+
+impl<'a, T: blocking::BlockingSender, C: std::ops::Deref<Target = T>> DevicePortal
+  for blocking::Proxy<'a, C>
+{
+  fn access_device(
+    &self,
+    pid: u32,
+    devices: &[Device],
+    options: AccessDeviceOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    let devices: Vec<&str> = devices.iter().map(Device::as_str).collect();
+    let options = PropMap::from(options);
+    let _span = CallSpan::enter(INTERFACE, "AccessDevice", "", trace::handle_token(&options));
+    self
+      .method_call(INTERFACE, "AccessDevice", (pid, devices, options))
+      .map(|r: (Path<'static>,)| trace::request_created(r.0))
+      .map_err(Into::into)
+  }
+
+  fn version(&self) -> Result<u32, PortalError> {
+    <Self as org_freedesktop_dbus::Properties>::get(self, INTERFACE, "version").map_err(Into::into)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::Device;
+
+  #[test]
+  fn device_names() {
+    assert_eq!(Device::Microphone.as_str(), "microphone");
+    assert_eq!(Device::Speakers.as_str(), "speakers");
+    assert_eq!(Device::Camera.as_str(), "camera");
+  }
+}
@@ -0,0 +1,274 @@
+// Copyright 2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::{IntoPortalFd, PortalError};
+
+use dbus::blocking::{self, stdintf::org_freedesktop_dbus};
+use std::collections::HashMap;
+
+const INTERFACE: &str = "org.freedesktop.portal.Documents";
+
+/// Implementation of the `org.freedesktop.portal.Documents` Portal API.
+/// See also https://flatpak.github.io/xdg-desktop-portal/#gdbus-org.freedesktop.portal.Documents
+///
+/// This portal is served by the document portal on its own bus name and object path,
+/// use `new_documents_blocking()` to create a proxy for it.
+///
+/// Exported documents are available to sandboxed applications in the document portal's
+/// fuse filesystem, at `<mount point>/<doc id>/<file name>`.
+pub trait Documents {
+  /// Returns the path at which the document store fuse filesystem is mounted.
+  /// The trailing nul byte the path is sent with is removed.
+  fn get_mount_point(&self) -> Result<Vec<u8>, PortalError>;
+
+  /// Adds a file to the document store, returning its doc id.
+  ///
+  /// - `fd`: File descriptor for the file to add, can be opened with `O_PATH`.
+  /// - `reuse_existing`: Whether to reuse an existing document store entry for the file.
+  /// - `persistent`: Whether to add the file only for this session or permanently.
+  fn add(
+    &self,
+    fd: impl IntoPortalFd,
+    reuse_existing: bool,
+    persistent: bool,
+  ) -> Result<String, PortalError>;
+
+  /// Creates an entry in the document store for a file that may not exist yet,
+  /// returning its doc id.
+  ///
+  /// - `parent_fd`: File descriptor for the parent directory, can be opened with `O_PATH`.
+  /// - `filename`: The basename of the file.
+  /// - `reuse_existing`: Whether to reuse an existing document store entry for the file.
+  /// - `persistent`: Whether to add the file only for this session or permanently.
+  fn add_named(
+    &self,
+    parent_fd: impl IntoPortalFd,
+    filename: &[u8],
+    reuse_existing: bool,
+    persistent: bool,
+  ) -> Result<String, PortalError>;
+
+  /// Grants access permissions for a file in the document store to an application.
+  ///
+  /// - `doc_id`: The id of the file in the document store.
+  /// - `app_id`: The id of the application to grant the permissions to.
+  fn grant_permissions(
+    &self,
+    doc_id: &str,
+    app_id: &str,
+    permissions: &[Permission],
+  ) -> Result<(), PortalError>;
+
+  /// Revokes access permissions for a file in the document store from an application.
+  ///
+  /// - `doc_id`: The id of the file in the document store.
+  /// - `app_id`: The id of the application to revoke the permissions from.
+  fn revoke_permissions(
+    &self,
+    doc_id: &str,
+    app_id: &str,
+    permissions: &[Permission],
+  ) -> Result<(), PortalError>;
+
+  /// Lists the documents in the document store an application has access to,
+  /// as a map of doc ids to their paths on the host.
+  ///
+  /// - `app_id`: The id of the application, or an empty string to list all documents.
+  fn list(&self, app_id: &str) -> Result<HashMap<String, Vec<u8>>, PortalError>;
+
+  /// Gets the path on the host of a file in the document store,
+  /// and the applications that have permissions for it.
+  ///
+  /// - `doc_id`: The id of the file in the document store.
+  fn info(&self, doc_id: &str) -> Result<DocumentInfo, PortalError>;
+
+  /// Reads the "version" property for this D-Bus interface.
+  fn version(&self) -> Result<u32, PortalError>;
+}
+
+/// A permission for a file in the document store.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Permission {
+  /// Read the file.
+  Read,
+  /// Write the file.
+  Write,
+  /// Grant permissions for the file to other applications.
+  GrantPermissions,
+  /// Delete the file from the document store.
+  Delete,
+}
+
+impl Permission {
+  fn as_str(&self) -> &'static str {
+    match self {
+      Self::Read => "read",
+      Self::Write => "write",
+      Self::GrantPermissions => "grant-permissions",
+      Self::Delete => "delete",
+    }
+  }
+}
+
+/// Information about a file in the document store, as returned by `Documents::info()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DocumentInfo {
+  /// The path of the file on the host.
+  pub path: Vec<u8>,
+  /// A map of application ids to the names of the permissions they have, e.g. `read`.
+  pub apps: HashMap<String, Vec<String>>,
+}
+
+fn permission_names(permissions: &[Permission]) -> Vec<&'static str> {
+  permissions.iter().map(Permission::as_str).collect()
+}
+
+/// Converts a file name to the nul terminated byte string the document portal expects.
+fn to_bytestring(filename: &[u8]) -> Vec<u8> {
+  let mut bytes = filename.to_vec();
+  if bytes.last() != Some(&0) {
+    bytes.push(0);
+  }
+  bytes
+}
+
+/// Strips the trailing nul byte of a byte string sent by the document portal.
+fn from_bytestring(mut bytes: Vec<u8>) -> Vec<u8> {
+  if bytes.last() == Some(&0) {
+    bytes.pop();
+  }
+  bytes
+}
+
+impl<'a, T: blocking::BlockingSender, C: std::ops::Deref<Target = T>> Documents
+  for blocking::Proxy<'a, C>
+{
+  fn get_mount_point(&self) -> Result<Vec<u8>, PortalError> {
+    self
+      .method_call(INTERFACE, "GetMountPoint", ())
+      .map(|r: (Vec<u8>,)| from_bytestring(r.0))
+      .map_err(Into::into)
+  }
+
+  fn add(
+    &self,
+    fd: impl IntoPortalFd,
+    reuse_existing: bool,
+    persistent: bool,
+  ) -> Result<String, PortalError> {
+    self
+      .method_call(
+        INTERFACE,
+        "Add",
+        (fd.into_portal_fd(), reuse_existing, persistent),
+      )
+      .map(|r: (String,)| r.0)
+      .map_err(Into::into)
+  }
+
+  fn add_named(
+    &self,
+    parent_fd: impl IntoPortalFd,
+    filename: &[u8],
+    reuse_existing: bool,
+    persistent: bool,
+  ) -> Result<String, PortalError> {
+    self
+      .method_call(
+        INTERFACE,
+        "AddNamed",
+        (
+          parent_fd.into_portal_fd(),
+          to_bytestring(filename),
+          reuse_existing,
+          persistent,
+        ),
+      )
+      .map(|r: (String,)| r.0)
+      .map_err(Into::into)
+  }
+
+  fn grant_permissions(
+    &self,
+    doc_id: &str,
+    app_id: &str,
+    permissions: &[Permission],
+  ) -> Result<(), PortalError> {
+    self
+      .method_call(
+        INTERFACE,
+        "GrantPermissions",
+        (doc_id, app_id, permission_names(permissions)),
+      )
+      .map_err(Into::into)
+  }
+
+  fn revoke_permissions(
+    &self,
+    doc_id: &str,
+    app_id: &str,
+    permissions: &[Permission],
+  ) -> Result<(), PortalError> {
+    self
+      .method_call(
+        INTERFACE,
+        "RevokePermissions",
+        (doc_id, app_id, permission_names(permissions)),
+      )
+      .map_err(Into::into)
+  }
+
+  fn list(&self, app_id: &str) -> Result<HashMap<String, Vec<u8>>, PortalError> {
+    self
+      .method_call(INTERFACE, "List", (app_id,))
+      .map(|r: (HashMap<String, Vec<u8>>,)| {
+        r.0
+          .into_iter()
+          .map(|(doc_id, path)| (doc_id, from_bytestring(path)))
+          .collect()
+      })
+      .map_err(Into::into)
+  }
+
+  fn info(&self, doc_id: &str) -> Result<DocumentInfo, PortalError> {
+    self
+      .method_call(INTERFACE, "Info", (doc_id,))
+      .map(
+        |(path, apps): (Vec<u8>, HashMap<String, Vec<String>>)| DocumentInfo {
+          path: from_bytestring(path),
+          apps,
+        },
+      )
+      .map_err(Into::into)
+  }
+
+  fn version(&self) -> Result<u32, PortalError> {
+    <Self as org_freedesktop_dbus::Properties>::get(self, INTERFACE, "version").map_err(Into::into)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::{from_bytestring, permission_names, to_bytestring, Permission};
+
+  #[test]
+  fn bytestrings() {
+    assert_eq!(to_bytestring(b"report.pdf"), b"report.pdf\0");
+    assert_eq!(to_bytestring(b"report.pdf\0"), b"report.pdf\0");
+    assert_eq!(
+      from_bytestring(b"/run/user/1000/doc\0".to_vec()),
+      b"/run/user/1000/doc"
+    );
+  }
+
+  #[test]
+  fn permissions_as_strings() {
+    assert_eq!(
+      permission_names(&[Permission::Read, Permission::GrantPermissions]),
+      vec!["read", "grant-permissions"]
+    );
+  }
+}
@@ -0,0 +1,459 @@
+// Copyright 2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::{
+  insert_activation_token,
+  trace::{self, CallSpan},
+  ActivationToken, Icon, ParentWindowArg, PortalError, Results, VersionedOptions,
+};
+
+use dbus::{
+  arg::{PropMap, Variant},
+  blocking::{self, stdintf::org_freedesktop_dbus},
+  Path,
+};
+use std::ops::{BitOr, BitOrAssign};
+
+const INTERFACE: &str = "org.freedesktop.portal.DynamicLauncher";
+
+/// The wire format of an `Icon::Bytes`, a serialized `GBytesIcon`.
+type BytesIconArg = Variant<(String, Variant<Vec<u8>>)>;
+
+/// Implementation of the `org.freedesktop.portal.DynamicLauncher` Portal API.
+/// See also https://flatpak.github.io/xdg-desktop-portal/#gdbus-org.freedesktop.portal.DynamicLauncher
+///
+/// Installing a launcher, such as for a web application, goes through these steps:
+/// 1. `DynamicLauncher::prepare_install()`, which lets the user confirm the name and icon, and returns a token,
+///    see `parse_prepare_install()`. Or, without a dialog, `DynamicLauncher::request_install_token()`.
+/// 2. `DynamicLauncher::install()` with the token and a desktop entry.
+///
+/// The icons must be `Icon::Bytes` of a PNG, JPEG or SVG image.
+pub trait DynamicLauncher {
+  /// Presents a dialog to confirm installing a launcher, with the given `name` and `icon` as suggestions.
+  ///
+  /// The chosen `name` and the `token` to install the launcher with are returned in the results
+  /// of the `Response` signal, see `parse_prepare_install()`.
+  ///
+  /// - `parent_window`: Identifier for the application window, see crate comments for common conventions.
+  fn prepare_install(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    name: &str,
+    icon: Icon,
+    options: PrepareInstallOptions,
+  ) -> Result<Path<'static>, PortalError>;
+
+  /// Requests a token to install a launcher, without a dialog.
+  ///
+  /// Only some applications are allowed to do this, such as the ones that are part of the desktop.
+  fn request_install_token(&self, name: &str, icon: Icon) -> Result<String, PortalError>;
+
+  /// Installs a launcher.
+  ///
+  /// - `token`: A token from `DynamicLauncher::prepare_install()` or `DynamicLauncher::request_install_token()`.
+  /// - `desktop_file_id`: The file name of the desktop entry, prefixed with the application id,
+  ///   like `org.example.App.webapp.desktop`.
+  /// - `desktop_entry`: The contents of the desktop entry, without the `Name` and `Icon` keys which the portal adds.
+  /// - `options`: Vardict with optional further information. There are currently no options.
+  fn install(
+    &self,
+    token: &str,
+    desktop_file_id: &str,
+    desktop_entry: &str,
+    options: PropMap,
+  ) -> Result<(), PortalError>;
+
+  /// Uninstalls a launcher installed by the application.
+  ///
+  /// - `options`: Vardict with optional further information. There are currently no options.
+  fn uninstall(&self, desktop_file_id: &str, options: PropMap) -> Result<(), PortalError>;
+
+  /// Reads the contents of the desktop entry of a launcher installed by the application.
+  fn get_desktop_entry(&self, desktop_file_id: &str) -> Result<String, PortalError>;
+
+  /// Reads the icon of a launcher installed by the application.
+  fn get_icon(&self, desktop_file_id: &str) -> Result<LauncherIcon, PortalError>;
+
+  /// Launches a launcher installed by the application.
+  fn launch(&self, desktop_file_id: &str, options: LaunchOptions) -> Result<(), PortalError>;
+
+  /// Reads the "SupportedLauncherTypes" property, the launcher types that can be installed.
+  fn supported_launcher_types(&self) -> Result<LauncherType, PortalError>;
+
+  /// Reads the "version" property for this D-Bus interface.
+  fn version(&self) -> Result<u32, PortalError>;
+}
+
+/// Types of launchers. Types can be combined with `|`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LauncherType(u32);
+
+impl LauncherType {
+  /// A launcher for an application.
+  pub const APPLICATION: Self = Self(1);
+  /// A launcher for a web application.
+  pub const WEB_APPLICATION: Self = Self(2);
+
+  /// No types set.
+  pub fn empty() -> Self {
+    Self(0)
+  }
+
+  /// Creates a `LauncherType` from the raw bits sent by the portal.
+  pub fn from_bits(bits: u32) -> Self {
+    Self(bits)
+  }
+
+  /// The raw bits, as sent to the portal.
+  pub fn bits(&self) -> u32 {
+    self.0
+  }
+
+  /// Whether all types in `other` are set.
+  pub fn contains(&self, other: Self) -> bool {
+    self.0 & other.0 == other.0
+  }
+}
+
+impl BitOr for LauncherType {
+  type Output = Self;
+
+  fn bitor(self, rhs: Self) -> Self {
+    Self(self.0 | rhs.0)
+  }
+}
+
+impl BitOrAssign for LauncherType {
+  fn bitor_assign(&mut self, rhs: Self) {
+    self.0 |= rhs.0;
+  }
+}
+
+/// Optional arguments for the `DynamicLauncher::prepare_install()` method.
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PrepareInstallOptions {
+  handle_token: Option<String>,
+  modal: Option<bool>,
+  launcher_type: Option<LauncherType>,
+  target: Option<String>,
+  editable_name: Option<bool>,
+  editable_icon: Option<bool>,
+}
+
+impl PrepareInstallOptions {
+  /// Creates a new `PrepareInstallOptions` struct with no arguments set.
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  /// A string that will be used as the last element of the @handle. Must be a valid
+  /// object path element. See the #org.freedesktop.portal.Request documentation for
+  /// more information about the @handle.
+  pub fn handle_token(mut self, handle_token: impl Into<String>) -> Self {
+    self.handle_token = Some(handle_token.into());
+    self
+  }
+
+  /// Whether the dialog should be modal. Default is yes.
+  pub fn modal(mut self, modal: bool) -> Self {
+    self.modal = Some(modal);
+    self
+  }
+
+  /// The type of launcher. Default is `LauncherType::APPLICATION`.
+  pub fn launcher_type(mut self, launcher_type: LauncherType) -> Self {
+    self.launcher_type = Some(launcher_type);
+    self
+  }
+
+  /// The url a `LauncherType::WEB_APPLICATION` launcher opens, shown in the dialog.
+  pub fn target(mut self, target: String) -> Self {
+    self.target = Some(target);
+    self
+  }
+
+  /// Whether the user can change the name. Default is yes.
+  pub fn editable_name(mut self, editable_name: bool) -> Self {
+    self.editable_name = Some(editable_name);
+    self
+  }
+
+  /// Whether the user can change the icon. Default is yes.
+  pub fn editable_icon(mut self, editable_icon: bool) -> Self {
+    self.editable_icon = Some(editable_icon);
+    self
+  }
+}
+
+impl From<PrepareInstallOptions> for PropMap {
+  fn from(options: PrepareInstallOptions) -> Self {
+    let mut map = PropMap::new();
+    if let Some(handle_token) = options.handle_token {
+      map.insert("handle_token".to_string(), Variant(Box::new(handle_token)));
+    }
+    if let Some(modal) = options.modal {
+      map.insert("modal".to_string(), Variant(Box::new(modal)));
+    }
+    if let Some(launcher_type) = options.launcher_type {
+      map.insert(
+        "launcher_type".to_string(),
+        Variant(Box::new(launcher_type.bits())),
+      );
+    }
+    if let Some(target) = options.target {
+      map.insert("target".to_string(), Variant(Box::new(target)));
+    }
+    if let Some(editable_name) = options.editable_name {
+      map.insert(
+        "editable_name".to_string(),
+        Variant(Box::new(editable_name)),
+      );
+    }
+    if let Some(editable_icon) = options.editable_icon {
+      map.insert(
+        "editable_icon".to_string(),
+        Variant(Box::new(editable_icon)),
+      );
+    }
+    map
+  }
+}
+
+/// Optional arguments for the `DynamicLauncher::launch()` method.
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LaunchOptions {
+  activation_token: Option<ActivationToken>,
+}
+
+impl LaunchOptions {
+  /// Creates a new `LaunchOptions` struct with no arguments set.
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  /// A token that can be used to activate the launched application.
+  pub fn activation_token(mut self, activation_token: impl Into<ActivationToken>) -> Self {
+    self.activation_token = Some(activation_token.into());
+    self
+  }
+}
+
+impl VersionedOptions for LaunchOptions {
+  fn versioned_options(&self) -> Vec<(&'static str, u32, bool)> {
+    vec![("activation_token", 1, self.activation_token.is_some())]
+  }
+
+  fn unset_option(&mut self, option: &str) {
+    match option {
+      "activation_token" => self.activation_token = None,
+      _ => unreachable!("`{}` isn't validated", option),
+    }
+  }
+}
+
+impl From<LaunchOptions> for PropMap {
+  fn from(options: LaunchOptions) -> Self {
+    let mut map = PropMap::new();
+    insert_activation_token(&mut map, options.activation_token);
+    map
+  }
+}
+
+/// The results of a successful `DynamicLauncher::prepare_install()` request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PrepareInstallResult {
+  /// The name chosen by the user.
+  pub name: String,
+  /// The token to pass to `DynamicLauncher::install()`.
+  pub token: String,
+}
+
+/// Reads the `PrepareInstallResult` from the results of a successful `Response`.
+pub fn parse_prepare_install(results: &PropMap) -> Result<PrepareInstallResult, PortalError> {
+  Ok(PrepareInstallResult {
+    name: get_string(results, "name")?,
+    token: get_string(results, "token")?,
+  })
+}
+
+fn get_string(results: &PropMap, key: &str) -> Result<String, PortalError> {
+  Results::new(results)
+    .required(key, Results::get_str)
+    .map(ToString::to_string)
+}
+
+/// The icon of an installed launcher, as returned by `DynamicLauncher::get_icon()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LauncherIcon {
+  /// The icon, an `Icon::Bytes`.
+  pub icon: Icon,
+  /// The image format, one of `png`, `jpeg` or `svg`.
+  pub format: String,
+  /// The width and height of the icon in pixels.
+  pub size: u32,
+}
+
+impl<'a, T: blocking::BlockingSender, C: std::ops::Deref<Target = T>> DynamicLauncher
+  for blocking::Proxy<'a, C>
+{
+  fn prepare_install(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    name: &str,
+    icon: Icon,
+    options: PrepareInstallOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    let parent_window: ParentWindowArg = parent_window.into();
+    let options = PropMap::from(options);
+    let _span = CallSpan::enter(
+      INTERFACE,
+      "PrepareInstall",
+      parent_window.as_str(),
+      trace::handle_token(&options),
+    );
+    self
+      .method_call(
+        INTERFACE,
+        "PrepareInstall",
+        (
+          parent_window.as_str(),
+          name,
+          Variant(icon.into_arg()),
+          options,
+        ),
+      )
+      .map(|r: (Path<'static>,)| trace::request_created(r.0))
+      .map_err(Into::into)
+  }
+
+  fn request_install_token(&self, name: &str, icon: Icon) -> Result<String, PortalError> {
+    self
+      .method_call(
+        INTERFACE,
+        "RequestInstallToken",
+        (name, Variant(icon.into_arg()), PropMap::new()),
+      )
+      .map(|r: (String,)| r.0)
+      .map_err(Into::into)
+  }
+
+  fn install(
+    &self,
+    token: &str,
+    desktop_file_id: &str,
+    desktop_entry: &str,
+    options: PropMap,
+  ) -> Result<(), PortalError> {
+    self
+      .method_call(
+        INTERFACE,
+        "Install",
+        (token, desktop_file_id, desktop_entry, options),
+      )
+      .map_err(Into::into)
+  }
+
+  fn uninstall(&self, desktop_file_id: &str, options: PropMap) -> Result<(), PortalError> {
+    self
+      .method_call(INTERFACE, "Uninstall", (desktop_file_id, options))
+      .map_err(Into::into)
+  }
+
+  fn get_desktop_entry(&self, desktop_file_id: &str) -> Result<String, PortalError> {
+    self
+      .method_call(INTERFACE, "GetDesktopEntry", (desktop_file_id,))
+      .map(|r: (String,)| r.0)
+      .map_err(Into::into)
+  }
+
+  fn get_icon(&self, desktop_file_id: &str) -> Result<LauncherIcon, PortalError> {
+    let (icon, format, size): (BytesIconArg, String, u32) =
+      self.method_call(INTERFACE, "GetIcon", (desktop_file_id,))?;
+    let (kind, Variant(bytes)) = icon.0;
+    if kind != "bytes" {
+      return Err(PortalError::UnexpectedResponse(format!(
+        "unexpected icon kind `{}`",
+        kind
+      )));
+    }
+    Ok(LauncherIcon {
+      icon: Icon::Bytes(bytes),
+      format,
+      size,
+    })
+  }
+
+  fn launch(&self, desktop_file_id: &str, options: LaunchOptions) -> Result<(), PortalError> {
+    self
+      .method_call(
+        INTERFACE,
+        "Launch",
+        (desktop_file_id, PropMap::from(options)),
+      )
+      .map_err(Into::into)
+  }
+
+  fn supported_launcher_types(&self) -> Result<LauncherType, PortalError> {
+    <Self as org_freedesktop_dbus::Properties>::get(self, INTERFACE, "SupportedLauncherTypes")
+      .map(LauncherType::from_bits)
+      .map_err(Into::into)
+  }
+
+  fn version(&self) -> Result<u32, PortalError> {
+    <Self as org_freedesktop_dbus::Properties>::get(self, INTERFACE, "version").map_err(Into::into)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::{parse_prepare_install, LauncherType, PrepareInstallOptions};
+  use dbus::arg::{prop_cast, PropMap, Variant};
+
+  #[test]
+  fn prepare_install_options() {
+    let map = PropMap::from(
+      PrepareInstallOptions::new()
+        .launcher_type(LauncherType::WEB_APPLICATION)
+        .target("https://example.com".to_string()),
+    );
+    assert_eq!(prop_cast::<u32>(&map, "launcher_type"), Some(&2));
+    assert_eq!(
+      prop_cast::<String>(&map, "target").map(String::as_str),
+      Some("https://example.com")
+    );
+    assert!((LauncherType::APPLICATION | LauncherType::WEB_APPLICATION)
+      .contains(LauncherType::WEB_APPLICATION));
+  }
+
+  #[test]
+  fn parse_prepare_install_results() {
+    let mut results = PropMap::new();
+    results.insert("name".to_string(), Variant(Box::new("Example".to_string())));
+    results.insert("token".to_string(), Variant(Box::new("token".to_string())));
+    let result = parse_prepare_install(&results).unwrap();
+    assert_eq!(result.name, "Example");
+    assert_eq!(result.token, "token");
+
+    results.remove("token");
+    assert!(parse_prepare_install(&results).is_err());
+  }
+  #[test]
+  fn launch_options_activation_token() {
+    use super::LaunchOptions;
+    use crate::VersionedOptions;
+
+    let options = LaunchOptions::new().activation_token("token");
+    assert!(options.validate_against(1).is_ok());
+    let map = PropMap::from(options.supported_by(1));
+    assert_eq!(
+      prop_cast::<String>(&map, "activation_token").map(String::as_str),
+      Some("token")
+    );
+  }
+}
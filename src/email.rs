@@ -0,0 +1,265 @@
+// Copyright 2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::{
+  insert_activation_token,
+  trace::{self, CallSpan},
+  ActivationToken, IntoPortalFd, ParentWindowArg, PortalError, VersionedOptions,
+};
+
+use dbus::{
+  arg::{OwnedFd, PropMap, Variant},
+  blocking::{self, stdintf::org_freedesktop_dbus},
+  Path,
+};
+use std::{fmt, os::unix::io::AsRawFd};
+
+const INTERFACE: &str = "org.freedesktop.portal.Email";
+
+/// Implementation of the `org.freedesktop.portal.Email` Portal API.
+/// See also https://flatpak.github.io/xdg-desktop-portal/#gdbus-org.freedesktop.portal.Email
+pub trait Email {
+  /// Presents a window that lets the user compose an email.
+  ///
+  /// - `parent_window`: Identifier for the application window, see crate comments for common conventions.
+  fn compose_email(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    options: EmailOptions,
+  ) -> Result<Path<'static>, PortalError>;
+
+  /// Reads the "version" property for this D-Bus interface.
+  fn version(&self) -> Result<u32, PortalError>;
+}
+
+/// Optional arguments for the `Email::compose_email()` method.
+#[derive(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EmailOptions {
+  handle_token: Option<String>,
+  address: Option<String>,
+  addresses: Option<Vec<String>>,
+  cc: Option<Vec<String>>,
+  bcc: Option<Vec<String>>,
+  subject: Option<String>,
+  body: Option<String>,
+  #[cfg_attr(feature = "serde", serde(skip))]
+  attachment_fds: Option<Vec<OwnedFd>>,
+  activation_token: Option<ActivationToken>,
+}
+
+// Written out as `OwnedFd` has no `Debug` impl in all dbus versions, the raw fd numbers are printed instead.
+impl fmt::Debug for EmailOptions {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("EmailOptions")
+      .field("handle_token", &self.handle_token)
+      .field("address", &self.address)
+      .field("addresses", &self.addresses)
+      .field("cc", &self.cc)
+      .field("bcc", &self.bcc)
+      .field("subject", &self.subject)
+      .field("body", &self.body)
+      .field(
+        "attachment_fds",
+        &self
+          .attachment_fds
+          .as_ref()
+          .map(|fds| fds.iter().map(AsRawFd::as_raw_fd).collect::<Vec<_>>()),
+      )
+      .field("activation_token", &self.activation_token)
+      .finish()
+  }
+}
+
+impl EmailOptions {
+  /// Creates a new `EmailOptions` struct with no arguments set.
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  /// A string that will be used as the last element of the @handle. Must be a valid
+  /// object path element. See the #org.freedesktop.portal.Request documentation for
+  /// more information about the @handle.
+  pub fn handle_token(mut self, handle_token: impl Into<String>) -> Self {
+    self.handle_token = Some(handle_token.into());
+    self
+  }
+
+  /// The email address to send to.
+  pub fn address(mut self, address: String) -> Self {
+    self.address = Some(address);
+    self
+  }
+
+  /// Email addresses to send to, in addition to `address`.
+  ///
+  /// The addresses option was introduced in version 3 of the interface.
+  pub fn addresses(mut self, addresses: Vec<String>) -> Self {
+    self.addresses = Some(addresses);
+    self
+  }
+
+  /// Email addresses to cc.
+  ///
+  /// The cc option was introduced in version 3 of the interface.
+  pub fn cc(mut self, cc: Vec<String>) -> Self {
+    self.cc = Some(cc);
+    self
+  }
+
+  /// Email addresses to bcc.
+  ///
+  /// The bcc option was introduced in version 3 of the interface.
+  pub fn bcc(mut self, bcc: Vec<String>) -> Self {
+    self.bcc = Some(bcc);
+    self
+  }
+
+  /// The subject for the email.
+  pub fn subject(mut self, subject: String) -> Self {
+    self.subject = Some(subject);
+    self
+  }
+
+  /// The body for the email.
+  pub fn body(mut self, body: String) -> Self {
+    self.body = Some(body);
+    self
+  }
+
+  /// File descriptors for files to attach.
+  pub fn attachment_fds(
+    mut self,
+    attachment_fds: impl IntoIterator<Item = impl IntoPortalFd>,
+  ) -> Self {
+    self.attachment_fds = Some(
+      attachment_fds
+        .into_iter()
+        .map(IntoPortalFd::into_portal_fd)
+        .collect(),
+    );
+    self
+  }
+
+  /// A token that can be used to activate the chosen application.
+  ///
+  /// The activation_token option was introduced in version 4 of the interface.
+  pub fn activation_token(mut self, activation_token: impl Into<ActivationToken>) -> Self {
+    self.activation_token = Some(activation_token.into());
+    self
+  }
+}
+
+impl VersionedOptions for EmailOptions {
+  fn versioned_options(&self) -> Vec<(&'static str, u32, bool)> {
+    vec![
+      ("addresses", 3, self.addresses.is_some()),
+      ("cc", 3, self.cc.is_some()),
+      ("bcc", 3, self.bcc.is_some()),
+      ("activation_token", 4, self.activation_token.is_some()),
+    ]
+  }
+
+  fn unset_option(&mut self, option: &str) {
+    match option {
+      "addresses" => self.addresses = None,
+      "cc" => self.cc = None,
+      "bcc" => self.bcc = None,
+      "activation_token" => self.activation_token = None,
+      _ => unreachable!("`{}` isn't validated", option),
+    }
+  }
+}
+
+impl From<EmailOptions> for PropMap {
+  fn from(options: EmailOptions) -> Self {
+    let mut map = PropMap::new();
+    if let Some(handle_token) = options.handle_token {
+      map.insert("handle_token".to_string(), Variant(Box::new(handle_token)));
+    }
+    if let Some(address) = options.address {
+      map.insert("address".to_string(), Variant(Box::new(address)));
+    }
+    if let Some(addresses) = options.addresses {
+      map.insert("addresses".to_string(), Variant(Box::new(addresses)));
+    }
+    if let Some(cc) = options.cc {
+      map.insert("cc".to_string(), Variant(Box::new(cc)));
+    }
+    if let Some(bcc) = options.bcc {
+      map.insert("bcc".to_string(), Variant(Box::new(bcc)));
+    }
+    if let Some(subject) = options.subject {
+      map.insert("subject".to_string(), Variant(Box::new(subject)));
+    }
+    if let Some(body) = options.body {
+      map.insert("body".to_string(), Variant(Box::new(body)));
+    }
+    if let Some(attachment_fds) = options.attachment_fds {
+      map.insert(
+        "attachment_fds".to_string(),
+        Variant(Box::new(attachment_fds)),
+      );
+    }
+    insert_activation_token(&mut map, options.activation_token);
+    map
+  }
+}
+
+/// The features the running `Email` portal supports, from a single `Email::version()` call,
+/// see `Portals::email_capabilities()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EmailCapabilities {
+  version: u32,
+}
+
+impl EmailCapabilities {
+  /// The capabilities of the `version` of the interface the portal implements.
+  pub fn from_version(version: u32) -> Self {
+    Self { version }
+  }
+
+  /// The version of the interface the portal implements.
+  pub fn version(&self) -> u32 {
+    self.version
+  }
+
+  /// Whether the `addresses`, `cc` and `bcc` options are supported.
+  pub fn supports_addresses(&self) -> bool {
+    self.version >= 3
+  }
+
+  /// Whether the `activation_token` option is supported.
+  pub fn supports_activation_token(&self) -> bool {
+    self.version >= 4
+  }
+}
+
+impl<'a, T: blocking::BlockingSender, C: std::ops::Deref<Target = T>> Email
+  for blocking::Proxy<'a, C>
+{
+  fn compose_email(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    options: EmailOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    let parent_window: ParentWindowArg = parent_window.into();
+    let options = PropMap::from(options);
+    let _span = CallSpan::enter(
+      INTERFACE,
+      "ComposeEmail",
+      parent_window.as_str(),
+      trace::handle_token(&options),
+    );
+    self
+      .method_call(INTERFACE, "ComposeEmail", (parent_window.as_str(), options))
+      .map(|r: (Path<'static>,)| trace::request_created(r.0))
+      .map_err(Into::into)
+  }
+
+  fn version(&self) -> Result<u32, PortalError> {
+    <Self as org_freedesktop_dbus::Properties>::get(self, INTERFACE, "version").map_err(Into::into)
+  }
+}
@@ -0,0 +1,185 @@
+// Copyright 2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::ResponseCode;
+
+/// All errors that can happen while calling a portal or reading its results.
+#[derive(Debug, thiserror::Error)]
+pub enum PortalError {
+  /// A generic D-Bus error that occurs while sending protocol messages.
+  ///
+  /// D-Bus errors with a more specific meaning are converted to the matching variant instead,
+  /// such as `PortalError::NotAllowed`.
+  #[cfg(feature = "dbus")]
+  #[error("Portal D-Bus error: {0}")]
+  Dbus(dbus::Error),
+
+  /// A generic D-Bus error that occurs while sending protocol messages with `zbus`.
+  #[cfg(feature = "zbus")]
+  #[error("Portal D-Bus error: {0}")]
+  Zbus(#[from] zbus::Error),
+
+  /// The portal service isn't running and couldn't be started, for example on a system without
+  /// a desktop portal, from `org.freedesktop.DBus.Error.ServiceUnknown`, `NameHasNoOwner` or a `Spawn` error.
+  #[error("Portal service unavailable: {0}")]
+  Unavailable(String),
+
+  /// An I/O error, for example while reading data the portal wrote to a file descriptor.
+  #[error("Portal I/O error: {0}")]
+  Io(#[from] std::io::Error),
+
+  /// The user cancelled the interaction.
+  #[error("Portal request was cancelled")]
+  Cancelled,
+
+  /// The application isn't allowed to use the portal, from `org.freedesktop.portal.Error.NotAllowed`.
+  #[error("Portal request not allowed: {0}")]
+  NotAllowed(String),
+
+  /// An argument or option was rejected by the portal, from `org.freedesktop.portal.Error.InvalidArgument`
+  /// or `org.freedesktop.DBus.Error.InvalidArgs`.
+  #[error("Invalid portal argument: {0}")]
+  InvalidArgument(String),
+
+  /// The parent window was destroyed, from `org.freedesktop.portal.Error.WindowDestroyed`.
+  #[error("Portal parent window was destroyed")]
+  WindowDestroyed,
+
+  /// The user interaction ended without success, in some other way than being cancelled.
+  #[error("Portal request ended with response {0:?}")]
+  Response(ResponseCode),
+
+  /// No response was received from the portal within the given timeout,
+  /// also when a method call itself timed out.
+  #[error("Timed out waiting for a portal response")]
+  Timeout,
+
+  /// The results returned by the portal are missing a value, or have an unexpected shape.
+  #[error("Unexpected portal response: {0}")]
+  UnexpectedResponse(String),
+
+  /// A value returned by the portal did not have the expected D-Bus type.
+  #[error("Unexpected type for {key}: expected signature {expected}, found {found}")]
+  TypeMismatch {
+    /// The key or argument the value was read from.
+    key: String,
+    /// The expected D-Bus signature.
+    expected: &'static str,
+    /// The D-Bus signature of the value that was received.
+    found: String,
+  },
+
+  /// An option was set that the portal doesn't support, as it implements an older version of the interface.
+  #[error("The {option} option requires version {required} of the interface, the portal implements version {version}")]
+  UnsupportedOption {
+    /// The name of the option.
+    option: &'static str,
+    /// The version of the interface that introduced the option.
+    required: u32,
+    /// The version of the interface the portal implements.
+    version: u32,
+  },
+
+  /// A feature was asked for that the portal doesn't support, as it implements an older version of the interface.
+  /// The portal wouldn't reject the request, but silently do something else instead.
+  #[error("{feature} requires version {required} of the interface, the portal implements version {version}")]
+  Unsupported {
+    /// The name of the feature.
+    feature: &'static str,
+    /// The version of the interface that introduced the feature.
+    required: u32,
+    /// The version of the interface the portal implements.
+    version: u32,
+  },
+}
+
+#[cfg(feature = "dbus")]
+impl From<dbus::Error> for PortalError {
+  fn from(err: dbus::Error) -> Self {
+    let message = || err.message().unwrap_or_default().to_string();
+    match err.name() {
+      Some("org.freedesktop.portal.Error.NotAllowed") => Self::NotAllowed(message()),
+      Some(
+        "org.freedesktop.portal.Error.InvalidArgument" | "org.freedesktop.DBus.Error.InvalidArgs",
+      ) => Self::InvalidArgument(message()),
+      Some("org.freedesktop.portal.Error.Cancelled") => Self::Cancelled,
+      Some("org.freedesktop.portal.Error.WindowDestroyed") => Self::WindowDestroyed,
+      Some(
+        "org.freedesktop.DBus.Error.NoReply"
+        | "org.freedesktop.DBus.Error.Timeout"
+        | "org.freedesktop.DBus.Error.TimedOut",
+      ) => Self::Timeout,
+      Some(
+        "org.freedesktop.DBus.Error.ServiceUnknown" | "org.freedesktop.DBus.Error.NameHasNoOwner",
+      ) => Self::Unavailable(message()),
+      Some(name) if name.starts_with("org.freedesktop.DBus.Error.Spawn.") => {
+        Self::Unavailable(message())
+      }
+      _ => Self::Dbus(err),
+    }
+  }
+}
+
+/// Checks a list of `(option, required version, is set)` against the `version` the portal implements.
+pub(crate) fn validate_options(
+  version: u32,
+  options: &[(&'static str, u32, bool)],
+) -> Result<(), PortalError> {
+  match options
+    .iter()
+    .find(|(_, required, set)| *set && *required > version)
+  {
+    Some(&(option, required, _)) => Err(PortalError::UnsupportedOption {
+      option,
+      required,
+      version,
+    }),
+    None => Ok(()),
+  }
+}
+
+#[cfg(all(test, feature = "dbus"))]
+mod test {
+  use super::PortalError;
+
+  fn from_dbus(name: &str) -> PortalError {
+    dbus::Error::new_custom(name, "message").into()
+  }
+
+  #[test]
+  fn portal_error_from_dbus_error() {
+    assert!(matches!(
+      from_dbus("org.freedesktop.portal.Error.NotAllowed"),
+      PortalError::NotAllowed(message) if message == "message"
+    ));
+    assert!(matches!(
+      from_dbus("org.freedesktop.DBus.Error.InvalidArgs"),
+      PortalError::InvalidArgument(_)
+    ));
+    assert!(matches!(
+      from_dbus("org.freedesktop.portal.Error.Cancelled"),
+      PortalError::Cancelled
+    ));
+    assert!(matches!(
+      from_dbus("org.freedesktop.portal.Error.WindowDestroyed"),
+      PortalError::WindowDestroyed
+    ));
+    assert!(matches!(
+      from_dbus("org.freedesktop.DBus.Error.NoReply"),
+      PortalError::Timeout
+    ));
+    assert!(matches!(
+      from_dbus("org.freedesktop.DBus.Error.ServiceUnknown"),
+      PortalError::Unavailable(_)
+    ));
+    assert!(matches!(
+      from_dbus("org.freedesktop.DBus.Error.Spawn.ChildExited"),
+      PortalError::Unavailable(_)
+    ));
+    assert!(matches!(
+      from_dbus("org.freedesktop.DBus.Error.Failed"),
+      PortalError::Dbus(_)
+    ));
+  }
+}
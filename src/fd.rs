@@ -0,0 +1,144 @@
+// Copyright 2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Creation of the file descriptors passed to portals.
+//!
+//! Every descriptor is wrapped in an `OwnedFd` as soon as it's created, so it's closed on every early return,
+//! and is opened with `O_CLOEXEC`, so it doesn't leak into child processes.
+
+use crate::PortalError;
+
+use dbus::arg::OwnedFd;
+use std::{
+  ffi::CString,
+  fs::File,
+  io,
+  os::unix::{
+    ffi::OsStrExt,
+    io::{FromRawFd, IntoRawFd, RawFd},
+  },
+  path::Path,
+};
+
+/// A file descriptor that can be passed to a portal, such as an open `File`.
+///
+/// Methods taking one close the descriptor on our side once the call is made, the portal receives a duplicate.
+pub trait IntoPortalFd {
+  /// Takes ownership of the descriptor.
+  fn into_portal_fd(self) -> OwnedFd;
+}
+
+impl IntoPortalFd for OwnedFd {
+  fn into_portal_fd(self) -> OwnedFd {
+    self
+  }
+}
+
+impl IntoPortalFd for File {
+  fn into_portal_fd(self) -> OwnedFd {
+    from_file(self)
+  }
+}
+
+impl IntoPortalFd for std::os::fd::OwnedFd {
+  fn into_portal_fd(self) -> OwnedFd {
+    unsafe { OwnedFd::new(self.into_raw_fd()) }
+  }
+}
+
+/// The descriptor must be open and owned by the caller, it is closed once passed to the portal.
+impl IntoPortalFd for RawFd {
+  fn into_portal_fd(self) -> OwnedFd {
+    unsafe { OwnedFd::new(self) }
+  }
+}
+
+/// Creates a pipe, returning the read and write ends.
+pub(crate) fn pipe() -> Result<(OwnedFd, OwnedFd), PortalError> {
+  let mut fds = [0; 2];
+  if unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) } != 0 {
+    return Err(io::Error::last_os_error().into());
+  }
+  Ok(unsafe { (OwnedFd::new(fds[0]), OwnedFd::new(fds[1])) })
+}
+
+/// Opens `path` with `flags`, such as `libc::O_RDONLY` or `libc::O_PATH`. `O_CLOEXEC` is always added.
+pub(crate) fn open_path(path: &Path, flags: libc::c_int) -> Result<OwnedFd, PortalError> {
+  let path = CString::new(path.as_os_str().as_bytes())
+    .map_err(|_| PortalError::InvalidArgument("path contains a nul byte".to_string()))?;
+  let fd = unsafe { libc::open(path.as_ptr(), flags | libc::O_CLOEXEC) };
+  if fd < 0 {
+    return Err(io::Error::last_os_error().into());
+  }
+  Ok(unsafe { OwnedFd::new(fd) })
+}
+
+/// Takes ownership of the descriptor of an open `file`.
+pub(crate) fn from_file(file: File) -> OwnedFd {
+  unsafe { OwnedFd::new(file.into_raw_fd()) }
+}
+
+/// Wraps an owned descriptor in a `File`, to read or write it.
+pub(crate) fn into_file(fd: OwnedFd) -> File {
+  unsafe { File::from_raw_fd(fd.into_fd()) }
+}
+
+/// The descriptors of this process that are open on the file at `path`.
+#[cfg(test)]
+pub(crate) fn fds_open_on(path: &Path) -> Vec<RawFd> {
+  std::fs::read_dir("/proc/self/fd")
+    .unwrap()
+    .filter_map(|entry| {
+      let entry = entry.ok()?;
+      let fd = entry.file_name().to_str()?.parse().ok()?;
+      (std::fs::read_link(entry.path()).ok()? == path).then_some(fd)
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod test {
+  use super::{into_file, open_path, pipe, IntoPortalFd};
+  use std::{
+    io::{Read, Write},
+    os::unix::io::AsRawFd,
+    path::Path,
+  };
+
+  #[test]
+  fn open_path_flags() {
+    let fd = open_path(Path::new(env!("CARGO_MANIFEST_DIR")), libc::O_RDONLY).unwrap();
+    let flags = unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_GETFD) };
+    assert_ne!(flags & libc::FD_CLOEXEC, 0);
+    assert!(open_path(Path::new("/nonexistent/file"), libc::O_RDONLY).is_err());
+    assert!(open_path(Path::new("/tmp/a\0b"), libc::O_RDONLY).is_err());
+  }
+
+  #[test]
+  fn pipe_round_trip() {
+    let (reader, writer) = pipe().unwrap();
+    into_file(writer).write_all(b"secret").unwrap();
+    let mut read = Vec::new();
+    into_file(reader).read_to_end(&mut read).unwrap();
+    assert_eq!(read, b"secret");
+  }
+
+  #[test]
+  fn into_portal_fd_keeps_descriptor() {
+    use std::{fs::File, os::unix::io::IntoRawFd};
+
+    let file = File::open(env!("CARGO_MANIFEST_DIR")).unwrap();
+    let raw = file.as_raw_fd();
+    assert_eq!(file.into_portal_fd().as_raw_fd(), raw);
+
+    let fd = std::os::fd::OwnedFd::from(File::open(env!("CARGO_MANIFEST_DIR")).unwrap());
+    let raw = fd.as_raw_fd();
+    assert_eq!(fd.into_portal_fd().as_raw_fd(), raw);
+
+    let raw = File::open(env!("CARGO_MANIFEST_DIR"))
+      .unwrap()
+      .into_raw_fd();
+    assert_eq!(raw.into_portal_fd().as_raw_fd(), raw);
+  }
+}
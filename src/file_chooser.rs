@@ -0,0 +1,898 @@
+// Copyright 2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::{
+  insert_activation_token,
+  trace::{self, CallSpan},
+  ActivationToken, ParentWindowArg, PortalError, Results, VersionedOptions,
+};
+
+use dbus::{
+  arg::{PropMap, RefArg, Variant},
+  blocking::{self, stdintf::org_freedesktop_dbus},
+  Path,
+};
+use std::os::unix::ffi::OsStrExt;
+
+const INTERFACE: &str = "org.freedesktop.portal.FileChooser";
+
+/// Implementation of the `org.freedesktop.portal.FileChooser` Portal API.
+/// See also https://flatpak.github.io/xdg-desktop-portal/#gdbus-org.freedesktop.portal.FileChooser
+///
+/// The chosen files are returned as a list of `uris` in the results of the `Response` signal,
+/// along with the `choices` and `current_filter` the user ended on. Use `parse_file_chooser_result()` to read them.
+pub trait FileChooser {
+  /// Asks to open one or more files.
+  ///
+  /// - `parent_window`: Identifier for the application window, see crate comments for common conventions.
+  /// - `title`: Title for the file chooser dialog.
+  fn open_file(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    title: &str,
+    options: OpenFileOptions,
+  ) -> Result<Path<'static>, PortalError>;
+
+  /// Asks for a location to save a file.
+  ///
+  /// - `parent_window`: Identifier for the application window, see crate comments for common conventions.
+  /// - `title`: Title for the file chooser dialog.
+  fn save_file(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    title: &str,
+    options: SaveFileOptions,
+  ) -> Result<Path<'static>, PortalError>;
+
+  /// Asks for a folder as a location to save one or more files.
+  ///
+  /// - `parent_window`: Identifier for the application window, see crate comments for common conventions.
+  /// - `title`: Title for the file chooser dialog.
+  fn save_files(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    title: &str,
+    options: SaveFilesOptions,
+  ) -> Result<Path<'static>, PortalError>;
+
+  /// Reads the "version" property for this D-Bus interface.
+  fn version(&self) -> Result<u32, PortalError>;
+}
+
+/// A named filter of glob patterns and MIME types, shown to the user as a choice in the dialog.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FileFilter {
+  name: String,
+  rules: Vec<(u32, String)>,
+}
+
+impl FileFilter {
+  /// Creates a new `FileFilter` without any rules.
+  ///
+  /// - `name`: User-visible name of the filter.
+  pub fn new(name: String) -> Self {
+    Self {
+      name,
+      rules: Vec::new(),
+    }
+  }
+
+  /// Adds a glob pattern like `*.ico` to the filter.
+  pub fn glob(mut self, pattern: &str) -> Self {
+    self.rules.push((0, pattern.to_string()));
+    self
+  }
+
+  /// Adds a MIME type like `image/png` to the filter.
+  pub fn mime_type(mut self, mime_type: &str) -> Self {
+    self.rules.push((1, mime_type.to_string()));
+    self
+  }
+
+  /// User-visible name of the filter.
+  pub fn name(&self) -> &str {
+    &self.name
+  }
+
+  /// The rules of the filter, as a kind (`0` for a glob pattern, `1` for a MIME type) and the pattern or MIME type.
+  pub fn rules(&self) -> &[(u32, String)] {
+    &self.rules
+  }
+
+  /// The wire format of a filter, `(sa(us))`.
+  fn into_arg(self) -> (String, Vec<(u32, String)>) {
+    (self.name, self.rules)
+  }
+
+  /// Reads a filter from its wire format, `(sa(us))`.
+  fn from_arg(arg: &dyn RefArg) -> Option<Self> {
+    let mut fields = arg.as_iter()?;
+    let name = fields.next()?.as_str()?.to_string();
+    let rules = fields
+      .next()?
+      .as_iter()?
+      .map(|rule| {
+        let mut fields = rule.as_iter()?;
+        let kind = fields.next()?.as_u64()? as u32;
+        let rule = fields.next()?.as_str()?.to_string();
+        Some((kind, rule))
+      })
+      .collect::<Option<_>>()?;
+    Some(Self { name, rules })
+  }
+}
+
+/// An extra widget shown in the dialog, such as a combo box.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Choice {
+  id: String,
+  label: String,
+  options: Vec<(String, String)>,
+  initial: String,
+}
+
+impl Choice {
+  /// Creates a new `Choice`.
+  ///
+  /// - `id`: Identifier of the choice, used for the selection in the `choices` result.
+  /// - `label`: User-visible label of the choice.
+  /// - `options`: Tuples of an identifier and a user-visible label for each option.
+  ///   Leave this empty to show a boolean choice.
+  /// - `initial`: Identifier of the initially selected option, or `"true"`/`"false"` for a boolean choice.
+  pub fn new(id: String, label: String, options: Vec<(String, String)>, initial: String) -> Self {
+    Self {
+      id,
+      label,
+      options,
+      initial,
+    }
+  }
+
+  /// Creates a combo box, to pick one of `options`.
+  ///
+  /// - `id`: Identifier of the choice, used for the selection in the `choices` result.
+  /// - `label`: User-visible label of the choice.
+  /// - `options`: Tuples of an identifier and a user-visible label for each option.
+  /// - `initial`: Identifier of the initially selected option.
+  pub fn combo(id: String, label: String, options: Vec<(String, String)>, initial: String) -> Self {
+    Self::new(id, label, options, initial)
+  }
+
+  /// Creates a checkbox. Its selection in the `choices` result is `"true"` or `"false"`.
+  ///
+  /// - `id`: Identifier of the choice, used for the selection in the `choices` result.
+  /// - `label`: User-visible label of the choice.
+  /// - `initial`: Whether the checkbox is initially checked.
+  pub fn checkbox(id: String, label: String, initial: bool) -> Self {
+    Self::new(id, label, Vec::new(), initial.to_string())
+  }
+
+  /// The wire format of a choice, `(ssa(ss)s)`.
+  fn into_arg(self) -> (String, String, Vec<(String, String)>, String) {
+    (self.id, self.label, self.options, self.initial)
+  }
+}
+
+/// The nul-terminated byte string of a path, as the portal expects it.
+fn to_bytestring(path: &std::path::Path) -> Vec<u8> {
+  let mut bytes = path.as_os_str().as_bytes().to_vec();
+  bytes.push(0);
+  bytes
+}
+
+fn insert_filters(map: &mut PropMap, filters: Option<Vec<FileFilter>>) {
+  if let Some(filters) = filters {
+    let filters: Vec<_> = filters.into_iter().map(FileFilter::into_arg).collect();
+    map.insert("filters".to_string(), Variant(Box::new(filters)));
+  }
+}
+
+fn insert_current_filter(map: &mut PropMap, current_filter: Option<FileFilter>) {
+  if let Some(current_filter) = current_filter {
+    map.insert(
+      "current_filter".to_string(),
+      Variant(Box::new(current_filter.into_arg())),
+    );
+  }
+}
+
+fn insert_choices(map: &mut PropMap, choices: Option<Vec<Choice>>) {
+  if let Some(choices) = choices {
+    let choices: Vec<_> = choices.into_iter().map(Choice::into_arg).collect();
+    map.insert("choices".to_string(), Variant(Box::new(choices)));
+  }
+}
+
+/// The files chosen with one of the `FileChooser` methods.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FileChooserResult {
+  /// The uris of the chosen files.
+  pub uris: Vec<String>,
+  /// The identifier of each choice, with the identifier of the option the user selected for it.
+  pub choices: Vec<(String, String)>,
+  /// The filter that was selected when the user accepted, if the portal reports it.
+  pub current_filter: Option<FileFilter>,
+}
+
+/// Reads the `FileChooserResult` from the results of a successful `Response`.
+pub fn parse_file_chooser_result(results: &PropMap) -> Result<FileChooserResult, PortalError> {
+  let uris = Results::new(results).required("uris", Results::get_str_array)?;
+  let choices = match results.get("choices") {
+    Some(choices) => choices
+      .0
+      .as_iter()
+      .and_then(|choices| {
+        choices
+          .map(|choice| {
+            let mut fields = choice.as_iter()?;
+            let id = fields.next()?.as_str()?.to_string();
+            let option = fields.next()?.as_str()?.to_string();
+            Some((id, option))
+          })
+          .collect()
+      })
+      .ok_or_else(|| PortalError::TypeMismatch {
+        key: "choices".to_string(),
+        expected: "a(ss)",
+        found: choices.0.signature().to_string(),
+      })?,
+    None => Vec::new(),
+  };
+  let current_filter = match results.get("current_filter") {
+    Some(current_filter) => {
+      Some(
+        FileFilter::from_arg(&*current_filter.0).ok_or_else(|| PortalError::TypeMismatch {
+          key: "current_filter".to_string(),
+          expected: "(sa(us))",
+          found: current_filter.0.signature().to_string(),
+        })?,
+      )
+    }
+    None => None,
+  };
+  Ok(FileChooserResult {
+    uris,
+    choices,
+    current_filter,
+  })
+}
+
+/// Optional arguments for the `FileChooser::open_file()` method.
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OpenFileOptions {
+  handle_token: Option<String>,
+  accept_label: Option<String>,
+  modal: Option<bool>,
+  multiple: Option<bool>,
+  directory: Option<bool>,
+  filters: Option<Vec<FileFilter>>,
+  current_filter: Option<FileFilter>,
+  choices: Option<Vec<Choice>>,
+  activation_token: Option<ActivationToken>,
+}
+
+impl OpenFileOptions {
+  /// Creates a new `OpenFileOptions` struct with no arguments set.
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  /// A string that will be used as the last element of the @handle. Must be a valid
+  /// object path element. See the #org.freedesktop.portal.Request documentation for
+  /// more information about the @handle.
+  pub fn handle_token(mut self, handle_token: impl Into<String>) -> Self {
+    self.handle_token = Some(handle_token.into());
+    self
+  }
+
+  /// Label for the accept button. Mnemonic underlines are allowed.
+  pub fn accept_label(mut self, accept_label: String) -> Self {
+    self.accept_label = Some(accept_label);
+    self
+  }
+
+  /// Whether the dialog should be modal. Default is yes.
+  pub fn modal(mut self, modal: bool) -> Self {
+    self.modal = Some(modal);
+    self
+  }
+
+  /// Whether multiple files can be selected or not. Default is single-selection.
+  pub fn multiple(mut self, multiple: bool) -> Self {
+    self.multiple = Some(multiple);
+    self
+  }
+
+  /// Whether to select for folders instead of files. Default is to select files.
+  ///
+  /// The directory option was introduced in version 3 of the interface.
+  pub fn directory(mut self, directory: bool) -> Self {
+    self.directory = Some(directory);
+    self
+  }
+
+  /// List of filters the user can choose from to limit the visible files.
+  pub fn filters(mut self, filters: Vec<FileFilter>) -> Self {
+    self.filters = Some(filters);
+    self
+  }
+
+  /// Request that this filter is set by default at dialog creation.
+  pub fn current_filter(mut self, current_filter: FileFilter) -> Self {
+    self.current_filter = Some(current_filter);
+    self
+  }
+
+  /// List of extra widgets to show in the dialog.
+  pub fn choices(mut self, choices: Vec<Choice>) -> Self {
+    self.choices = Some(choices);
+    self
+  }
+
+  /// A token that can be used to activate the file chooser dialog.
+  ///
+  /// The activation_token option was introduced in version 4 of the interface.
+  pub fn activation_token(mut self, activation_token: impl Into<ActivationToken>) -> Self {
+    self.activation_token = Some(activation_token.into());
+    self
+  }
+}
+
+impl VersionedOptions for OpenFileOptions {
+  fn versioned_options(&self) -> Vec<(&'static str, u32, bool)> {
+    vec![
+      ("directory", 3, self.directory.is_some()),
+      ("activation_token", 4, self.activation_token.is_some()),
+    ]
+  }
+
+  fn unset_option(&mut self, option: &str) {
+    match option {
+      "directory" => self.directory = None,
+      "activation_token" => self.activation_token = None,
+      _ => unreachable!("`{}` isn't validated", option),
+    }
+  }
+}
+
+impl From<OpenFileOptions> for PropMap {
+  fn from(options: OpenFileOptions) -> Self {
+    let mut map = PropMap::new();
+    if let Some(handle_token) = options.handle_token {
+      map.insert("handle_token".to_string(), Variant(Box::new(handle_token)));
+    }
+    if let Some(accept_label) = options.accept_label {
+      map.insert("accept_label".to_string(), Variant(Box::new(accept_label)));
+    }
+    if let Some(modal) = options.modal {
+      map.insert("modal".to_string(), Variant(Box::new(modal)));
+    }
+    if let Some(multiple) = options.multiple {
+      map.insert("multiple".to_string(), Variant(Box::new(multiple)));
+    }
+    if let Some(directory) = options.directory {
+      map.insert("directory".to_string(), Variant(Box::new(directory)));
+    }
+    insert_filters(&mut map, options.filters);
+    insert_current_filter(&mut map, options.current_filter);
+    insert_choices(&mut map, options.choices);
+    insert_activation_token(&mut map, options.activation_token);
+    map
+  }
+}
+
+/// Optional arguments for the `FileChooser::save_file()` method.
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SaveFileOptions {
+  handle_token: Option<String>,
+  accept_label: Option<String>,
+  modal: Option<bool>,
+  filters: Option<Vec<FileFilter>>,
+  current_filter: Option<FileFilter>,
+  choices: Option<Vec<Choice>>,
+  current_name: Option<String>,
+  current_folder: Option<Vec<u8>>,
+  current_file: Option<Vec<u8>>,
+  activation_token: Option<ActivationToken>,
+}
+
+impl SaveFileOptions {
+  /// Creates a new `SaveFileOptions` struct with no arguments set.
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  /// A string that will be used as the last element of the @handle. Must be a valid
+  /// object path element. See the #org.freedesktop.portal.Request documentation for
+  /// more information about the @handle.
+  pub fn handle_token(mut self, handle_token: impl Into<String>) -> Self {
+    self.handle_token = Some(handle_token.into());
+    self
+  }
+
+  /// Label for the accept button. Mnemonic underlines are allowed.
+  pub fn accept_label(mut self, accept_label: String) -> Self {
+    self.accept_label = Some(accept_label);
+    self
+  }
+
+  /// Whether the dialog should be modal. Default is yes.
+  pub fn modal(mut self, modal: bool) -> Self {
+    self.modal = Some(modal);
+    self
+  }
+
+  /// List of filters the user can choose from to limit the visible files.
+  pub fn filters(mut self, filters: Vec<FileFilter>) -> Self {
+    self.filters = Some(filters);
+    self
+  }
+
+  /// Request that this filter is set by default at dialog creation.
+  pub fn current_filter(mut self, current_filter: FileFilter) -> Self {
+    self.current_filter = Some(current_filter);
+    self
+  }
+
+  /// List of extra widgets to show in the dialog.
+  pub fn choices(mut self, choices: Vec<Choice>) -> Self {
+    self.choices = Some(choices);
+    self
+  }
+
+  /// Suggested filename.
+  pub fn current_name(mut self, current_name: String) -> Self {
+    self.current_name = Some(current_name);
+    self
+  }
+
+  /// Suggested folder to save the file in.
+  pub fn current_folder(mut self, current_folder: impl AsRef<std::path::Path>) -> Self {
+    self.current_folder = Some(to_bytestring(current_folder.as_ref()));
+    self
+  }
+
+  /// The current file, when saving an existing file.
+  pub fn current_file(mut self, current_file: impl AsRef<std::path::Path>) -> Self {
+    self.current_file = Some(to_bytestring(current_file.as_ref()));
+    self
+  }
+
+  /// A token that can be used to activate the file chooser dialog.
+  ///
+  /// The activation_token option was introduced in version 4 of the interface.
+  pub fn activation_token(mut self, activation_token: impl Into<ActivationToken>) -> Self {
+    self.activation_token = Some(activation_token.into());
+    self
+  }
+}
+
+impl VersionedOptions for SaveFileOptions {
+  fn versioned_options(&self) -> Vec<(&'static str, u32, bool)> {
+    vec![("activation_token", 4, self.activation_token.is_some())]
+  }
+
+  fn unset_option(&mut self, option: &str) {
+    match option {
+      "activation_token" => self.activation_token = None,
+      _ => unreachable!("`{}` isn't validated", option),
+    }
+  }
+}
+
+impl From<SaveFileOptions> for PropMap {
+  fn from(options: SaveFileOptions) -> Self {
+    let mut map = PropMap::new();
+    if let Some(handle_token) = options.handle_token {
+      map.insert("handle_token".to_string(), Variant(Box::new(handle_token)));
+    }
+    if let Some(accept_label) = options.accept_label {
+      map.insert("accept_label".to_string(), Variant(Box::new(accept_label)));
+    }
+    if let Some(modal) = options.modal {
+      map.insert("modal".to_string(), Variant(Box::new(modal)));
+    }
+    insert_filters(&mut map, options.filters);
+    insert_current_filter(&mut map, options.current_filter);
+    insert_choices(&mut map, options.choices);
+    if let Some(current_name) = options.current_name {
+      map.insert("current_name".to_string(), Variant(Box::new(current_name)));
+    }
+    if let Some(current_folder) = options.current_folder {
+      map.insert(
+        "current_folder".to_string(),
+        Variant(Box::new(current_folder)),
+      );
+    }
+    if let Some(current_file) = options.current_file {
+      map.insert("current_file".to_string(), Variant(Box::new(current_file)));
+    }
+    insert_activation_token(&mut map, options.activation_token);
+    map
+  }
+}
+
+/// Optional arguments for the `FileChooser::save_files()` method.
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SaveFilesOptions {
+  handle_token: Option<String>,
+  accept_label: Option<String>,
+  modal: Option<bool>,
+  choices: Option<Vec<Choice>>,
+  activation_token: Option<ActivationToken>,
+}
+
+impl SaveFilesOptions {
+  /// Creates a new `SaveFilesOptions` struct with no arguments set.
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  /// A string that will be used as the last element of the @handle. Must be a valid
+  /// object path element. See the #org.freedesktop.portal.Request documentation for
+  /// more information about the @handle.
+  pub fn handle_token(mut self, handle_token: impl Into<String>) -> Self {
+    self.handle_token = Some(handle_token.into());
+    self
+  }
+
+  /// Label for the accept button. Mnemonic underlines are allowed.
+  pub fn accept_label(mut self, accept_label: String) -> Self {
+    self.accept_label = Some(accept_label);
+    self
+  }
+
+  /// Whether the dialog should be modal. Default is yes.
+  pub fn modal(mut self, modal: bool) -> Self {
+    self.modal = Some(modal);
+    self
+  }
+
+  /// List of extra widgets to show in the dialog.
+  pub fn choices(mut self, choices: Vec<Choice>) -> Self {
+    self.choices = Some(choices);
+    self
+  }
+
+  /// A token that can be used to activate the file chooser dialog.
+  ///
+  /// The activation_token option was introduced in version 4 of the interface.
+  pub fn activation_token(mut self, activation_token: impl Into<ActivationToken>) -> Self {
+    self.activation_token = Some(activation_token.into());
+    self
+  }
+}
+
+impl VersionedOptions for SaveFilesOptions {
+  fn versioned_options(&self) -> Vec<(&'static str, u32, bool)> {
+    vec![("activation_token", 4, self.activation_token.is_some())]
+  }
+
+  fn unset_option(&mut self, option: &str) {
+    match option {
+      "activation_token" => self.activation_token = None,
+      _ => unreachable!("`{}` isn't validated", option),
+    }
+  }
+}
+
+impl From<SaveFilesOptions> for PropMap {
+  fn from(options: SaveFilesOptions) -> Self {
+    let mut map = PropMap::new();
+    if let Some(handle_token) = options.handle_token {
+      map.insert("handle_token".to_string(), Variant(Box::new(handle_token)));
+    }
+    if let Some(accept_label) = options.accept_label {
+      map.insert("accept_label".to_string(), Variant(Box::new(accept_label)));
+    }
+    if let Some(modal) = options.modal {
+      map.insert("modal".to_string(), Variant(Box::new(modal)));
+    }
+    insert_choices(&mut map, options.choices);
+    insert_activation_token(&mut map, options.activation_token);
+    map
+  }
+}
+
+/// The features the running `FileChooser` portal supports, from a single `FileChooser::version()` call,
+/// see `Portals::file_chooser_capabilities()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FileChooserCapabilities {
+  version: u32,
+}
+
+impl FileChooserCapabilities {
+  /// The capabilities of the `version` of the interface the portal implements.
+  pub fn from_version(version: u32) -> Self {
+    Self { version }
+  }
+
+  /// The version of the interface the portal implements.
+  pub fn version(&self) -> u32 {
+    self.version
+  }
+
+  /// Whether the `directory` option of `FileChooser::open_file()` is supported.
+  pub fn supports_directory(&self) -> bool {
+    self.version >= 3
+  }
+}
+
+impl<'a, T: blocking::BlockingSender, C: std::ops::Deref<Target = T>> FileChooser
+  for blocking::Proxy<'a, C>
+{
+  fn open_file(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    title: &str,
+    options: OpenFileOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    let parent_window: ParentWindowArg = parent_window.into();
+    let options = PropMap::from(options);
+    let _span = CallSpan::enter(
+      INTERFACE,
+      "OpenFile",
+      parent_window.as_str(),
+      trace::handle_token(&options),
+    );
+    self
+      .method_call(
+        INTERFACE,
+        "OpenFile",
+        (parent_window.as_str(), title, options),
+      )
+      .map(|r: (Path<'static>,)| trace::request_created(r.0))
+      .map_err(Into::into)
+  }
+
+  fn save_file(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    title: &str,
+    options: SaveFileOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    let parent_window: ParentWindowArg = parent_window.into();
+    let options = PropMap::from(options);
+    let _span = CallSpan::enter(
+      INTERFACE,
+      "SaveFile",
+      parent_window.as_str(),
+      trace::handle_token(&options),
+    );
+    self
+      .method_call(
+        INTERFACE,
+        "SaveFile",
+        (parent_window.as_str(), title, options),
+      )
+      .map(|r: (Path<'static>,)| trace::request_created(r.0))
+      .map_err(Into::into)
+  }
+
+  fn save_files(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    title: &str,
+    options: SaveFilesOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    let parent_window: ParentWindowArg = parent_window.into();
+    let options = PropMap::from(options);
+    let _span = CallSpan::enter(
+      INTERFACE,
+      "SaveFiles",
+      parent_window.as_str(),
+      trace::handle_token(&options),
+    );
+    self
+      .method_call(
+        INTERFACE,
+        "SaveFiles",
+        (parent_window.as_str(), title, options),
+      )
+      .map(|r: (Path<'static>,)| trace::request_created(r.0))
+      .map_err(Into::into)
+  }
+
+  fn version(&self) -> Result<u32, PortalError> {
+    <Self as org_freedesktop_dbus::Properties>::get(self, INTERFACE, "version").map_err(Into::into)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::{parse_file_chooser_result, Choice, FileFilter, OpenFileOptions, SaveFileOptions};
+  use crate::PortalError;
+  use dbus::arg::{prop_cast, PropMap, Variant};
+
+  #[test]
+  fn parse_file_chooser_result_results() {
+    let mut results = PropMap::new();
+    assert!(matches!(
+      parse_file_chooser_result(&results),
+      Err(PortalError::UnexpectedResponse(_))
+    ));
+
+    results.insert(
+      "uris".to_string(),
+      Variant(Box::new(vec!["file:///tmp/a.txt".to_string()])),
+    );
+    let result = parse_file_chooser_result(&results).unwrap();
+    assert_eq!(result.uris, ["file:///tmp/a.txt"]);
+    assert!(result.choices.is_empty());
+    assert_eq!(result.current_filter, None);
+
+    results.insert(
+      "choices".to_string(),
+      Variant(Box::new(vec![("encoding".to_string(), "utf8".to_string())])),
+    );
+    let result = parse_file_chooser_result(&results).unwrap();
+    assert_eq!(
+      result.choices,
+      [("encoding".to_string(), "utf8".to_string())]
+    );
+
+    results.insert(
+      "current_filter".to_string(),
+      Variant(Box::new((
+        "Text".to_string(),
+        vec![
+          (0u32, "*.txt".to_string()),
+          (1u32, "text/plain".to_string()),
+        ],
+      ))),
+    );
+    let result = parse_file_chooser_result(&results).unwrap();
+    let current_filter = result.current_filter.unwrap();
+    assert_eq!(current_filter.name(), "Text");
+    assert_eq!(
+      current_filter.rules(),
+      [(0, "*.txt".to_string()), (1, "text/plain".to_string())]
+    );
+    assert_eq!(
+      current_filter,
+      FileFilter::new("Text".to_string())
+        .glob("*.txt")
+        .mime_type("text/plain")
+    );
+
+    results.insert(
+      "current_filter".to_string(),
+      Variant(Box::new("Text".to_string())),
+    );
+    assert!(matches!(
+      parse_file_chooser_result(&results),
+      Err(PortalError::TypeMismatch {
+        expected: "(sa(us))",
+        ..
+      })
+    ));
+    results.remove("current_filter");
+
+    results.insert(
+      "uris".to_string(),
+      Variant(Box::new("file:///tmp/a.txt".to_string())),
+    );
+    assert!(matches!(
+      parse_file_chooser_result(&results),
+      Err(PortalError::TypeMismatch { expected: "as", .. })
+    ));
+  }
+
+  #[test]
+  fn save_file_options_paths() {
+    let options = PropMap::from(
+      SaveFileOptions::new()
+        .current_name("notes.txt".to_string())
+        .current_folder("/home/user/Documents")
+        .current_file("/home/user/Documents/notes.txt"),
+    );
+    assert_eq!(
+      prop_cast::<String>(&options, "current_name").unwrap(),
+      "notes.txt"
+    );
+    assert_eq!(
+      prop_cast::<Vec<u8>>(&options, "current_folder").unwrap(),
+      b"/home/user/Documents\0"
+    );
+    assert_eq!(
+      prop_cast::<Vec<u8>>(&options, "current_file").unwrap(),
+      b"/home/user/Documents/notes.txt\0"
+    );
+  }
+
+  #[test]
+  fn activation_token_requires_version_4() {
+    use super::SaveFilesOptions;
+    use crate::VersionedOptions;
+
+    let unsupported = |result: Result<(), PortalError>| {
+      matches!(
+        result,
+        Err(PortalError::UnsupportedOption {
+          option: "activation_token",
+          required: 4,
+          version: 3,
+        })
+      )
+    };
+    let open = OpenFileOptions::new()
+      .directory(true)
+      .activation_token("token");
+    assert!(unsupported(open.validate_against(3)));
+    assert!(open.validate_against(4).is_ok());
+    let map = PropMap::from(open.supported_by(3));
+    assert_eq!(prop_cast::<bool>(&map, "directory"), Some(&true));
+    assert!(!map.contains_key("activation_token"));
+
+    let save = SaveFileOptions::new().activation_token("token");
+    assert!(unsupported(save.validate_against(3)));
+    assert!(save.validate_against(4).is_ok());
+    assert!(!PropMap::from(save.supported_by(3)).contains_key("activation_token"));
+
+    let save_files = SaveFilesOptions::new().activation_token("token");
+    assert!(unsupported(save_files.validate_against(3)));
+    assert!(save_files.validate_against(4).is_ok());
+    assert!(!PropMap::from(save_files.supported_by(3)).contains_key("activation_token"));
+  }
+
+  #[test]
+  fn file_filter_rules() {
+    let filter = FileFilter::new("Images".to_string())
+      .glob("*.ico")
+      .mime_type("image/png");
+    let options = PropMap::from(OpenFileOptions::new().filters(vec![filter]));
+    assert_eq!(
+      prop_cast::<Vec<(String, Vec<(u32, String)>)>>(&options, "filters").unwrap(),
+      &vec![(
+        "Images".to_string(),
+        vec![(0, "*.ico".to_string()), (1, "image/png".to_string())]
+      )]
+    );
+  }
+
+  #[test]
+  fn choice_shapes() {
+    let options = PropMap::from(OpenFileOptions::new().choices(vec![
+      Choice::combo(
+        "encoding".to_string(),
+        "Encoding".to_string(),
+        vec![
+          ("utf8".to_string(), "Unicode (UTF-8)".to_string()),
+          ("latin15".to_string(), "Western".to_string()),
+        ],
+        "latin15".to_string(),
+      ),
+      Choice::checkbox("reencode".to_string(), "Reencode".to_string(), false),
+    ]));
+    assert_eq!(&*options["choices"].0.signature(), "a(ssa(ss)s)");
+    assert_eq!(
+      prop_cast::<Vec<(String, String, Vec<(String, String)>, String)>>(&options, "choices")
+        .unwrap(),
+      &vec![
+        (
+          "encoding".to_string(),
+          "Encoding".to_string(),
+          vec![
+            ("utf8".to_string(), "Unicode (UTF-8)".to_string()),
+            ("latin15".to_string(), "Western".to_string()),
+          ],
+          "latin15".to_string(),
+        ),
+        (
+          "reencode".to_string(),
+          "Reencode".to_string(),
+          vec![],
+          "false".to_string(),
+        ),
+      ]
+    );
+  }
+}
@@ -0,0 +1,269 @@
+// Copyright 2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::{
+  signal_rule,
+  trace::{self, CallSpan},
+  CreateSessionOptions, ParentWindowArg, PortalError, PortalEvent, Session, SignalGuard,
+};
+
+use dbus::{
+  arg::{PropMap, Variant},
+  blocking::{self, stdintf::org_freedesktop_dbus, Connection},
+  Path,
+};
+
+const INTERFACE: &str = "org.freedesktop.portal.GlobalShortcuts";
+
+/// Implementation of the `org.freedesktop.portal.GlobalShortcuts` Portal API.
+/// See also https://flatpak.github.io/xdg-desktop-portal/#gdbus-org.freedesktop.portal.GlobalShortcuts
+///
+/// The bound `shortcuts` are returned in the results of the `Response` signal,
+/// with a `trigger_description` describing the trigger the user picked.
+pub trait GlobalShortcuts {
+  /// Creates a global shortcuts session.
+  ///
+  /// The `session_handle` is returned in the results of the `Response` signal.
+  fn create_session(&self, options: CreateSessionOptions) -> Result<Path<'static>, PortalError>;
+
+  /// Binds the shortcuts to the session, which may ask the user to confirm or change the triggers.
+  ///
+  /// - `parent_window`: Identifier for the application window, see crate comments for common conventions.
+  fn bind_shortcuts(
+    &self,
+    session: &Session,
+    shortcuts: Vec<Shortcut>,
+    parent_window: impl Into<ParentWindowArg>,
+    options: ShortcutsOptions,
+  ) -> Result<Path<'static>, PortalError>;
+
+  /// Lists the shortcuts bound to the session.
+  fn list_shortcuts(
+    &self,
+    session: &Session,
+    options: ShortcutsOptions,
+  ) -> Result<Path<'static>, PortalError>;
+
+  /// Subscribes to the `Activated` signal, emitted when a shortcut is triggered.
+  ///
+  /// The callback receives the session, the shortcut id, a timestamp in milliseconds,
+  /// and a map of further details.
+  fn on_activated<'c, F>(
+    &self,
+    connection: &'c Connection,
+    callback: F,
+  ) -> Result<SignalGuard<'c>, PortalError>
+  where
+    F: FnMut(Session, String, u64, PropMap) + Send + 'static;
+
+  /// Subscribes to the `Deactivated` signal, emitted when a shortcut is released.
+  ///
+  /// The callback receives the session, the shortcut id, a timestamp in milliseconds,
+  /// and a map of further details.
+  fn on_deactivated<'c, F>(
+    &self,
+    connection: &'c Connection,
+    callback: F,
+  ) -> Result<SignalGuard<'c>, PortalError>
+  where
+    F: FnMut(Session, String, u64, PropMap) + Send + 'static;
+
+  /// Reads the "version" property for this D-Bus interface.
+  fn version(&self) -> Result<u32, PortalError>;
+}
+
+/// A shortcut to bind with `GlobalShortcuts::bind_shortcuts()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Shortcut {
+  id: String,
+  description: String,
+  preferred_trigger: Option<String>,
+}
+
+impl Shortcut {
+  /// Creates a new `Shortcut`.
+  ///
+  /// - `id`: Application-provided identifier, sent back by the `Activated` and `Deactivated` signals.
+  /// - `description`: User-readable text describing what the shortcut does.
+  pub fn new(id: String, description: String) -> Self {
+    Self {
+      id,
+      description,
+      preferred_trigger: None,
+    }
+  }
+
+  /// The preferred shortcut trigger, defined as described by the "shortcuts" XDG specification,
+  /// e.g. `CTRL+a`. The portal may ignore it.
+  pub fn preferred_trigger(mut self, preferred_trigger: String) -> Self {
+    self.preferred_trigger = Some(preferred_trigger);
+    self
+  }
+
+  /// The wire format of a shortcut, `(sa{sv})`.
+  fn into_arg(self) -> (String, PropMap) {
+    let mut map = PropMap::new();
+    map.insert(
+      "description".to_string(),
+      Variant(Box::new(self.description)),
+    );
+    if let Some(preferred_trigger) = self.preferred_trigger {
+      map.insert(
+        "preferred_trigger".to_string(),
+        Variant(Box::new(preferred_trigger)),
+      );
+    }
+    (self.id, map)
+  }
+}
+
+/// Optional arguments for the `GlobalShortcuts::bind_shortcuts()` and
+/// `GlobalShortcuts::list_shortcuts()` methods.
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ShortcutsOptions {
+  handle_token: Option<String>,
+}
+
+impl ShortcutsOptions {
+  /// Creates a new `ShortcutsOptions` struct with no arguments set.
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  /// A string that will be used as the last element of the @handle. Must be a valid
+  /// object path element. See the #org.freedesktop.portal.Request documentation for
+  /// more information about the @handle.
+  pub fn handle_token(mut self, handle_token: impl Into<String>) -> Self {
+    self.handle_token = Some(handle_token.into());
+    self
+  }
+}
+
+impl From<ShortcutsOptions> for PropMap {
+  fn from(options: ShortcutsOptions) -> Self {
+    let mut map = PropMap::new();
+    if let Some(handle_token) = options.handle_token {
+      map.insert("handle_token".to_string(), Variant(Box::new(handle_token)));
+    }
+    map
+  }
+}
+
+impl<'a, T: blocking::BlockingSender, C: std::ops::Deref<Target = T>> GlobalShortcuts
+  for blocking::Proxy<'a, C>
+{
+  fn create_session(&self, options: CreateSessionOptions) -> Result<Path<'static>, PortalError> {
+    let options = PropMap::from(options);
+    let _span = CallSpan::enter(
+      INTERFACE,
+      "CreateSession",
+      "",
+      trace::handle_token(&options),
+    );
+    self
+      .method_call(INTERFACE, "CreateSession", (options,))
+      .map(|r: (Path<'static>,)| trace::request_created(r.0))
+      .map_err(Into::into)
+  }
+
+  fn bind_shortcuts(
+    &self,
+    session: &Session,
+    shortcuts: Vec<Shortcut>,
+    parent_window: impl Into<ParentWindowArg>,
+    options: ShortcutsOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    let shortcuts: Vec<_> = shortcuts.into_iter().map(Shortcut::into_arg).collect();
+    let parent_window: ParentWindowArg = parent_window.into();
+    let options = PropMap::from(options);
+    let _span = CallSpan::enter(
+      INTERFACE,
+      "BindShortcuts",
+      parent_window.as_str(),
+      trace::handle_token(&options),
+    );
+    self
+      .method_call(
+        INTERFACE,
+        "BindShortcuts",
+        (session.path(), shortcuts, parent_window.as_str(), options),
+      )
+      .map(|r: (Path<'static>,)| trace::request_created(r.0))
+      .map_err(Into::into)
+  }
+
+  fn list_shortcuts(
+    &self,
+    session: &Session,
+    options: ShortcutsOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    let options = PropMap::from(options);
+    let _span = CallSpan::enter(
+      INTERFACE,
+      "ListShortcuts",
+      "",
+      trace::handle_token(&options),
+    );
+    self
+      .method_call(INTERFACE, "ListShortcuts", (session.path(), options))
+      .map(|r: (Path<'static>,)| trace::request_created(r.0))
+      .map_err(Into::into)
+  }
+
+  fn on_activated<'c, F>(
+    &self,
+    connection: &'c Connection,
+    mut callback: F,
+  ) -> Result<SignalGuard<'c>, PortalError>
+  where
+    F: FnMut(Session, String, u64, PropMap) + Send + 'static,
+  {
+    SignalGuard::for_events(
+      connection,
+      signal_rule(self, INTERFACE, "Activated"),
+      move |event| {
+        if let PortalEvent::ShortcutActivated {
+          session,
+          shortcut_id,
+          timestamp,
+          options,
+        } = event
+        {
+          callback(session, shortcut_id, timestamp, options);
+        }
+      },
+    )
+  }
+
+  fn on_deactivated<'c, F>(
+    &self,
+    connection: &'c Connection,
+    mut callback: F,
+  ) -> Result<SignalGuard<'c>, PortalError>
+  where
+    F: FnMut(Session, String, u64, PropMap) + Send + 'static,
+  {
+    SignalGuard::for_events(
+      connection,
+      signal_rule(self, INTERFACE, "Deactivated"),
+      move |event| {
+        if let PortalEvent::ShortcutDeactivated {
+          session,
+          shortcut_id,
+          timestamp,
+          options,
+        } = event
+        {
+          callback(session, shortcut_id, timestamp, options);
+        }
+      },
+    )
+  }
+
+  fn version(&self) -> Result<u32, PortalError> {
+    <Self as org_freedesktop_dbus::Properties>::get(self, INTERFACE, "version").map_err(Into::into)
+  }
+}
@@ -0,0 +1,1257 @@
+// Copyright 2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+#[cfg(feature = "spec-v2")]
+use crate::StatusOptions;
+use crate::{
+  AccessDeviceOptions, Account, AccountOptions, Background, BackgroundOptions, Barrier, Camera,
+  CameraOptions, CancelToken, Capabilities, CaptureActivation, CaptureSessionOptions, Color,
+  ColorScheme, Connectivity, CreateSessionOptions, Device, DevicePortal, DeviceType,
+  DynamicLauncher, Email, EmailOptions, FileChooser, GlobalShortcuts, Icon, Inhibit, InhibitFlags,
+  InhibitGuard, InhibitOptions, InputCapture, InputCaptureOptions, IntoPortalFd, KeyState,
+  LaunchOptions, LauncherIcon, LauncherType, Location, LocationPortal, LocationSessionOptions,
+  MemoryMonitor, NetworkMonitor, NetworkStatus, Notification, NotificationPortal, OpenFileOptions,
+  OpenURI, OpenURIOptions, ParentWindowArg, PickColorOptions, PortalError, Portals,
+  PrepareInstallOptions, PreparePrintOptions, Print, PrintOptions, ProxyResolver, Realtime,
+  ReleaseOptions, RemoteDesktop, ResponseCode, SaveFileOptions, SaveFilesOptions, ScreenCast,
+  Screenshot, ScreenshotOptions, Secret, SecretOptions, SelectDevicesOptions, SelectSourcesOptions,
+  Session, SettingValue, Settings, Shortcut, ShortcutsOptions, SignalGuard, SourceType,
+  StartOptions, Stream, Wallpaper, WallpaperOptions,
+};
+#[cfg(feature = "spec-v3")]
+use crate::{CreateMonitorOptions, SessionState};
+
+use dbus::{
+  arg::{OwnedFd, PropMap, RefArg, Variant},
+  blocking::{BlockingSender, Connection, Proxy},
+  Path,
+};
+use std::{collections::HashMap, fs::File, ops::Deref, path::PathBuf, time::Duration};
+
+macro_rules! portal_handles {
+  ($($accessor:ident => $handle:ident: $portal:ident,)*) => {
+    $(
+      #[doc = concat!("Handle for the `", stringify!($portal), "` portal, see `Portals::", stringify!($accessor), "()`.")]
+      pub struct $handle<'p, 'a, C>(&'p Proxy<'a, C>);
+    )*
+
+    impl<'a, C> Portals<'a, C> {
+      $(
+        #[doc = concat!("The `", stringify!($portal), "` portal.")]
+        pub fn $accessor(&self) -> $handle<'_, 'a, C> {
+          $handle(&self.proxy)
+        }
+      )*
+    }
+  };
+}
+
+portal_handles! {
+  account => AccountHandle: Account,
+  background => BackgroundHandle: Background,
+  camera => CameraHandle: Camera,
+  device => DeviceHandle: DevicePortal,
+  dynamic_launcher => DynamicLauncherHandle: DynamicLauncher,
+  email => EmailHandle: Email,
+  file_chooser => FileChooserHandle: FileChooser,
+  global_shortcuts => GlobalShortcutsHandle: GlobalShortcuts,
+  inhibit => InhibitHandle: Inhibit,
+  input_capture => InputCaptureHandle: InputCapture,
+  location => LocationHandle: LocationPortal,
+  memory_monitor => MemoryMonitorHandle: MemoryMonitor,
+  network_monitor => NetworkMonitorHandle: NetworkMonitor,
+  notification => NotificationHandle: NotificationPortal,
+  open_uri => OpenURIHandle: OpenURI,
+  print => PrintHandle: Print,
+  proxy_resolver => ProxyResolverHandle: ProxyResolver,
+  realtime => RealtimeHandle: Realtime,
+  remote_desktop => RemoteDesktopHandle: RemoteDesktop,
+  screen_cast => ScreenCastHandle: ScreenCast,
+  screenshot => ScreenshotHandle: Screenshot,
+  secret => SecretHandle: Secret,
+  settings => SettingsHandle: Settings,
+  wallpaper => WallpaperHandle: Wallpaper,
+}
+
+impl<'p, 'a, T: BlockingSender, C: Deref<Target = T>> AccountHandle<'p, 'a, C> {
+  /// See `Account::get_user_information()`.
+  pub fn get_user_information(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    options: AccountOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    Account::get_user_information(self.0, parent_window, options)
+  }
+
+  /// See `Account::version()`.
+  pub fn version(&self) -> Result<u32, PortalError> {
+    Account::version(self.0)
+  }
+}
+
+impl<'p, 'a, T: BlockingSender, C: Deref<Target = T>> BackgroundHandle<'p, 'a, C> {
+  /// See `Background::request_background()`.
+  pub fn request_background(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    options: BackgroundOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    Background::request_background(self.0, parent_window, options)
+  }
+
+  /// See `Background::set_status()`.
+  #[cfg(feature = "spec-v2")]
+  pub fn set_status(&self, options: StatusOptions) -> Result<(), PortalError> {
+    Background::set_status(self.0, options)
+  }
+
+  /// See `Background::version()`.
+  pub fn version(&self) -> Result<u32, PortalError> {
+    Background::version(self.0)
+  }
+}
+
+impl<'p, 'a, T: BlockingSender, C: Deref<Target = T>> CameraHandle<'p, 'a, C> {
+  /// See `Camera::access_camera()`.
+  pub fn access_camera(&self, options: CameraOptions) -> Result<Path<'static>, PortalError> {
+    Camera::access_camera(self.0, options)
+  }
+
+  /// See `Camera::open_pipe_wire_remote()`.
+  pub fn open_pipe_wire_remote(&self, options: PropMap) -> Result<OwnedFd, PortalError> {
+    Camera::open_pipe_wire_remote(self.0, options)
+  }
+
+  /// See `Camera::is_camera_present()`.
+  pub fn is_camera_present(&self) -> Result<bool, PortalError> {
+    Camera::is_camera_present(self.0)
+  }
+
+  /// See `Camera::version()`.
+  pub fn version(&self) -> Result<u32, PortalError> {
+    Camera::version(self.0)
+  }
+}
+
+impl<'p, 'a, T: BlockingSender, C: Deref<Target = T>> DeviceHandle<'p, 'a, C> {
+  /// See `DevicePortal::access_device()`.
+  pub fn access_device(
+    &self,
+    pid: u32,
+    devices: &[Device],
+    options: AccessDeviceOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    DevicePortal::access_device(self.0, pid, devices, options)
+  }
+
+  /// See `DevicePortal::version()`.
+  pub fn version(&self) -> Result<u32, PortalError> {
+    DevicePortal::version(self.0)
+  }
+}
+
+impl<'p, 'a, T: BlockingSender, C: Deref<Target = T>> DynamicLauncherHandle<'p, 'a, C> {
+  /// See `DynamicLauncher::prepare_install()`.
+  pub fn prepare_install(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    name: &str,
+    icon: Icon,
+    options: PrepareInstallOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    DynamicLauncher::prepare_install(self.0, parent_window, name, icon, options)
+  }
+
+  /// See `DynamicLauncher::request_install_token()`.
+  pub fn request_install_token(&self, name: &str, icon: Icon) -> Result<String, PortalError> {
+    DynamicLauncher::request_install_token(self.0, name, icon)
+  }
+
+  /// See `DynamicLauncher::install()`.
+  pub fn install(
+    &self,
+    token: &str,
+    desktop_file_id: &str,
+    desktop_entry: &str,
+    options: PropMap,
+  ) -> Result<(), PortalError> {
+    DynamicLauncher::install(self.0, token, desktop_file_id, desktop_entry, options)
+  }
+
+  /// See `DynamicLauncher::uninstall()`.
+  pub fn uninstall(&self, desktop_file_id: &str, options: PropMap) -> Result<(), PortalError> {
+    DynamicLauncher::uninstall(self.0, desktop_file_id, options)
+  }
+
+  /// See `DynamicLauncher::get_desktop_entry()`.
+  pub fn get_desktop_entry(&self, desktop_file_id: &str) -> Result<String, PortalError> {
+    DynamicLauncher::get_desktop_entry(self.0, desktop_file_id)
+  }
+
+  /// See `DynamicLauncher::get_icon()`.
+  pub fn get_icon(&self, desktop_file_id: &str) -> Result<LauncherIcon, PortalError> {
+    DynamicLauncher::get_icon(self.0, desktop_file_id)
+  }
+
+  /// See `DynamicLauncher::launch()`.
+  pub fn launch(&self, desktop_file_id: &str, options: LaunchOptions) -> Result<(), PortalError> {
+    DynamicLauncher::launch(self.0, desktop_file_id, options)
+  }
+
+  /// See `DynamicLauncher::supported_launcher_types()`.
+  pub fn supported_launcher_types(&self) -> Result<LauncherType, PortalError> {
+    DynamicLauncher::supported_launcher_types(self.0)
+  }
+
+  /// See `DynamicLauncher::version()`.
+  pub fn version(&self) -> Result<u32, PortalError> {
+    DynamicLauncher::version(self.0)
+  }
+}
+
+impl<'p, 'a, T: BlockingSender, C: Deref<Target = T>> EmailHandle<'p, 'a, C> {
+  /// See `Email::compose_email()`.
+  pub fn compose_email(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    options: EmailOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    Email::compose_email(self.0, parent_window, options)
+  }
+
+  /// See `Email::version()`.
+  pub fn version(&self) -> Result<u32, PortalError> {
+    Email::version(self.0)
+  }
+}
+
+impl<'p, 'a, T: BlockingSender, C: Deref<Target = T>> FileChooserHandle<'p, 'a, C> {
+  /// See `FileChooser::open_file()`.
+  pub fn open_file(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    title: &str,
+    options: OpenFileOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    FileChooser::open_file(self.0, parent_window, title, options)
+  }
+
+  /// See `FileChooser::save_file()`.
+  pub fn save_file(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    title: &str,
+    options: SaveFileOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    FileChooser::save_file(self.0, parent_window, title, options)
+  }
+
+  /// See `FileChooser::save_files()`.
+  pub fn save_files(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    title: &str,
+    options: SaveFilesOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    FileChooser::save_files(self.0, parent_window, title, options)
+  }
+
+  /// See `FileChooser::version()`.
+  pub fn version(&self) -> Result<u32, PortalError> {
+    FileChooser::version(self.0)
+  }
+}
+
+impl<'p, 'a, T: BlockingSender, C: Deref<Target = T>> GlobalShortcutsHandle<'p, 'a, C> {
+  /// See `GlobalShortcuts::create_session()`.
+  pub fn create_session(
+    &self,
+    options: CreateSessionOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    GlobalShortcuts::create_session(self.0, options)
+  }
+
+  /// See `GlobalShortcuts::bind_shortcuts()`.
+  pub fn bind_shortcuts(
+    &self,
+    session: &Session,
+    shortcuts: Vec<Shortcut>,
+    parent_window: impl Into<ParentWindowArg>,
+    options: ShortcutsOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    GlobalShortcuts::bind_shortcuts(self.0, session, shortcuts, parent_window, options)
+  }
+
+  /// See `GlobalShortcuts::list_shortcuts()`.
+  pub fn list_shortcuts(
+    &self,
+    session: &Session,
+    options: ShortcutsOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    GlobalShortcuts::list_shortcuts(self.0, session, options)
+  }
+
+  /// See `GlobalShortcuts::on_activated()`.
+  pub fn on_activated<'c, F>(
+    &self,
+    connection: &'c Connection,
+    callback: F,
+  ) -> Result<SignalGuard<'c>, PortalError>
+  where
+    F: FnMut(Session, String, u64, PropMap) + Send + 'static,
+  {
+    GlobalShortcuts::on_activated(self.0, connection, callback)
+  }
+
+  /// See `GlobalShortcuts::on_deactivated()`.
+  pub fn on_deactivated<'c, F>(
+    &self,
+    connection: &'c Connection,
+    callback: F,
+  ) -> Result<SignalGuard<'c>, PortalError>
+  where
+    F: FnMut(Session, String, u64, PropMap) + Send + 'static,
+  {
+    GlobalShortcuts::on_deactivated(self.0, connection, callback)
+  }
+
+  /// See `GlobalShortcuts::version()`.
+  pub fn version(&self) -> Result<u32, PortalError> {
+    GlobalShortcuts::version(self.0)
+  }
+}
+
+impl<'p, 'a, T: BlockingSender + 'a, C: Deref<Target = T>> InhibitHandle<'p, 'a, C> {
+  /// See `Inhibit::inhibit()`.
+  pub fn inhibit(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    flags: InhibitFlags,
+    options: InhibitOptions,
+  ) -> Result<InhibitGuard<'_>, PortalError> {
+    Inhibit::inhibit(self.0, parent_window, flags, options)
+  }
+
+  /// See `Inhibit::release()`.
+  pub fn release(&self, handle: &Path<'_>) -> Result<(), PortalError> {
+    Inhibit::release(self.0, handle)
+  }
+
+  /// See `Inhibit::create_monitor()`.
+  #[cfg(feature = "spec-v3")]
+  pub fn create_monitor(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    options: CreateMonitorOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    Inhibit::create_monitor(self.0, parent_window, options)
+  }
+
+  /// See `Inhibit::create_monitor_and_wait()`.
+  #[cfg(feature = "spec-v3")]
+  pub fn create_monitor_and_wait(
+    &self,
+    connection: &Connection,
+    parent_window: impl Into<ParentWindowArg>,
+    options: CreateMonitorOptions,
+    timeout: Duration,
+  ) -> Result<Session, PortalError> {
+    Inhibit::create_monitor_and_wait(self.0, connection, parent_window, options, timeout)
+  }
+
+  /// See `Inhibit::query_end_response()`.
+  #[cfg(feature = "spec-v3")]
+  pub fn query_end_response(&self, session: &Session) -> Result<(), PortalError> {
+    Inhibit::query_end_response(self.0, session)
+  }
+
+  /// See `Inhibit::on_state_changed()`.
+  #[cfg(feature = "spec-v3")]
+  pub fn on_state_changed<'c, F>(
+    &self,
+    connection: &'c Connection,
+    callback: F,
+  ) -> Result<SignalGuard<'c>, PortalError>
+  where
+    F: FnMut(Session, SessionState) + Send + 'static,
+  {
+    Inhibit::on_state_changed(self.0, connection, callback)
+  }
+
+  /// See `Inhibit::version()`.
+  pub fn version(&self) -> Result<u32, PortalError> {
+    Inhibit::version(self.0)
+  }
+}
+
+impl<'p, 'a, T: BlockingSender, C: Deref<Target = T>> InputCaptureHandle<'p, 'a, C> {
+  /// See `InputCapture::create_session()`.
+  pub fn create_session(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    options: CaptureSessionOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    InputCapture::create_session(self.0, parent_window, options)
+  }
+
+  /// See `InputCapture::get_zones()`.
+  pub fn get_zones(
+    &self,
+    session: &Session,
+    options: InputCaptureOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    InputCapture::get_zones(self.0, session, options)
+  }
+
+  /// See `InputCapture::set_pointer_barriers()`.
+  pub fn set_pointer_barriers(
+    &self,
+    session: &Session,
+    options: InputCaptureOptions,
+    barriers: Vec<Barrier>,
+    zone_set: u32,
+  ) -> Result<Path<'static>, PortalError> {
+    InputCapture::set_pointer_barriers(self.0, session, options, barriers, zone_set)
+  }
+
+  /// See `InputCapture::enable()`.
+  pub fn enable(&self, session: &Session, options: PropMap) -> Result<(), PortalError> {
+    InputCapture::enable(self.0, session, options)
+  }
+
+  /// See `InputCapture::disable()`.
+  pub fn disable(&self, session: &Session, options: PropMap) -> Result<(), PortalError> {
+    InputCapture::disable(self.0, session, options)
+  }
+
+  /// See `InputCapture::release()`.
+  pub fn release(&self, session: &Session, options: ReleaseOptions) -> Result<(), PortalError> {
+    InputCapture::release(self.0, session, options)
+  }
+
+  /// See `InputCapture::connect_to_eis()`.
+  pub fn connect_to_eis(
+    &self,
+    session: &Session,
+    options: PropMap,
+  ) -> Result<OwnedFd, PortalError> {
+    InputCapture::connect_to_eis(self.0, session, options)
+  }
+
+  /// See `InputCapture::on_disabled()`.
+  pub fn on_disabled<'c, F>(
+    &self,
+    connection: &'c Connection,
+    callback: F,
+  ) -> Result<SignalGuard<'c>, PortalError>
+  where
+    F: FnMut(Session, PropMap) + Send + 'static,
+  {
+    InputCapture::on_disabled(self.0, connection, callback)
+  }
+
+  /// See `InputCapture::on_activated()`.
+  pub fn on_activated<'c, F>(
+    &self,
+    connection: &'c Connection,
+    callback: F,
+  ) -> Result<SignalGuard<'c>, PortalError>
+  where
+    F: FnMut(Session, CaptureActivation) + Send + 'static,
+  {
+    InputCapture::on_activated(self.0, connection, callback)
+  }
+
+  /// See `InputCapture::on_deactivated()`.
+  pub fn on_deactivated<'c, F>(
+    &self,
+    connection: &'c Connection,
+    callback: F,
+  ) -> Result<SignalGuard<'c>, PortalError>
+  where
+    F: FnMut(Session, CaptureActivation) + Send + 'static,
+  {
+    InputCapture::on_deactivated(self.0, connection, callback)
+  }
+
+  /// See `InputCapture::on_zones_changed()`.
+  pub fn on_zones_changed<'c, F>(
+    &self,
+    connection: &'c Connection,
+    callback: F,
+  ) -> Result<SignalGuard<'c>, PortalError>
+  where
+    F: FnMut(Session, Option<u32>) + Send + 'static,
+  {
+    InputCapture::on_zones_changed(self.0, connection, callback)
+  }
+
+  /// See `InputCapture::supported_capabilities()`.
+  pub fn supported_capabilities(&self) -> Result<Capabilities, PortalError> {
+    InputCapture::supported_capabilities(self.0)
+  }
+
+  /// See `InputCapture::version()`.
+  pub fn version(&self) -> Result<u32, PortalError> {
+    InputCapture::version(self.0)
+  }
+}
+
+impl<'p, 'a, T: BlockingSender, C: Deref<Target = T>> LocationHandle<'p, 'a, C> {
+  /// See `LocationPortal::create_session()`.
+  pub fn create_session(
+    &self,
+    options: LocationSessionOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    LocationPortal::create_session(self.0, options)
+  }
+
+  /// See `LocationPortal::start()`.
+  pub fn start(
+    &self,
+    session: &Session,
+    parent_window: impl Into<ParentWindowArg>,
+    options: StartOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    LocationPortal::start(self.0, session, parent_window, options)
+  }
+
+  /// See `LocationPortal::on_location_updated()`.
+  pub fn on_location_updated<'c, F>(
+    &self,
+    connection: &'c Connection,
+    callback: F,
+  ) -> Result<SignalGuard<'c>, PortalError>
+  where
+    F: FnMut(Session, Location) + Send + 'static,
+  {
+    LocationPortal::on_location_updated(self.0, connection, callback)
+  }
+
+  /// See `LocationPortal::version()`.
+  pub fn version(&self) -> Result<u32, PortalError> {
+    LocationPortal::version(self.0)
+  }
+}
+
+impl<'p, 'a, T: BlockingSender, C: Deref<Target = T>> MemoryMonitorHandle<'p, 'a, C> {
+  /// See `MemoryMonitor::on_low_memory_warning()`.
+  pub fn on_low_memory_warning<'c, F>(
+    &self,
+    connection: &'c Connection,
+    callback: F,
+  ) -> Result<SignalGuard<'c>, PortalError>
+  where
+    F: FnMut(u8) + Send + 'static,
+  {
+    MemoryMonitor::on_low_memory_warning(self.0, connection, callback)
+  }
+
+  /// See `MemoryMonitor::version()`.
+  pub fn version(&self) -> Result<u32, PortalError> {
+    MemoryMonitor::version(self.0)
+  }
+}
+
+impl<'p, 'a, T: BlockingSender, C: Deref<Target = T>> NetworkMonitorHandle<'p, 'a, C> {
+  /// See `NetworkMonitor::get_available()`.
+  pub fn get_available(&self) -> Result<bool, PortalError> {
+    NetworkMonitor::get_available(self.0)
+  }
+
+  /// See `NetworkMonitor::get_metered()`.
+  pub fn get_metered(&self) -> Result<bool, PortalError> {
+    NetworkMonitor::get_metered(self.0)
+  }
+
+  /// See `NetworkMonitor::get_connectivity()`.
+  pub fn get_connectivity(&self) -> Result<Connectivity, PortalError> {
+    NetworkMonitor::get_connectivity(self.0)
+  }
+
+  /// See `NetworkMonitor::get_status()`.
+  pub fn get_status(&self) -> Result<NetworkStatus, PortalError> {
+    NetworkMonitor::get_status(self.0)
+  }
+
+  /// See `NetworkMonitor::can_reach()`.
+  pub fn can_reach(&self, hostname: &str, port: u32) -> Result<bool, PortalError> {
+    NetworkMonitor::can_reach(self.0, hostname, port)
+  }
+
+  /// See `NetworkMonitor::on_changed()`.
+  pub fn on_changed<'c, F>(
+    &self,
+    connection: &'c Connection,
+    callback: F,
+  ) -> Result<SignalGuard<'c>, PortalError>
+  where
+    F: FnMut() + Send + 'static,
+  {
+    NetworkMonitor::on_changed(self.0, connection, callback)
+  }
+
+  /// See `NetworkMonitor::version()`.
+  pub fn version(&self) -> Result<u32, PortalError> {
+    NetworkMonitor::version(self.0)
+  }
+}
+
+impl<'p, 'a, T: BlockingSender, C: Deref<Target = T>> NotificationHandle<'p, 'a, C> {
+  /// See `NotificationPortal::add_notification()`.
+  pub fn add_notification(&self, id: &str, notification: Notification) -> Result<(), PortalError> {
+    NotificationPortal::add_notification(self.0, id, notification)
+  }
+
+  /// See `NotificationPortal::remove_notification()`.
+  pub fn remove_notification(&self, id: &str) -> Result<(), PortalError> {
+    NotificationPortal::remove_notification(self.0, id)
+  }
+
+  /// See `NotificationPortal::on_action_invoked()`.
+  pub fn on_action_invoked<'c, F>(
+    &self,
+    connection: &'c Connection,
+    callback: F,
+  ) -> Result<SignalGuard<'c>, PortalError>
+  where
+    F: FnMut(String, String, Vec<Variant<Box<dyn RefArg>>>) + Send + 'static,
+  {
+    NotificationPortal::on_action_invoked(self.0, connection, callback)
+  }
+
+  /// See `NotificationPortal::version()`.
+  pub fn version(&self) -> Result<u32, PortalError> {
+    NotificationPortal::version(self.0)
+  }
+}
+
+impl<'p, 'a, T: BlockingSender, C: Deref<Target = T>> OpenURIHandle<'p, 'a, C> {
+  /// See `OpenURI::open_uri()`.
+  pub fn open_uri(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    uri: &str,
+    options: OpenURIOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    OpenURI::open_uri(self.0, parent_window, uri, options)
+  }
+
+  /// See `OpenURI::open_file()`.
+  pub fn open_file(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    fd: impl IntoPortalFd,
+    options: OpenURIOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    OpenURI::open_file(self.0, parent_window, fd, options)
+  }
+
+  /// See `OpenURI::open_directory()`.
+  pub fn open_directory(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    fd: impl IntoPortalFd,
+    options: OpenURIOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    OpenURI::open_directory(self.0, parent_window, fd, options)
+  }
+
+  /// See `OpenURI::open()`.
+  pub fn open(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    uri_or_path: &str,
+    options: OpenURIOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    OpenURI::open(self.0, parent_window, uri_or_path, options)
+  }
+
+  /// See `OpenURI::open_uri_checked()`.
+  pub fn open_uri_checked(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    uri: &str,
+    options: OpenURIOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    OpenURI::open_uri_checked(self.0, parent_window, uri, options)
+  }
+
+  /// See `OpenURI::open_file_owned()`.
+  pub fn open_file_owned(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    file: File,
+    options: OpenURIOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    OpenURI::open_file_owned(self.0, parent_window, file, options)
+  }
+
+  /// See `OpenURI::open_file_path()`.
+  pub fn open_file_path(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    path: impl AsRef<std::path::Path>,
+    options: OpenURIOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    OpenURI::open_file_path(self.0, parent_window, path, options)
+  }
+
+  /// See `OpenURI::open_directory_path()`.
+  pub fn open_directory_path(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    path: impl AsRef<std::path::Path>,
+    options: OpenURIOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    OpenURI::open_directory_path(self.0, parent_window, path, options)
+  }
+
+  /// See `OpenURI::open_writable_file()`.
+  pub fn open_writable_file(
+    &self,
+    documents: &impl crate::Documents,
+    parent_window: impl Into<ParentWindowArg>,
+    path: impl AsRef<std::path::Path>,
+    peer_app_id: &str,
+    options: OpenURIOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    OpenURI::open_writable_file(self.0, documents, parent_window, path, peer_app_id, options)
+  }
+
+  /// See `OpenURI::reveal_file()`.
+  pub fn reveal_file(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    path: impl AsRef<std::path::Path>,
+    options: OpenURIOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    OpenURI::reveal_file(self.0, parent_window, path, options)
+  }
+
+  /// See `OpenURI::can_open_scheme()`.
+  pub fn can_open_scheme(&self, scheme: &str) -> Result<bool, PortalError> {
+    OpenURI::can_open_scheme(self.0, scheme)
+  }
+
+  /// See `OpenURI::can_open_scheme_with()`.
+  pub fn can_open_scheme_with(&self, scheme: &str, fallback: &[&str]) -> Result<bool, PortalError> {
+    OpenURI::can_open_scheme_with(self.0, scheme, fallback)
+  }
+
+  /// See `OpenURI::open_uri_and_wait()`.
+  pub fn open_uri_and_wait(
+    &self,
+    connection: &Connection,
+    parent_window: impl Into<ParentWindowArg>,
+    uri: &str,
+    options: OpenURIOptions,
+    timeout: Duration,
+  ) -> Result<(ResponseCode, PropMap), PortalError> {
+    OpenURI::open_uri_and_wait(self.0, connection, parent_window, uri, options, timeout)
+  }
+
+  /// See `OpenURI::open_uri_and_wait_cancellable()`.
+  pub fn open_uri_and_wait_cancellable(
+    &self,
+    connection: &Connection,
+    parent_window: impl Into<ParentWindowArg>,
+    uri: &str,
+    options: OpenURIOptions,
+    timeout: Duration,
+    cancel_token: &CancelToken,
+  ) -> Result<(ResponseCode, PropMap), PortalError> {
+    OpenURI::open_uri_and_wait_cancellable(
+      self.0,
+      connection,
+      parent_window,
+      uri,
+      options,
+      timeout,
+      cancel_token,
+    )
+  }
+
+  /// See `OpenURI::open_file_and_wait()`.
+  pub fn open_file_and_wait(
+    &self,
+    connection: &Connection,
+    parent_window: impl Into<ParentWindowArg>,
+    fd: impl IntoPortalFd,
+    options: OpenURIOptions,
+    timeout: Duration,
+  ) -> Result<(ResponseCode, PropMap), PortalError> {
+    OpenURI::open_file_and_wait(self.0, connection, parent_window, fd, options, timeout)
+  }
+
+  /// See `OpenURI::open_directory_and_wait()`.
+  pub fn open_directory_and_wait(
+    &self,
+    connection: &Connection,
+    parent_window: impl Into<ParentWindowArg>,
+    fd: impl IntoPortalFd,
+    options: OpenURIOptions,
+    timeout: Duration,
+  ) -> Result<(ResponseCode, PropMap), PortalError> {
+    OpenURI::open_directory_and_wait(self.0, connection, parent_window, fd, options, timeout)
+  }
+
+  /// See `OpenURI::version()`.
+  pub fn version(&self) -> Result<u32, PortalError> {
+    OpenURI::version(self.0)
+  }
+}
+
+impl<'p, 'a, T: BlockingSender, C: Deref<Target = T>> PrintHandle<'p, 'a, C> {
+  /// See `Print::prepare_print()`.
+  pub fn prepare_print(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    title: &str,
+    settings: PropMap,
+    page_setup: PropMap,
+    options: PreparePrintOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    Print::prepare_print(self.0, parent_window, title, settings, page_setup, options)
+  }
+
+  /// See `Print::print()`.
+  pub fn print(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    title: &str,
+    fd: impl IntoPortalFd,
+    options: PrintOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    Print::print(self.0, parent_window, title, fd, options)
+  }
+
+  /// See `Print::version()`.
+  pub fn version(&self) -> Result<u32, PortalError> {
+    Print::version(self.0)
+  }
+}
+
+impl<'p, 'a, T: BlockingSender, C: Deref<Target = T>> ProxyResolverHandle<'p, 'a, C> {
+  /// See `ProxyResolver::lookup()`.
+  pub fn lookup(&self, uri: &str) -> Result<Vec<String>, PortalError> {
+    ProxyResolver::lookup(self.0, uri)
+  }
+
+  /// See `ProxyResolver::version()`.
+  pub fn version(&self) -> Result<u32, PortalError> {
+    ProxyResolver::version(self.0)
+  }
+}
+
+impl<'p, 'a, T: BlockingSender, C: Deref<Target = T>> RealtimeHandle<'p, 'a, C> {
+  /// See `Realtime::make_thread_realtime()`.
+  pub fn make_thread_realtime(
+    &self,
+    process: u64,
+    thread: u64,
+    priority: u32,
+  ) -> Result<(), PortalError> {
+    Realtime::make_thread_realtime(self.0, process, thread, priority)
+  }
+
+  /// See `Realtime::make_thread_high_priority()`.
+  pub fn make_thread_high_priority(
+    &self,
+    process: u64,
+    thread: u64,
+    nice_level: i32,
+  ) -> Result<(), PortalError> {
+    Realtime::make_thread_high_priority(self.0, process, thread, nice_level)
+  }
+
+  /// See `Realtime::max_realtime_priority()`.
+  pub fn max_realtime_priority(&self) -> Result<i32, PortalError> {
+    Realtime::max_realtime_priority(self.0)
+  }
+
+  /// See `Realtime::min_nice_level()`.
+  pub fn min_nice_level(&self) -> Result<i32, PortalError> {
+    Realtime::min_nice_level(self.0)
+  }
+
+  /// See `Realtime::rt_time_usec_max()`.
+  pub fn rt_time_usec_max(&self) -> Result<i64, PortalError> {
+    Realtime::rt_time_usec_max(self.0)
+  }
+
+  /// See `Realtime::version()`.
+  pub fn version(&self) -> Result<u32, PortalError> {
+    Realtime::version(self.0)
+  }
+}
+
+impl<'p, 'a, T: BlockingSender, C: Deref<Target = T>> RemoteDesktopHandle<'p, 'a, C> {
+  /// See `RemoteDesktop::create_session()`.
+  pub fn create_session(
+    &self,
+    options: CreateSessionOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    RemoteDesktop::create_session(self.0, options)
+  }
+
+  /// See `RemoteDesktop::select_devices()`.
+  pub fn select_devices(
+    &self,
+    session: &Session,
+    options: SelectDevicesOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    RemoteDesktop::select_devices(self.0, session, options)
+  }
+
+  /// See `RemoteDesktop::start()`.
+  pub fn start(
+    &self,
+    session: &Session,
+    parent_window: impl Into<ParentWindowArg>,
+    options: StartOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    RemoteDesktop::start(self.0, session, parent_window, options)
+  }
+
+  /// See `RemoteDesktop::notify_pointer_motion()`.
+  pub fn notify_pointer_motion(
+    &self,
+    session: &Session,
+    options: PropMap,
+    dx: f64,
+    dy: f64,
+  ) -> Result<(), PortalError> {
+    RemoteDesktop::notify_pointer_motion(self.0, session, options, dx, dy)
+  }
+
+  /// See `RemoteDesktop::notify_pointer_motion_absolute()`.
+  pub fn notify_pointer_motion_absolute(
+    &self,
+    session: &Session,
+    options: PropMap,
+    stream: u32,
+    x: f64,
+    y: f64,
+  ) -> Result<(), PortalError> {
+    RemoteDesktop::notify_pointer_motion_absolute(self.0, session, options, stream, x, y)
+  }
+
+  /// See `RemoteDesktop::notify_pointer_button()`.
+  pub fn notify_pointer_button(
+    &self,
+    session: &Session,
+    options: PropMap,
+    button: i32,
+    state: KeyState,
+  ) -> Result<(), PortalError> {
+    RemoteDesktop::notify_pointer_button(self.0, session, options, button, state)
+  }
+
+  /// See `RemoteDesktop::notify_keyboard_keycode()`.
+  pub fn notify_keyboard_keycode(
+    &self,
+    session: &Session,
+    options: PropMap,
+    keycode: i32,
+    state: KeyState,
+  ) -> Result<(), PortalError> {
+    RemoteDesktop::notify_keyboard_keycode(self.0, session, options, keycode, state)
+  }
+
+  /// See `RemoteDesktop::notify_keyboard_keysym()`.
+  pub fn notify_keyboard_keysym(
+    &self,
+    session: &Session,
+    options: PropMap,
+    keysym: i32,
+    state: KeyState,
+  ) -> Result<(), PortalError> {
+    RemoteDesktop::notify_keyboard_keysym(self.0, session, options, keysym, state)
+  }
+
+  /// See `RemoteDesktop::notify_touch_down()`.
+  pub fn notify_touch_down(
+    &self,
+    session: &Session,
+    options: PropMap,
+    stream: u32,
+    slot: u32,
+    x: f64,
+    y: f64,
+  ) -> Result<(), PortalError> {
+    RemoteDesktop::notify_touch_down(self.0, session, options, stream, slot, x, y)
+  }
+
+  /// See `RemoteDesktop::notify_touch_motion()`.
+  pub fn notify_touch_motion(
+    &self,
+    session: &Session,
+    options: PropMap,
+    stream: u32,
+    slot: u32,
+    x: f64,
+    y: f64,
+  ) -> Result<(), PortalError> {
+    RemoteDesktop::notify_touch_motion(self.0, session, options, stream, slot, x, y)
+  }
+
+  /// See `RemoteDesktop::notify_touch_up()`.
+  pub fn notify_touch_up(
+    &self,
+    session: &Session,
+    options: PropMap,
+    slot: u32,
+  ) -> Result<(), PortalError> {
+    RemoteDesktop::notify_touch_up(self.0, session, options, slot)
+  }
+
+  /// See `RemoteDesktop::available_device_types()`.
+  pub fn available_device_types(&self) -> Result<DeviceType, PortalError> {
+    RemoteDesktop::available_device_types(self.0)
+  }
+
+  /// See `RemoteDesktop::version()`.
+  pub fn version(&self) -> Result<u32, PortalError> {
+    RemoteDesktop::version(self.0)
+  }
+}
+
+impl<'p, 'a, T: BlockingSender, C: Deref<Target = T>> ScreenCastHandle<'p, 'a, C> {
+  /// See `ScreenCast::create_session()`.
+  pub fn create_session(
+    &self,
+    options: CreateSessionOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    ScreenCast::create_session(self.0, options)
+  }
+
+  /// See `ScreenCast::select_sources()`.
+  pub fn select_sources(
+    &self,
+    session: &Session,
+    options: SelectSourcesOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    ScreenCast::select_sources(self.0, session, options)
+  }
+
+  /// See `ScreenCast::start()`.
+  pub fn start(
+    &self,
+    session: &Session,
+    parent_window: impl Into<ParentWindowArg>,
+    options: StartOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    ScreenCast::start(self.0, session, parent_window, options)
+  }
+
+  /// See `ScreenCast::open_pipe_wire_remote()`.
+  pub fn open_pipe_wire_remote(
+    &self,
+    session: &Session,
+    options: PropMap,
+  ) -> Result<OwnedFd, PortalError> {
+    ScreenCast::open_pipe_wire_remote(self.0, session, options)
+  }
+
+  /// See `ScreenCast::connect()`.
+  pub fn connect(
+    &self,
+    connection: &Connection,
+    session: &Session,
+    parent_window: impl Into<ParentWindowArg>,
+    options: StartOptions,
+    timeout: Duration,
+  ) -> Result<(OwnedFd, Vec<Stream>), PortalError> {
+    ScreenCast::connect(self.0, connection, session, parent_window, options, timeout)
+  }
+
+  /// See `ScreenCast::available_source_types()`.
+  pub fn available_source_types(&self) -> Result<SourceType, PortalError> {
+    ScreenCast::available_source_types(self.0)
+  }
+
+  /// See `ScreenCast::available_cursor_modes()`.
+  #[cfg(feature = "spec-v2")]
+  pub fn available_cursor_modes(&self) -> Result<u32, PortalError> {
+    ScreenCast::available_cursor_modes(self.0)
+  }
+
+  /// See `ScreenCast::version()`.
+  pub fn version(&self) -> Result<u32, PortalError> {
+    ScreenCast::version(self.0)
+  }
+}
+
+impl<'p, 'a, T: BlockingSender, C: Deref<Target = T>> ScreenshotHandle<'p, 'a, C> {
+  /// See `Screenshot::screenshot()`.
+  pub fn screenshot(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    options: ScreenshotOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    Screenshot::screenshot(self.0, parent_window, options)
+  }
+
+  /// See `Screenshot::screenshot_and_wait()`.
+  pub fn screenshot_and_wait(
+    &self,
+    connection: &Connection,
+    parent_window: impl Into<ParentWindowArg>,
+    options: ScreenshotOptions,
+    timeout: Duration,
+  ) -> Result<String, PortalError> {
+    Screenshot::screenshot_and_wait(self.0, connection, parent_window, options, timeout)
+  }
+
+  /// See `Screenshot::screenshot_to_path()`.
+  pub fn screenshot_to_path(
+    &self,
+    connection: &Connection,
+    parent_window: impl Into<ParentWindowArg>,
+    dest: impl AsRef<std::path::Path>,
+    options: ScreenshotOptions,
+    timeout: Duration,
+  ) -> Result<PathBuf, PortalError> {
+    Screenshot::screenshot_to_path(self.0, connection, parent_window, dest, options, timeout)
+  }
+
+  /// See `Screenshot::pick_color()`.
+  pub fn pick_color(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    options: PickColorOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    Screenshot::pick_color(self.0, parent_window, options)
+  }
+
+  /// See `Screenshot::pick_color_and_wait()`.
+  pub fn pick_color_and_wait(
+    &self,
+    connection: &Connection,
+    parent_window: impl Into<ParentWindowArg>,
+    options: PickColorOptions,
+    timeout: Duration,
+  ) -> Result<Color, PortalError> {
+    Screenshot::pick_color_and_wait(self.0, connection, parent_window, options, timeout)
+  }
+
+  /// See `Screenshot::version()`.
+  pub fn version(&self) -> Result<u32, PortalError> {
+    Screenshot::version(self.0)
+  }
+}
+
+impl<'p, 'a, T: BlockingSender, C: Deref<Target = T>> SecretHandle<'p, 'a, C> {
+  /// See `Secret::retrieve_secret()`.
+  pub fn retrieve_secret(
+    &self,
+    fd: impl IntoPortalFd,
+    options: SecretOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    Secret::retrieve_secret(self.0, fd, options)
+  }
+
+  /// See `Secret::retrieve_secret_bytes()`.
+  pub fn retrieve_secret_bytes(
+    &self,
+    connection: &Connection,
+    timeout: Duration,
+  ) -> Result<Vec<u8>, PortalError> {
+    Secret::retrieve_secret_bytes(self.0, connection, timeout)
+  }
+
+  /// See `Secret::version()`.
+  pub fn version(&self) -> Result<u32, PortalError> {
+    Secret::version(self.0)
+  }
+}
+
+impl<'p, 'a, T: BlockingSender, C: Deref<Target = T>> SettingsHandle<'p, 'a, C> {
+  /// See `Settings::read_all()`.
+  pub fn read_all(&self, namespaces: &[&str]) -> Result<HashMap<String, PropMap>, PortalError> {
+    Settings::read_all(self.0, namespaces)
+  }
+
+  /// See `Settings::read()`.
+  pub fn read(&self, namespace: &str, key: &str) -> Result<SettingValue, PortalError> {
+    Settings::read(self.0, namespace, key)
+  }
+
+  /// See `Settings::read_bool()`.
+  pub fn read_bool(&self, namespace: &str, key: &str) -> Result<bool, PortalError> {
+    Settings::read_bool(self.0, namespace, key)
+  }
+
+  /// See `Settings::read_u32()`.
+  pub fn read_u32(&self, namespace: &str, key: &str) -> Result<u32, PortalError> {
+    Settings::read_u32(self.0, namespace, key)
+  }
+
+  /// See `Settings::read_string()`.
+  pub fn read_string(&self, namespace: &str, key: &str) -> Result<String, PortalError> {
+    Settings::read_string(self.0, namespace, key)
+  }
+
+  /// See `Settings::color_scheme()`.
+  pub fn color_scheme(&self) -> Result<ColorScheme, PortalError> {
+    Settings::color_scheme(self.0)
+  }
+
+  /// See `Settings::on_setting_changed()`.
+  pub fn on_setting_changed<'c, F>(
+    &self,
+    connection: &'c Connection,
+    callback: F,
+  ) -> Result<SignalGuard<'c>, PortalError>
+  where
+    F: FnMut(String, String, Variant<Box<dyn RefArg>>) + Send + 'static,
+  {
+    Settings::on_setting_changed(self.0, connection, callback)
+  }
+
+  /// See `Settings::on_color_scheme_changed()`.
+  pub fn on_color_scheme_changed<'c, F>(
+    &self,
+    connection: &'c Connection,
+    callback: F,
+  ) -> Result<SignalGuard<'c>, PortalError>
+  where
+    F: FnMut(ColorScheme) + Send + 'static,
+  {
+    Settings::on_color_scheme_changed(self.0, connection, callback)
+  }
+
+  /// See `Settings::version()`.
+  pub fn version(&self) -> Result<u32, PortalError> {
+    Settings::version(self.0)
+  }
+}
+
+impl<'p, 'a, T: BlockingSender, C: Deref<Target = T>> WallpaperHandle<'p, 'a, C> {
+  /// See `Wallpaper::set_wallpaper_uri()`.
+  pub fn set_wallpaper_uri(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    uri: &str,
+    options: WallpaperOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    Wallpaper::set_wallpaper_uri(self.0, parent_window, uri, options)
+  }
+
+  /// See `Wallpaper::set_wallpaper_file()`.
+  pub fn set_wallpaper_file(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    fd: impl IntoPortalFd,
+    options: WallpaperOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    Wallpaper::set_wallpaper_file(self.0, parent_window, fd, options)
+  }
+
+  /// See `Wallpaper::version()`.
+  pub fn version(&self) -> Result<u32, PortalError> {
+    Wallpaper::version(self.0)
+  }
+}
@@ -0,0 +1,491 @@
+// Copyright 2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::{
+  close_request,
+  trace::{self, CallSpan},
+  ParentWindowArg, PortalError,
+};
+#[cfg(feature = "spec-v3")]
+use crate::{
+  generate_handle_token, signal_rule, PortalEvent, ResponseListener, Results, Session, SignalGuard,
+};
+
+#[cfg(feature = "spec-v3")]
+use dbus::blocking::Connection;
+use dbus::{
+  arg::{PropMap, Variant},
+  blocking::{self, stdintf::org_freedesktop_dbus, BlockingSender},
+  strings::BusName,
+  Path,
+};
+use std::{
+  ops::{BitOr, BitOrAssign},
+  time::Duration,
+};
+
+const INTERFACE: &str = "org.freedesktop.portal.Inhibit";
+
+/// Implementation of the `org.freedesktop.portal.Inhibit` Portal API.
+/// See also https://flatpak.github.io/xdg-desktop-portal/#gdbus-org.freedesktop.portal.Inhibit
+pub trait Inhibit {
+  /// Inhibits a session status change.
+  ///
+  /// The inhibition stays active for as long as the returned `InhibitGuard` is alive,
+  /// and is released when it's dropped.
+  ///
+  /// - `parent_window`: Identifier for the application window, see crate comments for common conventions.
+  /// - `flags`: What to inhibit.
+  fn inhibit(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    flags: InhibitFlags,
+    options: InhibitOptions,
+  ) -> Result<InhibitGuard<'_>, PortalError>;
+
+  /// Releases an inhibition, by closing its request path, see `InhibitGuard::leak()`.
+  fn release(&self, handle: &Path<'_>) -> Result<(), PortalError>;
+
+  /// Creates a monitoring session, which reports changes of the login session state with the
+  /// `StateChanged` signal, see `Inhibit::on_state_changed()`.
+  ///
+  /// The `session_handle` is returned in the results of the `Response` signal,
+  /// use `Session::from_results()` to read it.
+  ///
+  /// The CreateMonitor method was introduced in version 3 of the interface.
+  ///
+  /// - `parent_window`: Identifier for the application window, see crate comments for common conventions.
+  #[cfg(feature = "spec-v3")]
+  fn create_monitor(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    options: CreateMonitorOptions,
+  ) -> Result<Path<'static>, PortalError>;
+
+  /// Same as `Inhibit::create_monitor()`, but blocks until the portal has responded,
+  /// and returns the monitoring session.
+  ///
+  /// If no `handle_token` is set, one is generated, so the signal match for the predicted
+  /// request path can be added on `connection` before the request is made.
+  ///
+  /// The CreateMonitor method was introduced in version 3 of the interface.
+  #[cfg(feature = "spec-v3")]
+  fn create_monitor_and_wait(
+    &self,
+    connection: &Connection,
+    parent_window: impl Into<ParentWindowArg>,
+    options: CreateMonitorOptions,
+    timeout: Duration,
+  ) -> Result<Session, PortalError>;
+
+  /// Acknowledges that the application has handled the `LoginSessionState::QueryEnd` state,
+  /// for example by inhibiting logging out until it has saved its work.
+  ///
+  /// The QueryEndResponse method was introduced in version 3 of the interface.
+  #[cfg(feature = "spec-v3")]
+  fn query_end_response(&self, session: &Session) -> Result<(), PortalError>;
+
+  /// Subscribes to the `StateChanged` signal, emitted when the state of the login session changes.
+  ///
+  /// The callback receives the monitoring session, and the new state.
+  ///
+  /// The StateChanged signal was introduced in version 3 of the interface.
+  #[cfg(feature = "spec-v3")]
+  fn on_state_changed<'c, F>(
+    &self,
+    connection: &'c Connection,
+    callback: F,
+  ) -> Result<SignalGuard<'c>, PortalError>
+  where
+    F: FnMut(Session, SessionState) + Send + 'static;
+
+  /// Reads the "version" property for this D-Bus interface.
+  fn version(&self) -> Result<u32, PortalError>;
+}
+
+/// The session status changes to inhibit. Flags can be combined with `|`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InhibitFlags(u32);
+
+impl InhibitFlags {
+  /// Inhibit logging out.
+  pub const LOGOUT: Self = Self(1);
+  /// Inhibit user switching.
+  pub const USER_SWITCH: Self = Self(2);
+  /// Inhibit suspending.
+  pub const SUSPEND: Self = Self(4);
+  /// Inhibit the session being marked as idle.
+  pub const IDLE: Self = Self(8);
+
+  /// No flags set.
+  pub fn empty() -> Self {
+    Self(0)
+  }
+
+  /// The raw bits, as sent to the portal.
+  pub fn bits(&self) -> u32 {
+    self.0
+  }
+
+  /// Whether all flags in `other` are set.
+  pub fn contains(&self, other: Self) -> bool {
+    self.0 & other.0 == other.0
+  }
+}
+
+impl BitOr for InhibitFlags {
+  type Output = Self;
+
+  fn bitor(self, rhs: Self) -> Self {
+    Self(self.0 | rhs.0)
+  }
+}
+
+impl BitOrAssign for InhibitFlags {
+  fn bitor_assign(&mut self, rhs: Self) {
+    self.0 |= rhs.0;
+  }
+}
+
+/// An active inhibition, returned by `Inhibit::inhibit()`.
+///
+/// Dropping the guard closes the request, which releases the inhibition. Errors closing it are ignored,
+/// use `InhibitGuard::release()` to handle them.
+#[must_use = "the inhibition is released as soon as the guard is dropped"]
+pub struct InhibitGuard<'a> {
+  connection: &'a dyn BlockingSender,
+  destination: BusName<'static>,
+  path: Option<Path<'static>>,
+  timeout: Duration,
+}
+
+impl<'a> InhibitGuard<'a> {
+  /// The request path of the inhibition.
+  pub fn path(&self) -> &Path<'static> {
+    self.path.as_ref().expect("path is only taken on drop")
+  }
+
+  /// Releases the inhibition now, returning any error closing the request.
+  pub fn release(mut self) -> Result<(), PortalError> {
+    let path = self.path.take().expect("path is only taken on drop");
+    close_request(self.connection, &self.destination, &path, self.timeout)
+  }
+
+  /// Keeps the inhibition active after the guard is gone, returning its request path.
+  ///
+  /// The inhibition then lasts until the path is passed to `Inhibit::release()`, or the application exits.
+  pub fn leak(mut self) -> Path<'static> {
+    self.path.take().expect("path is only taken on drop")
+  }
+}
+
+impl<'a> Drop for InhibitGuard<'a> {
+  fn drop(&mut self) {
+    if let Some(path) = self.path.take() {
+      let _ = close_request(self.connection, &self.destination, &path, self.timeout);
+    }
+  }
+}
+
+/// Optional arguments for the `Inhibit::inhibit()` method.
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InhibitOptions {
+  handle_token: Option<String>,
+  reason: Option<String>,
+}
+
+impl InhibitOptions {
+  /// Creates a new `InhibitOptions` struct with no arguments set.
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  /// A string that will be used as the last element of the @handle. Must be a valid
+  /// object path element. See the #org.freedesktop.portal.Request documentation for
+  /// more information about the @handle.
+  pub fn handle_token(mut self, handle_token: impl Into<String>) -> Self {
+    self.handle_token = Some(handle_token.into());
+    self
+  }
+
+  /// User-visible reason for the inhibition.
+  pub fn reason(mut self, reason: String) -> Self {
+    self.reason = Some(reason);
+    self
+  }
+}
+
+impl From<InhibitOptions> for PropMap {
+  fn from(options: InhibitOptions) -> Self {
+    let mut map = PropMap::new();
+    if let Some(handle_token) = options.handle_token {
+      map.insert("handle_token".to_string(), Variant(Box::new(handle_token)));
+    }
+    if let Some(reason) = options.reason {
+      map.insert("reason".to_string(), Variant(Box::new(reason)));
+    }
+    map
+  }
+}
+
+/// Optional arguments for the `Inhibit::create_monitor()` method.
+///
+/// The CreateMonitor method was introduced in version 3 of the interface.
+#[cfg(feature = "spec-v3")]
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CreateMonitorOptions {
+  handle_token: Option<String>,
+  session_handle_token: Option<String>,
+}
+
+#[cfg(feature = "spec-v3")]
+impl CreateMonitorOptions {
+  /// Creates a new `CreateMonitorOptions` struct with no arguments set.
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  /// A string that will be used as the last element of the @handle. Must be a valid
+  /// object path element. See the #org.freedesktop.portal.Request documentation for
+  /// more information about the @handle.
+  pub fn handle_token(mut self, handle_token: impl Into<String>) -> Self {
+    self.handle_token = Some(handle_token.into());
+    self
+  }
+
+  /// A string that will be used as the last element of the session handle. Must be a valid
+  /// object path element. See the #org.freedesktop.portal.Session documentation for
+  /// more information about the session handle.
+  pub fn session_handle_token(mut self, session_handle_token: impl Into<String>) -> Self {
+    self.session_handle_token = Some(session_handle_token.into());
+    self
+  }
+
+  /// Returns the `handle_token`, generating one first if it's unset.
+  pub(crate) fn ensure_handle_token(&mut self) -> String {
+    self
+      .handle_token
+      .get_or_insert_with(generate_handle_token)
+      .clone()
+  }
+}
+
+#[cfg(feature = "spec-v3")]
+impl From<CreateMonitorOptions> for PropMap {
+  fn from(options: CreateMonitorOptions) -> Self {
+    let mut map = PropMap::new();
+    if let Some(handle_token) = options.handle_token {
+      map.insert("handle_token".to_string(), Variant(Box::new(handle_token)));
+    }
+    if let Some(session_handle_token) = options.session_handle_token {
+      map.insert(
+        "session_handle_token".to_string(),
+        Variant(Box::new(session_handle_token)),
+      );
+    }
+    map
+  }
+}
+
+/// The state of the login session, as reported to monitoring sessions.
+#[cfg(feature = "spec-v3")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LoginSessionState {
+  /// The session is running.
+  Running,
+  /// The session is about to end. Applications can inhibit logging out,
+  /// and should acknowledge the state with `Inhibit::query_end_response()`.
+  QueryEnd,
+  /// The session is ending.
+  Ending,
+  /// A state this crate doesn't know about.
+  Other(u32),
+}
+
+#[cfg(feature = "spec-v3")]
+impl From<u32> for LoginSessionState {
+  fn from(state: u32) -> Self {
+    match state {
+      1 => Self::Running,
+      2 => Self::QueryEnd,
+      3 => Self::Ending,
+      state => Self::Other(state),
+    }
+  }
+}
+
+/// The state sent by the `StateChanged` signal, see `Inhibit::on_state_changed()`.
+#[cfg(feature = "spec-v3")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SessionState {
+  /// Whether the screensaver is active.
+  pub screensaver_active: bool,
+  /// The state of the login session.
+  pub session_state: LoginSessionState,
+}
+
+#[cfg(feature = "spec-v3")]
+impl TryFrom<&PropMap> for SessionState {
+  type Error = PortalError;
+
+  fn try_from(state: &PropMap) -> Result<Self, Self::Error> {
+    let state = Results::new(state);
+    Ok(Self {
+      screensaver_active: state.required("screensaver-active", Results::get_bool)?,
+      session_state: LoginSessionState::from(state.required("session-state", Results::get_u32)?),
+    })
+  }
+}
+
+impl<'a, T: blocking::BlockingSender + 'a, C: std::ops::Deref<Target = T>> Inhibit
+  for blocking::Proxy<'a, C>
+{
+  fn inhibit(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    flags: InhibitFlags,
+    options: InhibitOptions,
+  ) -> Result<InhibitGuard<'_>, PortalError> {
+    let parent_window: ParentWindowArg = parent_window.into();
+    let options = PropMap::from(options);
+    let _span = CallSpan::enter(
+      INTERFACE,
+      "Inhibit",
+      parent_window.as_str(),
+      trace::handle_token(&options),
+    );
+    let (path,): (Path<'static>,) = self.method_call(
+      INTERFACE,
+      "Inhibit",
+      (parent_window.as_str(), flags.bits(), options),
+    )?;
+    Ok(InhibitGuard {
+      connection: &*self.connection,
+      destination: self.destination.clone().into_static(),
+      path: Some(trace::request_created(path)),
+      timeout: self.timeout,
+    })
+  }
+
+  fn release(&self, handle: &Path<'_>) -> Result<(), PortalError> {
+    close_request(&*self.connection, &self.destination, handle, self.timeout)
+  }
+
+  #[cfg(feature = "spec-v3")]
+  fn create_monitor(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    options: CreateMonitorOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    let parent_window: ParentWindowArg = parent_window.into();
+    let options = PropMap::from(options);
+    let _span = CallSpan::enter(
+      INTERFACE,
+      "CreateMonitor",
+      parent_window.as_str(),
+      trace::handle_token(&options),
+    );
+    self
+      .method_call(
+        INTERFACE,
+        "CreateMonitor",
+        (parent_window.as_str(), options),
+      )
+      .map(|r: (Path<'static>,)| trace::request_created(r.0))
+      .map_err(Into::into)
+  }
+
+  #[cfg(feature = "spec-v3")]
+  fn create_monitor_and_wait(
+    &self,
+    connection: &Connection,
+    parent_window: impl Into<ParentWindowArg>,
+    mut options: CreateMonitorOptions,
+    timeout: Duration,
+  ) -> Result<Session, PortalError> {
+    let parent_window: ParentWindowArg = parent_window.into();
+    let listener = ResponseListener::for_call(
+      connection,
+      &self.destination,
+      INTERFACE,
+      "CreateMonitor",
+      parent_window.as_str(),
+      &options.ensure_handle_token(),
+    )?;
+    let path = self.create_monitor(parent_window, options)?;
+    let session = Session::from_results(&listener.wait_success(&path, timeout)?)?;
+    Ok(session.with_destination(self.destination.clone().into_static()))
+  }
+
+  #[cfg(feature = "spec-v3")]
+  fn query_end_response(&self, session: &Session) -> Result<(), PortalError> {
+    self
+      .method_call(INTERFACE, "QueryEndResponse", (session.path(),))
+      .map_err(Into::into)
+  }
+
+  #[cfg(feature = "spec-v3")]
+  fn on_state_changed<'c, F>(
+    &self,
+    connection: &'c Connection,
+    mut callback: F,
+  ) -> Result<SignalGuard<'c>, PortalError>
+  where
+    F: FnMut(Session, SessionState) + Send + 'static,
+  {
+    SignalGuard::for_events(
+      connection,
+      signal_rule(self, INTERFACE, "StateChanged"),
+      move |event| {
+        if let PortalEvent::InhibitStateChanged { session, state } = event {
+          callback(session, state);
+        }
+      },
+    )
+  }
+
+  fn version(&self) -> Result<u32, PortalError> {
+    <Self as org_freedesktop_dbus::Properties>::get(self, INTERFACE, "version").map_err(Into::into)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::InhibitFlags;
+  #[cfg(feature = "spec-v3")]
+  use super::{LoginSessionState, SessionState};
+  #[cfg(feature = "spec-v3")]
+  use dbus::arg::{PropMap, Variant};
+
+  #[test]
+  fn inhibit_flags_bits() {
+    let flags = InhibitFlags::SUSPEND | InhibitFlags::IDLE;
+    assert_eq!(flags.bits(), 12);
+    assert!(flags.contains(InhibitFlags::IDLE));
+    assert!(!flags.contains(InhibitFlags::LOGOUT));
+  }
+
+  #[test]
+  #[cfg(feature = "spec-v3")]
+  fn session_state_from_map() {
+    let mut map = PropMap::new();
+    map.insert("screensaver-active".to_string(), Variant(Box::new(true)));
+    map.insert("session-state".to_string(), Variant(Box::new(2u32)));
+    assert_eq!(
+      SessionState::try_from(&map).unwrap(),
+      SessionState {
+        screensaver_active: true,
+        session_state: LoginSessionState::QueryEnd,
+      }
+    );
+
+    map.remove("session-state");
+    assert!(SessionState::try_from(&map).is_err());
+  }
+}
@@ -0,0 +1,698 @@
+// Copyright 2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::{
+  signal_rule,
+  trace::{self, CallSpan},
+  ParentWindowArg, PortalError, PortalEvent, Results, Session, SignalGuard,
+};
+
+use dbus::{
+  arg::{OwnedFd, PropMap, RefArg, Variant},
+  blocking::{self, stdintf::org_freedesktop_dbus, Connection},
+  Path,
+};
+use std::ops::{BitOr, BitOrAssign};
+
+const INTERFACE: &str = "org.freedesktop.portal.InputCapture";
+
+/// Implementation of the `org.freedesktop.portal.InputCapture` Portal API.
+/// See also https://flatpak.github.io/xdg-desktop-portal/#gdbus-org.freedesktop.portal.InputCapture
+///
+/// An input capture goes through these steps:
+/// 1. `InputCapture::create_session()`, then read the session with `Session::from_results()`.
+/// 2. `InputCapture::get_zones()`, see `parse_zones()`, and `InputCapture::set_pointer_barriers()`
+///    on the edges of the zones.
+/// 3. `InputCapture::connect_to_eis()` to receive the captured input events, and `InputCapture::enable()`.
+/// 4. Input is captured once the pointer crosses a barrier, as announced by the `Activated` signal,
+///    until the application calls `InputCapture::release()`.
+pub trait InputCapture {
+  /// Creates an input capture session.
+  ///
+  /// The `session_handle` and the granted `capabilities` are returned in the results of the `Response` signal.
+  ///
+  /// - `parent_window`: Identifier for the application window, see crate comments for common conventions.
+  fn create_session(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    options: CaptureSessionOptions,
+  ) -> Result<Path<'static>, PortalError>;
+
+  /// Requests the current zones, the regions of the desktop pointer barriers can be placed on.
+  ///
+  /// The `zones` and `zone_set` are returned in the results of the `Response` signal, see `parse_zones()`.
+  fn get_zones(
+    &self,
+    session: &Session,
+    options: InputCaptureOptions,
+  ) -> Result<Path<'static>, PortalError>;
+
+  /// Sets the pointer barriers, which trigger the capture when the pointer crosses them.
+  /// Replaces all barriers set before.
+  ///
+  /// The `failed_barriers` are returned in the results of the `Response` signal, see `parse_failed_barriers()`.
+  ///
+  /// - `zone_set`: The `zone_set` of the zones the barriers were placed on, from `InputCapture::get_zones()`.
+  fn set_pointer_barriers(
+    &self,
+    session: &Session,
+    options: InputCaptureOptions,
+    barriers: Vec<Barrier>,
+    zone_set: u32,
+  ) -> Result<Path<'static>, PortalError>;
+
+  /// Enables the capture of input, once the pointer crosses a barrier.
+  ///
+  /// - `options`: Vardict with optional further information. There are currently no options.
+  fn enable(&self, session: &Session, options: PropMap) -> Result<(), PortalError>;
+
+  /// Disables the capture of input, also releasing a capture in progress.
+  ///
+  /// - `options`: Vardict with optional further information. There are currently no options.
+  fn disable(&self, session: &Session, options: PropMap) -> Result<(), PortalError>;
+
+  /// Releases a capture in progress, input is captured again the next time a barrier is crossed.
+  fn release(&self, session: &Session, options: ReleaseOptions) -> Result<(), PortalError>;
+
+  /// Opens a file descriptor to the EIS implementation, which sends the captured input events.
+  ///
+  /// - `options`: Vardict with optional further information. There are currently no options.
+  fn connect_to_eis(&self, session: &Session, options: PropMap) -> Result<OwnedFd, PortalError>;
+
+  /// Subscribes to the `Disabled` signal, emitted when the capture was disabled by the portal.
+  ///
+  /// The callback receives the session, and a map of further details.
+  fn on_disabled<'c, F>(
+    &self,
+    connection: &'c Connection,
+    callback: F,
+  ) -> Result<SignalGuard<'c>, PortalError>
+  where
+    F: FnMut(Session, PropMap) + Send + 'static;
+
+  /// Subscribes to the `Activated` signal, emitted when input starts being captured.
+  fn on_activated<'c, F>(
+    &self,
+    connection: &'c Connection,
+    callback: F,
+  ) -> Result<SignalGuard<'c>, PortalError>
+  where
+    F: FnMut(Session, CaptureActivation) + Send + 'static;
+
+  /// Subscribes to the `Deactivated` signal, emitted when input stops being captured.
+  fn on_deactivated<'c, F>(
+    &self,
+    connection: &'c Connection,
+    callback: F,
+  ) -> Result<SignalGuard<'c>, PortalError>
+  where
+    F: FnMut(Session, CaptureActivation) + Send + 'static;
+
+  /// Subscribes to the `ZonesChanged` signal, emitted when the zones changed and the barriers
+  /// must be set again.
+  ///
+  /// The callback receives the session, and the `zone_set` that is no longer valid.
+  fn on_zones_changed<'c, F>(
+    &self,
+    connection: &'c Connection,
+    callback: F,
+  ) -> Result<SignalGuard<'c>, PortalError>
+  where
+    F: FnMut(Session, Option<u32>) + Send + 'static;
+
+  /// Reads the "SupportedCapabilities" property, the capabilities that can be requested.
+  fn supported_capabilities(&self) -> Result<Capabilities, PortalError>;
+
+  /// Reads the "version" property for this D-Bus interface.
+  fn version(&self) -> Result<u32, PortalError>;
+}
+
+/// Types of input devices to capture. Types can be combined with `|`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Capabilities(u32);
+
+impl Capabilities {
+  /// Capture keyboards.
+  pub const KEYBOARD: Self = Self(1);
+  /// Capture pointer devices.
+  pub const POINTER: Self = Self(2);
+  /// Capture touchscreens.
+  pub const TOUCHSCREEN: Self = Self(4);
+
+  /// No types set.
+  pub fn empty() -> Self {
+    Self(0)
+  }
+
+  /// Creates a `Capabilities` from the raw bits sent by the portal.
+  pub fn from_bits(bits: u32) -> Self {
+    Self(bits)
+  }
+
+  /// The raw bits, as sent to the portal.
+  pub fn bits(&self) -> u32 {
+    self.0
+  }
+
+  /// Whether all types in `other` are set.
+  pub fn contains(&self, other: Self) -> bool {
+    self.0 & other.0 == other.0
+  }
+}
+
+impl BitOr for Capabilities {
+  type Output = Self;
+
+  fn bitor(self, rhs: Self) -> Self {
+    Self(self.0 | rhs.0)
+  }
+}
+
+impl BitOrAssign for Capabilities {
+  fn bitor_assign(&mut self, rhs: Self) {
+    self.0 |= rhs.0;
+  }
+}
+
+/// Optional arguments for the `InputCapture::create_session()` method.
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CaptureSessionOptions {
+  handle_token: Option<String>,
+  session_handle_token: Option<String>,
+  capabilities: Option<Capabilities>,
+}
+
+impl CaptureSessionOptions {
+  /// Creates a new `CaptureSessionOptions` struct with no arguments set.
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  /// A string that will be used as the last element of the @handle. Must be a valid
+  /// object path element. See the #org.freedesktop.portal.Request documentation for
+  /// more information about the @handle.
+  pub fn handle_token(mut self, handle_token: impl Into<String>) -> Self {
+    self.handle_token = Some(handle_token.into());
+    self
+  }
+
+  /// A string that will be used as the last element of the session handle. Must be a valid
+  /// object path element. See the #org.freedesktop.portal.Session documentation for
+  /// more information about the session handle.
+  pub fn session_handle_token(mut self, session_handle_token: impl Into<String>) -> Self {
+    self.session_handle_token = Some(session_handle_token.into());
+    self
+  }
+
+  /// The types of input devices to capture.
+  pub fn capabilities(mut self, capabilities: Capabilities) -> Self {
+    self.capabilities = Some(capabilities);
+    self
+  }
+}
+
+impl From<CaptureSessionOptions> for PropMap {
+  fn from(options: CaptureSessionOptions) -> Self {
+    let mut map = PropMap::new();
+    if let Some(handle_token) = options.handle_token {
+      map.insert("handle_token".to_string(), Variant(Box::new(handle_token)));
+    }
+    if let Some(session_handle_token) = options.session_handle_token {
+      map.insert(
+        "session_handle_token".to_string(),
+        Variant(Box::new(session_handle_token)),
+      );
+    }
+    if let Some(capabilities) = options.capabilities {
+      map.insert(
+        "capabilities".to_string(),
+        Variant(Box::new(capabilities.bits())),
+      );
+    }
+    map
+  }
+}
+
+/// Optional arguments for the `InputCapture::get_zones()` and `InputCapture::set_pointer_barriers()` methods.
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InputCaptureOptions {
+  handle_token: Option<String>,
+}
+
+impl InputCaptureOptions {
+  /// Creates a new `InputCaptureOptions` struct with no arguments set.
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  /// A string that will be used as the last element of the @handle. Must be a valid
+  /// object path element. See the #org.freedesktop.portal.Request documentation for
+  /// more information about the @handle.
+  pub fn handle_token(mut self, handle_token: impl Into<String>) -> Self {
+    self.handle_token = Some(handle_token.into());
+    self
+  }
+}
+
+impl From<InputCaptureOptions> for PropMap {
+  fn from(options: InputCaptureOptions) -> Self {
+    let mut map = PropMap::new();
+    if let Some(handle_token) = options.handle_token {
+      map.insert("handle_token".to_string(), Variant(Box::new(handle_token)));
+    }
+    map
+  }
+}
+
+/// Optional arguments for the `InputCapture::release()` method.
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReleaseOptions {
+  activation_id: Option<u32>,
+  cursor_position: Option<(f64, f64)>,
+}
+
+impl ReleaseOptions {
+  /// Creates a new `ReleaseOptions` struct with no arguments set.
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  /// The `activation_id` of the capture to release, from the `Activated` signal.
+  pub fn activation_id(mut self, activation_id: u32) -> Self {
+    self.activation_id = Some(activation_id);
+    self
+  }
+
+  /// Where to put the pointer once the capture is released, in the compositor coordinate space.
+  pub fn cursor_position(mut self, cursor_position: (f64, f64)) -> Self {
+    self.cursor_position = Some(cursor_position);
+    self
+  }
+}
+
+impl From<ReleaseOptions> for PropMap {
+  fn from(options: ReleaseOptions) -> Self {
+    let mut map = PropMap::new();
+    if let Some(activation_id) = options.activation_id {
+      map.insert(
+        "activation_id".to_string(),
+        Variant(Box::new(activation_id)),
+      );
+    }
+    if let Some(cursor_position) = options.cursor_position {
+      map.insert(
+        "cursor_position".to_string(),
+        Variant(Box::new(cursor_position)),
+      );
+    }
+    map
+  }
+}
+
+/// A pointer barrier to set with `InputCapture::set_pointer_barriers()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Barrier {
+  /// Application-provided identifier, sent back by the `Activated` signal. Must not be 0.
+  pub id: u32,
+  /// The `(x1, y1, x2, y2)` of the barrier, a horizontal or vertical line on the edge of a zone.
+  pub position: (i32, i32, i32, i32),
+}
+
+impl Barrier {
+  /// The wire format of a barrier, `a{sv}`.
+  fn into_arg(self) -> PropMap {
+    let mut map = PropMap::new();
+    map.insert("barrier_id".to_string(), Variant(Box::new(self.id)));
+    map.insert("position".to_string(), Variant(Box::new(self.position)));
+    map
+  }
+}
+
+/// A region of the desktop, as returned by `parse_zones()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Zone {
+  /// The width of the zone.
+  pub width: u32,
+  /// The height of the zone.
+  pub height: u32,
+  /// The x offset of the zone in the compositor coordinate space.
+  pub x: i32,
+  /// The y offset of the zone in the compositor coordinate space.
+  pub y: i32,
+}
+
+/// The zones returned by `InputCapture::get_zones()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Zones {
+  /// The zones.
+  pub zones: Vec<Zone>,
+  /// Identifies this set of zones, see `InputCapture::set_pointer_barriers()`.
+  pub zone_set: u32,
+}
+
+/// Reads the `Zones` from the results of a successful `InputCapture::get_zones()` response.
+pub fn parse_zones(results: &PropMap) -> Result<Zones, PortalError> {
+  let zones = results
+    .get("zones")
+    .ok_or_else(|| PortalError::UnexpectedResponse("missing result `zones`".to_string()))?;
+  let parsed = zones
+    .0
+    .as_iter()
+    .and_then(|zones| zones.map(parse_zone).collect::<Option<Vec<_>>>());
+  Ok(Zones {
+    zones: parsed.ok_or_else(|| PortalError::TypeMismatch {
+      key: "zones".to_string(),
+      expected: "a(uuii)",
+      found: zones.0.signature().to_string(),
+    })?,
+    zone_set: Results::new(results).required("zone_set", Results::get_u32)?,
+  })
+}
+
+fn parse_zone(zone: &dyn RefArg) -> Option<Zone> {
+  let mut fields = zone.as_iter()?;
+  Some(Zone {
+    width: fields.next()?.as_u64()? as u32,
+    height: fields.next()?.as_u64()? as u32,
+    x: fields.next()?.as_i64()? as i32,
+    y: fields.next()?.as_i64()? as i32,
+  })
+}
+
+/// Reads the ids of the `failed_barriers` from the results of a successful
+/// `InputCapture::set_pointer_barriers()` response.
+pub fn parse_failed_barriers(results: &PropMap) -> Result<Vec<u32>, PortalError> {
+  let value = results.get("failed_barriers").ok_or_else(|| {
+    PortalError::UnexpectedResponse("missing result `failed_barriers`".to_string())
+  })?;
+  let ids = value.0.as_iter().and_then(|ids| {
+    ids
+      .map(|id| id.as_u64().map(|id| id as u32))
+      .collect::<Option<Vec<_>>>()
+  });
+  ids.ok_or_else(|| PortalError::TypeMismatch {
+    key: "failed_barriers".to_string(),
+    expected: "au",
+    found: value.0.signature().to_string(),
+  })
+}
+
+/// The details of the `Activated` and `Deactivated` signals.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CaptureActivation {
+  /// Identifies the capture, see `ReleaseOptions::activation_id()`.
+  pub activation_id: Option<u32>,
+  /// The position of the pointer in the compositor coordinate space.
+  pub cursor_position: Option<(f64, f64)>,
+  /// The id of the barrier that was crossed, only sent by the `Activated` signal.
+  pub barrier_id: Option<u32>,
+}
+
+impl From<&PropMap> for CaptureActivation {
+  fn from(options: &PropMap) -> Self {
+    let results = Results::new(options);
+    let get_u32 = |key: &str| results.get_u32(key).ok().flatten();
+    let cursor_position = options.get("cursor_position").and_then(|value| {
+      let mut fields = value.0.as_iter()?;
+      Some((fields.next()?.as_f64()?, fields.next()?.as_f64()?))
+    });
+    Self {
+      activation_id: get_u32("activation_id"),
+      cursor_position,
+      barrier_id: get_u32("barrier_id"),
+    }
+  }
+}
+
+impl<'a, T: blocking::BlockingSender, C: std::ops::Deref<Target = T>> InputCapture
+  for blocking::Proxy<'a, C>
+{
+  fn create_session(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    options: CaptureSessionOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    let parent_window: ParentWindowArg = parent_window.into();
+    let options = PropMap::from(options);
+    let _span = CallSpan::enter(
+      INTERFACE,
+      "CreateSession",
+      parent_window.as_str(),
+      trace::handle_token(&options),
+    );
+    self
+      .method_call(
+        INTERFACE,
+        "CreateSession",
+        (parent_window.as_str(), options),
+      )
+      .map(|r: (Path<'static>,)| trace::request_created(r.0))
+      .map_err(Into::into)
+  }
+
+  fn get_zones(
+    &self,
+    session: &Session,
+    options: InputCaptureOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    let options = PropMap::from(options);
+    let _span = CallSpan::enter(INTERFACE, "GetZones", "", trace::handle_token(&options));
+    self
+      .method_call(INTERFACE, "GetZones", (session.path(), options))
+      .map(|r: (Path<'static>,)| trace::request_created(r.0))
+      .map_err(Into::into)
+  }
+
+  fn set_pointer_barriers(
+    &self,
+    session: &Session,
+    options: InputCaptureOptions,
+    barriers: Vec<Barrier>,
+    zone_set: u32,
+  ) -> Result<Path<'static>, PortalError> {
+    let barriers: Vec<_> = barriers.into_iter().map(Barrier::into_arg).collect();
+    let options = PropMap::from(options);
+    let _span = CallSpan::enter(
+      INTERFACE,
+      "SetPointerBarriers",
+      "",
+      trace::handle_token(&options),
+    );
+    self
+      .method_call(
+        INTERFACE,
+        "SetPointerBarriers",
+        (session.path(), options, barriers, zone_set),
+      )
+      .map(|r: (Path<'static>,)| trace::request_created(r.0))
+      .map_err(Into::into)
+  }
+
+  fn enable(&self, session: &Session, options: PropMap) -> Result<(), PortalError> {
+    self
+      .method_call(INTERFACE, "Enable", (session.path(), options))
+      .map_err(Into::into)
+  }
+
+  fn disable(&self, session: &Session, options: PropMap) -> Result<(), PortalError> {
+    self
+      .method_call(INTERFACE, "Disable", (session.path(), options))
+      .map_err(Into::into)
+  }
+
+  fn release(&self, session: &Session, options: ReleaseOptions) -> Result<(), PortalError> {
+    self
+      .method_call(
+        INTERFACE,
+        "Release",
+        (session.path(), PropMap::from(options)),
+      )
+      .map_err(Into::into)
+  }
+
+  fn connect_to_eis(&self, session: &Session, options: PropMap) -> Result<OwnedFd, PortalError> {
+    self
+      .method_call(INTERFACE, "ConnectToEIS", (session.path(), options))
+      .map(|r: (OwnedFd,)| r.0)
+      .map_err(Into::into)
+  }
+
+  fn on_disabled<'c, F>(
+    &self,
+    connection: &'c Connection,
+    mut callback: F,
+  ) -> Result<SignalGuard<'c>, PortalError>
+  where
+    F: FnMut(Session, PropMap) + Send + 'static,
+  {
+    SignalGuard::for_events(
+      connection,
+      signal_rule(self, INTERFACE, "Disabled"),
+      move |event| {
+        if let PortalEvent::CaptureDisabled { session, options } = event {
+          callback(session, options);
+        }
+      },
+    )
+  }
+
+  fn on_activated<'c, F>(
+    &self,
+    connection: &'c Connection,
+    mut callback: F,
+  ) -> Result<SignalGuard<'c>, PortalError>
+  where
+    F: FnMut(Session, CaptureActivation) + Send + 'static,
+  {
+    SignalGuard::for_events(
+      connection,
+      signal_rule(self, INTERFACE, "Activated"),
+      move |event| {
+        if let PortalEvent::CaptureActivated {
+          session,
+          activation,
+        } = event
+        {
+          callback(session, activation);
+        }
+      },
+    )
+  }
+
+  fn on_deactivated<'c, F>(
+    &self,
+    connection: &'c Connection,
+    mut callback: F,
+  ) -> Result<SignalGuard<'c>, PortalError>
+  where
+    F: FnMut(Session, CaptureActivation) + Send + 'static,
+  {
+    SignalGuard::for_events(
+      connection,
+      signal_rule(self, INTERFACE, "Deactivated"),
+      move |event| {
+        if let PortalEvent::CaptureDeactivated {
+          session,
+          activation,
+        } = event
+        {
+          callback(session, activation);
+        }
+      },
+    )
+  }
+
+  fn on_zones_changed<'c, F>(
+    &self,
+    connection: &'c Connection,
+    mut callback: F,
+  ) -> Result<SignalGuard<'c>, PortalError>
+  where
+    F: FnMut(Session, Option<u32>) + Send + 'static,
+  {
+    SignalGuard::for_events(
+      connection,
+      signal_rule(self, INTERFACE, "ZonesChanged"),
+      move |event| {
+        if let PortalEvent::ZonesChanged { session, zone_set } = event {
+          callback(session, zone_set);
+        }
+      },
+    )
+  }
+
+  fn supported_capabilities(&self) -> Result<Capabilities, PortalError> {
+    <Self as org_freedesktop_dbus::Properties>::get(self, INTERFACE, "SupportedCapabilities")
+      .map(Capabilities::from_bits)
+      .map_err(Into::into)
+  }
+
+  fn version(&self) -> Result<u32, PortalError> {
+    <Self as org_freedesktop_dbus::Properties>::get(self, INTERFACE, "version").map_err(Into::into)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::{parse_failed_barriers, parse_zones, Barrier, CaptureActivation, Zone};
+  use crate::PortalError;
+  use dbus::arg::{prop_cast, PropMap, Variant};
+
+  #[test]
+  fn barrier_arg() {
+    let map = Barrier {
+      id: 1,
+      position: (0, 0, 0, 1080),
+    }
+    .into_arg();
+    assert_eq!(prop_cast::<u32>(&map, "barrier_id"), Some(&1));
+    assert_eq!(
+      prop_cast::<(i32, i32, i32, i32)>(&map, "position"),
+      Some(&(0, 0, 0, 1080))
+    );
+  }
+
+  #[test]
+  fn parse_zones_results() {
+    let mut results = PropMap::new();
+    results.insert(
+      "zones".to_string(),
+      Variant(Box::new(vec![(1920u32, 1080u32, 0i32, 0i32)])),
+    );
+    results.insert("zone_set".to_string(), Variant(Box::new(3u32)));
+    let zones = parse_zones(&results).unwrap();
+    assert_eq!(
+      zones.zones,
+      [Zone {
+        width: 1920,
+        height: 1080,
+        x: 0,
+        y: 0
+      }]
+    );
+    assert_eq!(zones.zone_set, 3);
+
+    results.insert("zones".to_string(), Variant(Box::new(1u32)));
+    assert!(matches!(
+      parse_zones(&results),
+      Err(PortalError::TypeMismatch { .. })
+    ));
+  }
+
+  #[test]
+  fn parse_failed_barriers_results() {
+    let mut results = PropMap::new();
+    results.insert(
+      "failed_barriers".to_string(),
+      Variant(Box::new(vec![2u32, 5u32])),
+    );
+    assert_eq!(parse_failed_barriers(&results).unwrap(), [2, 5]);
+  }
+
+  #[test]
+  fn capture_activation_from_options() {
+    let mut options = PropMap::new();
+    options.insert("activation_id".to_string(), Variant(Box::new(7u32)));
+    options.insert(
+      "cursor_position".to_string(),
+      Variant(Box::new((10.5f64, 20.0f64))),
+    );
+    assert_eq!(
+      CaptureActivation::from(&options),
+      CaptureActivation {
+        activation_id: Some(7),
+        cursor_position: Some((10.5, 20.0)),
+        barrier_id: None,
+      }
+    );
+  }
+}
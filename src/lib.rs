@@ -7,36 +7,502 @@
 //! Most portals interact with the user by showing dialogs. These dialogs should generally be placed on top of the application window that triggered them. To arrange this, the compositor needs to know about the application window. Many portal requests expect a "parent_window" string argument for this reason.
 //! Under X11, the "parent_window" argument should have the form "x11:XID", where XID is the XID of the application window in hexadecimal notation.
 //! Under Wayland, it should have the form "wayland:HANDLE", where HANDLE is a surface handle obtained with the xdg_foreign protocol.
+//! Newer prefixes may be added to the specification, these can be passed through as they are, see `ParentWindow::Handle`.
 //! For other windowing systems, or if you don't have a suitable handle, just pass an empty string for "parent_window".
+//!
+//! The portal methods accept both a raw identifier string and a `ParentWindow`, which renders these forms.
+//!
+//! Backends
+//!
+//! The portals are called through the `dbus` crate, which links libdbus, enabled by the default `dbus` feature.
+//!
+//! The `zbus` feature only covers the OpenURI portal so far, with `ZbusOpenURI` on a blocking connection and
+//! `AsyncZbusOpenURI` on an async one. Applications that already use `zbus` and only open uris and files can disable
+//! the default features and enable `zbus` instead; every other portal still needs the `dbus` feature.
+//! `OpenURIOptions` is shared by both backends.
+//!
+//! Serialization
+//!
+//! With the `serde` feature, the options structs and the results read from portals implement `Serialize` and `Deserialize`,
+//! so choices like a ScreenCast `restore_token` or the last used FileChooser filters can be persisted.
+//! Most of them round-trip unchanged. The exceptions hold values that can't be represented outside of D-Bus:
+//! - File descriptors aren't serialized. `EmailOptions` attachments are left out, and serializing an `Icon::File` or
+//!   `Sound::File` fails.
+//! - `Variant` values, in `SettingValue::Other` and the action targets of a `Notification`, are serialized as the plain
+//!   value they hold. They are deserialized into the closest D-Bus type, so a `u` value comes back as a `t` value,
+//!   and a struct as an `av` array.
 
 #![warn(missing_docs, rust_2018_idioms)]
 
+#[cfg(feature = "dbus")]
+mod account;
+#[cfg(feature = "dbus")]
+mod background;
+#[cfg(feature = "dbus")]
+mod camera;
+mod common;
+#[cfg(feature = "dbus")]
+mod device;
+#[cfg(feature = "dbus")]
+mod documents;
+#[cfg(feature = "dbus")]
+mod dynamic_launcher;
+#[cfg(feature = "dbus")]
+mod email;
+mod error;
+#[cfg(feature = "dbus")]
+mod fd;
+#[cfg(feature = "dbus")]
+mod file_chooser;
+#[cfg(feature = "dbus")]
+mod global_shortcuts;
+#[cfg(feature = "dbus")]
+mod handles;
+#[cfg(feature = "dbus")]
+mod inhibit;
+#[cfg(feature = "dbus")]
+mod input_capture;
+#[cfg(feature = "dbus")]
+mod location;
+#[cfg(feature = "dbus")]
+mod memory_monitor;
+#[cfg(feature = "dbus")]
+mod network_monitor;
+#[cfg(feature = "async")]
+mod nonblock;
+#[cfg(feature = "dbus")]
+mod notification;
 mod open_uri;
+pub mod prelude;
+#[cfg(feature = "dbus")]
+mod print;
+#[cfg(feature = "dbus")]
+mod proxy_resolver;
+#[cfg(feature = "dbus")]
+mod realtime;
+#[cfg(feature = "dbus")]
+mod remote_desktop;
+mod request;
+#[cfg(feature = "dbus")]
+mod results;
+#[cfg(feature = "dbus")]
+mod screen_cast;
+#[cfg(feature = "dbus")]
+mod screenshot;
+#[cfg(feature = "dbus")]
+mod secret;
+#[cfg(all(feature = "dbus", feature = "serde"))]
+mod serde_support;
+#[cfg(feature = "dbus")]
+mod session;
+#[cfg(feature = "dbus")]
+mod settings;
+#[cfg(feature = "dbus")]
+mod signal;
+#[cfg(feature = "testing")]
+pub mod testing;
+mod trace;
+#[cfg(feature = "dbus")]
+mod wallpaper;
+#[cfg(feature = "wayland")]
+mod wayland;
+#[cfg(feature = "zbus")]
+mod zbus_backend;
 
+#[cfg(feature = "dbus")]
+pub use account::*;
+#[cfg(feature = "dbus")]
+pub use background::*;
+#[cfg(feature = "dbus")]
+pub use camera::*;
+pub use common::*;
+#[cfg(feature = "dbus")]
 pub use dbus;
+#[cfg(feature = "dbus")]
+pub use device::*;
+#[cfg(feature = "dbus")]
+pub use documents::*;
+#[cfg(feature = "dbus")]
+pub use dynamic_launcher::*;
+#[cfg(feature = "dbus")]
+pub use email::*;
+pub use error::*;
+#[cfg(feature = "dbus")]
+pub use fd::IntoPortalFd;
+#[cfg(feature = "dbus")]
+pub use file_chooser::*;
+#[cfg(feature = "dbus")]
+pub use global_shortcuts::*;
+#[cfg(feature = "dbus")]
+pub use handles::*;
+#[cfg(feature = "dbus")]
+pub use inhibit::*;
+#[cfg(feature = "dbus")]
+pub use input_capture::*;
+#[cfg(feature = "dbus")]
+pub use location::*;
+#[cfg(feature = "dbus")]
+pub use memory_monitor::*;
+#[cfg(feature = "dbus")]
+pub use network_monitor::*;
+#[cfg(feature = "async")]
+pub use nonblock::*;
+#[cfg(feature = "dbus")]
+pub use notification::*;
 pub use open_uri::*;
+#[cfg(feature = "dbus")]
+pub use print::*;
+#[cfg(feature = "dbus")]
+pub use proxy_resolver::*;
+#[cfg(feature = "dbus")]
+pub use realtime::*;
+#[cfg(feature = "dbus")]
+pub use remote_desktop::*;
+pub use request::*;
+#[cfg(feature = "dbus")]
+pub use results::*;
+#[cfg(feature = "dbus")]
+pub use screen_cast::*;
+#[cfg(feature = "dbus")]
+pub use screenshot::*;
+#[cfg(feature = "dbus")]
+pub use secret::*;
+#[cfg(feature = "dbus")]
+pub use session::*;
+#[cfg(feature = "dbus")]
+pub use settings::*;
+#[cfg(feature = "dbus")]
+pub use signal::*;
+#[cfg(feature = "dbus")]
+pub use wallpaper::*;
+#[cfg(feature = "wayland")]
+pub use wayland::*;
+#[cfg(feature = "zbus")]
+pub use zbus_backend::*;
 
-use dbus::blocking::{BlockingSender, Proxy};
+#[cfg(feature = "dbus")]
+use dbus::blocking::{stdintf::org_freedesktop_dbus::Peer, BlockingSender, Connection, Proxy};
+use std::fmt;
+#[cfg(feature = "dbus")]
 use std::{ops::Deref, time::Duration};
 
+/// The well-known bus name of the portal service.
+#[cfg(any(feature = "dbus", feature = "zbus"))]
+pub(crate) const DESTINATION: &str = "org.freedesktop.portal.Desktop";
+
+/// The object path the portal interfaces are exported on.
+#[cfg(any(feature = "dbus", feature = "zbus"))]
+pub(crate) const PATH: &str = "/org/freedesktop/portal/desktop";
+
+/// The well-known bus name of the document portal service.
+#[cfg(feature = "dbus")]
+pub(crate) const DOCUMENTS_DESTINATION: &str = "org.freedesktop.portal.Documents";
+
+/// The object path the document portal interface is exported on.
+#[cfg(feature = "dbus")]
+pub(crate) const DOCUMENTS_PATH: &str = "/org/freedesktop/portal/documents";
+
+/// Identifier for an application window, used to place portal dialogs on top of it.
+/// See crate comments for the conventions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ParentWindow {
+  /// An X11 window, by its XID.
+  X11(u64),
+  /// A Wayland surface, by the handle obtained with the xdg_foreign protocol.
+  /// With the `wayland` feature, `ParentWindow::export_wayland()` performs the export.
+  Wayland(String),
+  /// A complete identifier, including its prefix, sent as it is.
+  /// For forms this crate doesn't know about yet.
+  Handle(String),
+  /// No suitable window.
+  None,
+}
+
+impl fmt::Display for ParentWindow {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::X11(xid) => write!(f, "x11:{:#x}", xid),
+      Self::Wayland(handle) => write!(f, "wayland:{}", handle),
+      Self::Handle(handle) => f.write_str(handle),
+      Self::None => Ok(()),
+    }
+  }
+}
+
+/// The `parent_window` argument of the portal methods,
+/// created from either a raw identifier string or a `ParentWindow`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParentWindowArg(String);
+
+impl ParentWindowArg {
+  /// The identifier, as sent to the portal.
+  pub fn as_str(&self) -> &str {
+    &self.0
+  }
+}
+
+impl From<&str> for ParentWindowArg {
+  fn from(parent_window: &str) -> Self {
+    Self(parent_window.to_string())
+  }
+}
+
+impl From<String> for ParentWindowArg {
+  fn from(parent_window: String) -> Self {
+    Self(parent_window)
+  }
+}
+
+impl From<ParentWindow> for ParentWindowArg {
+  fn from(parent_window: ParentWindow) -> Self {
+    Self(parent_window.to_string())
+  }
+}
+
+impl From<&ParentWindow> for ParentWindowArg {
+  fn from(parent_window: &ParentWindow) -> Self {
+    Self(parent_window.to_string())
+  }
+}
+
+/// The future returned by the methods of the async portal traits, such as `AsyncOpenURI` and `AsyncZbusOpenURI`.
+#[cfg(any(feature = "async", feature = "zbus"))]
+pub type PortalFuture<'a, T> =
+  std::pin::Pin<Box<dyn std::future::Future<Output = Result<T, PortalError>> + Send + 'a>>;
+
 /// Creates a new `dbus::blocking::Proxy` targetting the `org.freedesktop.portal.Desktop` bus.
 /// Can be used with any of the Traits to call Portal API methods.
+#[cfg(feature = "dbus")]
 pub fn new_blocking<'a, B: BlockingSender, C: Deref<Target = B>>(
   timeout: Duration,
   connection: C,
 ) -> Proxy<'a, C> {
+  new_blocking_with(DESTINATION, PATH, timeout, connection)
+}
+
+/// Opens a new connection to the session bus, and creates a `dbus::blocking::Proxy` owning it,
+/// targetting the `org.freedesktop.portal.Desktop` bus.
+///
+/// Meant for programs that don't share the bus with anything else. The connection stays available as
+/// `proxy.connection`, for the methods that wait for a response or subscribe to signals.
+#[cfg(feature = "dbus")]
+pub fn new_blocking_owned(
+  timeout: Duration,
+) -> Result<Proxy<'static, Box<Connection>>, PortalError> {
+  Ok(new_blocking(timeout, Box::new(Connection::new_session()?)))
+}
+
+/// Opens a new connection to the system bus, and creates a `dbus::blocking::Proxy` owning it,
+/// targetting the `org.freedesktop.portal.Desktop` bus.
+///
+/// The portals are normally served on the session bus, see `new_blocking()`. For services on the system bus
+/// with another name, pass a `Connection::new_system()` connection to `new_blocking_with()` instead.
+#[cfg(feature = "dbus")]
+pub fn new_blocking_system(
+  timeout: Duration,
+) -> Result<Proxy<'static, Box<Connection>>, PortalError> {
+  Ok(new_blocking(timeout, Box::new(Connection::new_system()?)))
+}
+
+/// Creates a new `dbus::blocking::Proxy` targetting the portal object at `path` of the `destination` bus,
+/// for portals served on another well-known name, or a fake portal in tests.
+///
+/// The `_and_wait` methods of the portals match the responses sent by `destination`. Set it on the `Request`s
+/// and `Session`s made from the returned paths with `Request::with_destination()` and `Session::with_destination()`.
+///
+/// Panics if `destination` isn't a valid bus name, or `path` isn't a valid object path.
+#[cfg(feature = "dbus")]
+pub fn new_blocking_with<'a, B: BlockingSender, C: Deref<Target = B>>(
+  destination: &'a str,
+  path: &'a str,
+  timeout: Duration,
+  connection: C,
+) -> Proxy<'a, C> {
+  Proxy::new(destination, path, timeout, connection)
+}
+
+/// Creates a proxy for the same portal object and connection as `proxy`, with another timeout for method calls.
+///
+/// Use it for calls that should fail faster, or may take longer, than the others on `proxy`:
+/// `with_timeout(&proxy, Duration::from_millis(500)).version()`.
+/// Note that the portal methods showing a dialog return as soon as the request is made,
+/// the time the user takes is covered by the `timeout` argument of the `_and_wait` methods.
+#[cfg(feature = "dbus")]
+pub fn with_timeout<'a, 'p, B, C: Deref<Target = B>>(
+  proxy: &'p Proxy<'a, C>,
+  timeout: Duration,
+) -> Proxy<'a, &'p B> {
   Proxy::new(
-    "org.freedesktop.portal.Desktop",
-    "/org/freedesktop/portal/desktop",
+    proxy.destination.clone(),
+    proxy.path.clone(),
     timeout,
-    connection,
+    &*proxy.connection,
   )
 }
 
-/// All errors that can happen while validating a scoped command.
-#[derive(Debug, thiserror::Error)]
-pub enum PortalError {
-  /// A generic D-Bus error that occurs while sending protocol messages.
-  #[error("Portal D-Bus error: {0}")]
-  Dbus(#[from] dbus::Error),
+/// Creates a new `dbus::nonblock::Proxy` targetting the `org.freedesktop.portal.Desktop` bus.
+/// Can be used with the Async Traits, such as `AsyncOpenURI`, to call Portal API methods.
+///
+/// The connection is typically created with `dbus_tokio::connection::new_session_sync()`,
+/// which also drives the timeout of method calls.
+#[cfg(feature = "async")]
+pub fn new_nonblocking<'a, C>(timeout: Duration, connection: C) -> dbus::nonblock::Proxy<'a, C> {
+  dbus::nonblock::Proxy::new(DESTINATION, PATH, timeout, connection)
+}
+
+/// Creates a new `dbus::blocking::Proxy` targetting the `org.freedesktop.portal.Documents` bus.
+/// Can be used with the `Documents` Trait, which isn't served by the `new_blocking()` object.
+#[cfg(feature = "dbus")]
+pub fn new_documents_blocking<'a, B: BlockingSender, C: Deref<Target = B>>(
+  timeout: Duration,
+  connection: C,
+) -> Proxy<'a, C> {
+  new_blocking_with(DOCUMENTS_DESTINATION, DOCUMENTS_PATH, timeout, connection)
+}
+
+/// All portals served by the `org.freedesktop.portal.Desktop` bus, on a single proxy.
+///
+/// Each accessor returns a handle for one portal, with the methods of the matching Trait only,
+/// which can be called without the Trait in scope: `portals.open_uri().open_uri("", uri, OpenURIOptions::new())`.
+///
+/// The `Documents` portal is served on another bus, see `new_documents_blocking()`.
+#[cfg(feature = "dbus")]
+pub struct Portals<'a, C> {
+  proxy: Proxy<'a, C>,
+}
+
+#[cfg(feature = "dbus")]
+impl<'a, B: BlockingSender, C: Deref<Target = B>> Portals<'a, C> {
+  /// Creates the proxy for all portals, see `new_blocking()`.
+  pub fn new(timeout: Duration, connection: C) -> Self {
+    Self {
+      proxy: new_blocking(timeout, connection),
+    }
+  }
+
+  /// Checks whether the portal service is running, or can be started.
+  ///
+  /// Fails with `PortalError::Unavailable` if it can't, in which case an application could fall back
+  /// to not using portals at all. Whether a specific portal is served can be checked with `portal_available()`.
+  pub fn probe(&self) -> Result<(), PortalError> {
+    Peer::ping(&self.proxy).map_err(Into::into)
+  }
+
+  /// Lists the portals that are served, with the version each one implements, see `enumerate_portals()`.
+  pub fn enumerate(&self) -> Result<Vec<PortalInfo>, PortalError> {
+    enumerate_portals(&self.proxy)
+  }
+
+  /// Reads the version of the `Email` portal once, to check which of its features are supported.
+  pub fn email_capabilities(&self) -> Result<EmailCapabilities, PortalError> {
+    Email::version(&self.proxy).map(EmailCapabilities::from_version)
+  }
+
+  /// Reads the version of the `FileChooser` portal once, to check which of its features are supported.
+  pub fn file_chooser_capabilities(&self) -> Result<FileChooserCapabilities, PortalError> {
+    FileChooser::version(&self.proxy).map(FileChooserCapabilities::from_version)
+  }
+
+  /// Reads the version of the `OpenURI` portal once, to check which of its features are supported.
+  pub fn open_uri_capabilities(&self) -> Result<OpenURICapabilities, PortalError> {
+    OpenURI::version(&self.proxy).map(OpenURICapabilities::from_version)
+  }
+
+  /// Reads the version of the `ScreenCast` portal once, to check which of its features are supported.
+  pub fn screen_cast_capabilities(&self) -> Result<ScreenCastCapabilities, PortalError> {
+    ScreenCast::version(&self.proxy).map(ScreenCastCapabilities::from_version)
+  }
+
+  /// Reads the version of the `Screenshot` portal once, to check which of its features are supported.
+  pub fn screenshot_capabilities(&self) -> Result<ScreenshotCapabilities, PortalError> {
+    Screenshot::version(&self.proxy).map(ScreenshotCapabilities::from_version)
+  }
+}
+
+#[cfg(feature = "dbus")]
+impl<'a, C: Deref<Target = Connection>> Portals<'a, C> {
+  /// Processes incoming messages on the connection until `done` returns a value, or `timeout` has passed,
+  /// see `process_until()`.
+  ///
+  /// For programs without an event loop, to receive the signals the handlers of the portals subscribe to.
+  pub fn run_until<T>(
+    &self,
+    timeout: Duration,
+    done: impl FnMut() -> Result<Option<T>, PortalError>,
+  ) -> Result<T, PortalError> {
+    process_until(&self.proxy.connection, timeout, done)
+  }
+}
+
+#[cfg(feature = "dbus")]
+impl<'a, C> Portals<'a, C> {
+  /// The underlying proxy.
+  pub fn proxy(&self) -> &Proxy<'a, C> {
+    &self.proxy
+  }
+
+  /// All portals on the same connection, with another timeout for method calls, see `with_timeout()`.
+  pub fn with_timeout<B>(&self, timeout: Duration) -> Portals<'a, &B>
+  where
+    C: Deref<Target = B>,
+  {
+    Portals {
+      proxy: with_timeout(&self.proxy, timeout),
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::{ParentWindow, ParentWindowArg};
+
+  #[test]
+  fn parent_window_identifiers() {
+    assert_eq!(ParentWindow::X11(0x3a0000b).to_string(), "x11:0x3a0000b");
+    assert_eq!(
+      ParentWindow::Wayland("a1b2c3".to_string()).to_string(),
+      "wayland:a1b2c3"
+    );
+    assert_eq!(ParentWindow::None.to_string(), "");
+    assert_eq!(
+      ParentWindow::Handle("future:a1b2c3".to_string()).to_string(),
+      "future:a1b2c3"
+    );
+    assert_eq!(ParentWindowArg::from("x11:1f").as_str(), "x11:1f");
+    assert_eq!(
+      ParentWindowArg::from("future:a1b2c3").as_str(),
+      "future:a1b2c3"
+    );
+  }
+
+  #[test]
+  #[cfg(feature = "dbus")]
+  fn proxy_with_timeout() {
+    use super::{with_timeout, DESTINATION, PATH};
+    use dbus::blocking::Proxy;
+    use std::time::Duration;
+
+    let proxy = Proxy::new(DESTINATION, PATH, Duration::from_secs(30), Box::new(()));
+    let fast = with_timeout(&proxy, Duration::from_millis(500));
+    assert_eq!(fast.timeout, Duration::from_millis(500));
+    assert_eq!(&*fast.destination, DESTINATION);
+    assert_eq!(&*fast.path, PATH);
+    assert_eq!(proxy.timeout, Duration::from_secs(30));
+  }
+
+  #[test]
+  #[cfg(feature = "testing")]
+  fn portal_handles_use_their_own_interface() {
+    use super::{testing::MockSender, Portals};
+    use std::time::Duration;
+
+    let sender = MockSender::new();
+    sender.version("org.freedesktop.portal.Screenshot", 2);
+    sender.version("org.freedesktop.portal.ScreenCast", 4);
+    let portals = Portals::new(Duration::from_secs(2), &sender);
+    assert_eq!(portals.screenshot().version().unwrap(), 2);
+    assert_eq!(portals.screen_cast().version().unwrap(), 4);
+    assert!(portals.open_uri().version().is_err());
+  }
 }
@@ -0,0 +1,327 @@
+// Copyright 2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::{
+  signal_rule,
+  trace::{self, CallSpan},
+  ParentWindowArg, PortalError, PortalEvent, Session, SignalGuard, StartOptions,
+};
+
+use dbus::{
+  arg::{PropMap, RefArg, Variant},
+  blocking::{self, stdintf::org_freedesktop_dbus, Connection},
+  Path,
+};
+use std::time::Duration;
+
+const INTERFACE: &str = "org.freedesktop.portal.Location";
+
+/// Implementation of the `org.freedesktop.portal.Location` Portal API.
+/// See also https://flatpak.github.io/xdg-desktop-portal/#gdbus-org.freedesktop.portal.Location
+///
+/// Location updates are only sent for sessions that were started,
+/// subscribe with `LocationPortal::on_location_updated()` before calling `LocationPortal::start()`.
+pub trait LocationPortal {
+  /// Creates a location session, returning its handle.
+  fn create_session(&self, options: LocationSessionOptions) -> Result<Path<'static>, PortalError>;
+
+  /// Starts the location session, which may ask the user for permission.
+  ///
+  /// - `parent_window`: Identifier for the application window, see crate comments for common conventions.
+  fn start(
+    &self,
+    session: &Session,
+    parent_window: impl Into<ParentWindowArg>,
+    options: StartOptions,
+  ) -> Result<Path<'static>, PortalError>;
+
+  /// Subscribes to the `LocationUpdated` signal, emitted when the location changes.
+  ///
+  /// The callback receives the session the update belongs to, and the new location.
+  /// Updates that can't be read as a `Location` are ignored.
+  fn on_location_updated<'c, F>(
+    &self,
+    connection: &'c Connection,
+    callback: F,
+  ) -> Result<SignalGuard<'c>, PortalError>
+  where
+    F: FnMut(Session, Location) + Send + 'static;
+
+  /// Reads the "version" property for this D-Bus interface.
+  fn version(&self) -> Result<u32, PortalError>;
+}
+
+/// The requested accuracy of the location.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Accuracy {
+  /// No location.
+  None,
+  /// Country level accuracy.
+  Country,
+  /// City level accuracy.
+  City,
+  /// Neighborhood level accuracy.
+  Neighborhood,
+  /// Street level accuracy.
+  Street,
+  /// The most accurate location available.
+  Exact,
+}
+
+impl Accuracy {
+  fn bits(&self) -> u32 {
+    match self {
+      Self::None => 0,
+      Self::Country => 1,
+      Self::City => 2,
+      Self::Neighborhood => 3,
+      Self::Street => 4,
+      Self::Exact => 5,
+    }
+  }
+}
+
+/// Optional arguments for the `LocationPortal::create_session()` method.
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LocationSessionOptions {
+  session_handle_token: Option<String>,
+  distance_threshold: Option<u32>,
+  time_threshold: Option<u32>,
+  accuracy: Option<Accuracy>,
+}
+
+impl LocationSessionOptions {
+  /// Creates a new `LocationSessionOptions` struct with no arguments set.
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  /// A string that will be used as the last element of the session handle. Must be a valid
+  /// object path element. See the #org.freedesktop.portal.Session documentation for
+  /// more information about the session handle.
+  pub fn session_handle_token(mut self, session_handle_token: impl Into<String>) -> Self {
+    self.session_handle_token = Some(session_handle_token.into());
+    self
+  }
+
+  /// Distance threshold in meters. Default is 0.
+  pub fn distance_threshold(mut self, distance_threshold: u32) -> Self {
+    self.distance_threshold = Some(distance_threshold);
+    self
+  }
+
+  /// Time threshold in seconds. Default is 0.
+  pub fn time_threshold(mut self, time_threshold: u32) -> Self {
+    self.time_threshold = Some(time_threshold);
+    self
+  }
+
+  /// Requested accuracy. Default is `Accuracy::Exact`.
+  pub fn accuracy(mut self, accuracy: Accuracy) -> Self {
+    self.accuracy = Some(accuracy);
+    self
+  }
+}
+
+impl From<LocationSessionOptions> for PropMap {
+  fn from(options: LocationSessionOptions) -> Self {
+    let mut map = PropMap::new();
+    if let Some(session_handle_token) = options.session_handle_token {
+      map.insert(
+        "session_handle_token".to_string(),
+        Variant(Box::new(session_handle_token)),
+      );
+    }
+    if let Some(distance_threshold) = options.distance_threshold {
+      map.insert(
+        "distance-threshold".to_string(),
+        Variant(Box::new(distance_threshold)),
+      );
+    }
+    if let Some(time_threshold) = options.time_threshold {
+      map.insert(
+        "time-threshold".to_string(),
+        Variant(Box::new(time_threshold)),
+      );
+    }
+    if let Some(accuracy) = options.accuracy {
+      map.insert("accuracy".to_string(), Variant(Box::new(accuracy.bits())));
+    }
+    map
+  }
+}
+
+/// A location, as sent by the `LocationUpdated` signal.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Location {
+  /// The latitude, in degrees.
+  pub latitude: f64,
+  /// The longitude, in degrees.
+  pub longitude: f64,
+  /// The altitude, in meters, if known.
+  pub altitude: Option<f64>,
+  /// The accuracy, in meters.
+  pub accuracy: f64,
+  /// The speed, in meters per second, if known.
+  pub speed: Option<f64>,
+  /// The heading, in degrees, going clockwise with North at 0 and East at 90, if known.
+  pub heading: Option<f64>,
+  /// The time the location was measured, since the Unix epoch.
+  pub timestamp: Duration,
+}
+
+impl TryFrom<&PropMap> for Location {
+  type Error = PortalError;
+
+  fn try_from(location: &PropMap) -> Result<Self, Self::Error> {
+    Ok(Self {
+      latitude: get_f64(location, "Latitude")?,
+      longitude: get_f64(location, "Longitude")?,
+      altitude: get_optional_f64(location, "Altitude")?,
+      accuracy: get_f64(location, "Accuracy")?,
+      // An unknown speed or heading is sent as -1.
+      speed: get_optional_f64(location, "Speed")?.filter(|speed| *speed >= 0.0),
+      heading: get_optional_f64(location, "Heading")?.filter(|heading| *heading >= 0.0),
+      timestamp: get_timestamp(location)?,
+    })
+  }
+}
+
+fn get_f64(map: &PropMap, key: &str) -> Result<f64, PortalError> {
+  get_optional_f64(map, key)?
+    .ok_or_else(|| PortalError::UnexpectedResponse(format!("missing result `{}`", key)))
+}
+
+/// Reads a `d` value, treating a missing key or the `-f64::MAX` "unknown" marker as `None`.
+fn get_optional_f64(map: &PropMap, key: &str) -> Result<Option<f64>, PortalError> {
+  let value = match map.get(key) {
+    Some(value) => value,
+    None => return Ok(None),
+  };
+  match value.as_f64() {
+    Some(value) if value == -f64::MAX => Ok(None),
+    Some(value) => Ok(Some(value)),
+    None => Err(PortalError::TypeMismatch {
+      key: key.to_string(),
+      expected: "d",
+      found: value.0.signature().to_string(),
+    }),
+  }
+}
+
+/// Reads the `(tt)` timestamp, the seconds and microseconds since the Unix epoch.
+fn get_timestamp(map: &PropMap) -> Result<Duration, PortalError> {
+  let value = map
+    .get("Timestamp")
+    .ok_or_else(|| PortalError::UnexpectedResponse("missing result `Timestamp`".to_string()))?;
+  let timestamp = value.0.as_iter().and_then(|mut fields| {
+    let seconds = fields.next()?.as_u64()?;
+    let microseconds = fields.next()?.as_u64()?;
+    Some(Duration::from_secs(seconds) + Duration::from_micros(microseconds))
+  });
+  timestamp.ok_or_else(|| PortalError::TypeMismatch {
+    key: "Timestamp".to_string(),
+    expected: "(tt)",
+    found: value.0.signature().to_string(),
+  })
+}
+
+impl<'a, T: blocking::BlockingSender, C: std::ops::Deref<Target = T>> LocationPortal
+  for blocking::Proxy<'a, C>
+{
+  fn create_session(&self, options: LocationSessionOptions) -> Result<Path<'static>, PortalError> {
+    let options = PropMap::from(options);
+    let _span = CallSpan::enter(
+      INTERFACE,
+      "CreateSession",
+      "",
+      trace::handle_token(&options),
+    );
+    self
+      .method_call(INTERFACE, "CreateSession", (options,))
+      .map(|r: (Path<'static>,)| r.0)
+      .map_err(Into::into)
+  }
+
+  fn start(
+    &self,
+    session: &Session,
+    parent_window: impl Into<ParentWindowArg>,
+    options: StartOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    let parent_window: ParentWindowArg = parent_window.into();
+    let options = PropMap::from(options);
+    let _span = CallSpan::enter(
+      INTERFACE,
+      "Start",
+      parent_window.as_str(),
+      trace::handle_token(&options),
+    );
+    self
+      .method_call(
+        INTERFACE,
+        "Start",
+        (session.path(), parent_window.as_str(), options),
+      )
+      .map(|r: (Path<'static>,)| trace::request_created(r.0))
+      .map_err(Into::into)
+  }
+
+  fn on_location_updated<'c, F>(
+    &self,
+    connection: &'c Connection,
+    mut callback: F,
+  ) -> Result<SignalGuard<'c>, PortalError>
+  where
+    F: FnMut(Session, Location) + Send + 'static,
+  {
+    SignalGuard::for_events(
+      connection,
+      signal_rule(self, INTERFACE, "LocationUpdated"),
+      move |event| {
+        if let PortalEvent::LocationUpdated { session, location } = event {
+          callback(session, location);
+        }
+      },
+    )
+  }
+
+  fn version(&self) -> Result<u32, PortalError> {
+    <Self as org_freedesktop_dbus::Properties>::get(self, INTERFACE, "version").map_err(Into::into)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::Location;
+  use dbus::arg::{PropMap, Variant};
+  use std::time::Duration;
+
+  #[test]
+  fn location_from_prop_map() {
+    let mut location = PropMap::new();
+    location.insert("Latitude".to_string(), Variant(Box::new(52.37f64)));
+    location.insert("Longitude".to_string(), Variant(Box::new(4.89f64)));
+    location.insert("Altitude".to_string(), Variant(Box::new(-f64::MAX)));
+    location.insert("Accuracy".to_string(), Variant(Box::new(30f64)));
+    location.insert("Speed".to_string(), Variant(Box::new(-1f64)));
+    location.insert("Heading".to_string(), Variant(Box::new(90f64)));
+    location.insert(
+      "Timestamp".to_string(),
+      Variant(Box::new((1_600_000_000u64, 500_000u64))),
+    );
+
+    let location = Location::try_from(&location).unwrap();
+    assert_eq!(location.latitude, 52.37);
+    assert_eq!(location.longitude, 4.89);
+    assert_eq!(location.altitude, None);
+    assert_eq!(location.accuracy, 30.0);
+    assert_eq!(location.speed, None);
+    assert_eq!(location.heading, Some(90.0));
+    assert_eq!(location.timestamp, Duration::from_millis(1_600_000_000_500));
+  }
+}
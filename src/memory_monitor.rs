@@ -0,0 +1,59 @@
+// Copyright 2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::{signal_rule, PortalError, PortalEvent, SignalGuard};
+
+use dbus::blocking::{self, stdintf::org_freedesktop_dbus, Connection};
+
+const INTERFACE: &str = "org.freedesktop.portal.MemoryMonitor";
+
+/// Implementation of the `org.freedesktop.portal.MemoryMonitor` Portal API.
+/// See also https://flatpak.github.io/xdg-desktop-portal/#gdbus-org.freedesktop.portal.MemoryMonitor
+pub trait MemoryMonitor {
+  /// Subscribes to the `LowMemoryWarning` signal, emitted when a particular low memory situation happens.
+  ///
+  /// The callback receives the warning level, which ranges from 0 to 255. The levels match glib's
+  /// `GMemoryMonitorWarningLevel`, and applications should free more memory the higher the level:
+  /// - `50`: Memory on the device is low, processes should free up unneeded resources so they can be used elsewhere.
+  /// - `100`: Same as 50, but the device is already slowing down because of memory pressure,
+  ///   processes should drop caches and other data that can be regenerated.
+  /// - `255`: The system will start terminating processes to reclaim memory, including background processes.
+  fn on_low_memory_warning<'c, F>(
+    &self,
+    connection: &'c Connection,
+    callback: F,
+  ) -> Result<SignalGuard<'c>, PortalError>
+  where
+    F: FnMut(u8) + Send + 'static;
+
+  /// Reads the "version" property for this D-Bus interface.
+  fn version(&self) -> Result<u32, PortalError>;
+}
+
+impl<'a, T: blocking::BlockingSender, C: std::ops::Deref<Target = T>> MemoryMonitor
+  for blocking::Proxy<'a, C>
+{
+  fn on_low_memory_warning<'c, F>(
+    &self,
+    connection: &'c Connection,
+    mut callback: F,
+  ) -> Result<SignalGuard<'c>, PortalError>
+  where
+    F: FnMut(u8) + Send + 'static,
+  {
+    SignalGuard::for_events(
+      connection,
+      signal_rule(self, INTERFACE, "LowMemoryWarning"),
+      move |event| {
+        if let PortalEvent::LowMemoryWarning { level } = event {
+          callback(level);
+        }
+      },
+    )
+  }
+
+  fn version(&self) -> Result<u32, PortalError> {
+    <Self as org_freedesktop_dbus::Properties>::get(self, INTERFACE, "version").map_err(Into::into)
+  }
+}
@@ -0,0 +1,187 @@
+// Copyright 2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::{signal_rule, PortalError, PortalEvent, Results, SignalGuard};
+
+use dbus::{
+  arg::PropMap,
+  blocking::{self, stdintf::org_freedesktop_dbus, Connection},
+};
+
+const INTERFACE: &str = "org.freedesktop.portal.NetworkMonitor";
+
+/// Implementation of the `org.freedesktop.portal.NetworkMonitor` Portal API.
+/// See also https://flatpak.github.io/xdg-desktop-portal/#gdbus-org.freedesktop.portal.NetworkMonitor
+pub trait NetworkMonitor {
+  /// Returns whether the network is considered available.
+  /// That is, whether the system has a default route for at least one of IPv4 or IPv6.
+  fn get_available(&self) -> Result<bool, PortalError>;
+
+  /// Returns whether the network is considered metered.
+  /// That is, whether the system has traffic flowing through the default connection that is subject to limitations.
+  fn get_metered(&self) -> Result<bool, PortalError>;
+
+  /// Returns more detailed information about the host's network connectivity.
+  fn get_connectivity(&self) -> Result<Connectivity, PortalError>;
+
+  /// Returns the availability, meteredness and connectivity in a single call.
+  fn get_status(&self) -> Result<NetworkStatus, PortalError>;
+
+  /// Returns whether the given hostname is believed to be reachable.
+  ///
+  /// - `hostname`: The hostname to reach.
+  /// - `port`: The port to reach.
+  fn can_reach(&self, hostname: &str, port: u32) -> Result<bool, PortalError>;
+
+  /// Subscribes to the `changed` signal, emitted when the network configuration changes.
+  ///
+  /// The signal carries no details, use `NetworkMonitor::get_status()` to query the new state.
+  fn on_changed<'c, F>(
+    &self,
+    connection: &'c Connection,
+    callback: F,
+  ) -> Result<SignalGuard<'c>, PortalError>
+  where
+    F: FnMut() + Send + 'static;
+
+  /// Reads the "version" property for this D-Bus interface.
+  fn version(&self) -> Result<u32, PortalError>;
+}
+
+/// The host's network connectivity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Connectivity {
+  /// The host is not configured with a route to the internet.
+  Local,
+  /// The host is connected to a network, but can't reach the full internet.
+  Limited,
+  /// The host is behind a captive portal and cannot reach the full internet.
+  CaptivePortal,
+  /// The host connected to a network, and can reach the full internet.
+  Full,
+}
+
+impl TryFrom<u32> for Connectivity {
+  type Error = PortalError;
+
+  fn try_from(value: u32) -> Result<Self, Self::Error> {
+    match value {
+      1 => Ok(Self::Local),
+      2 => Ok(Self::Limited),
+      3 => Ok(Self::CaptivePortal),
+      4 => Ok(Self::Full),
+      value => Err(PortalError::UnexpectedResponse(format!(
+        "unknown connectivity value {}",
+        value
+      ))),
+    }
+  }
+}
+
+/// The network status, as returned by `NetworkMonitor::get_status()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NetworkStatus {
+  /// Whether the network is considered available.
+  pub available: bool,
+  /// Whether the network is considered metered.
+  pub metered: bool,
+  /// More detailed information about the host's network connectivity.
+  pub connectivity: Connectivity,
+}
+
+impl TryFrom<&PropMap> for NetworkStatus {
+  type Error = PortalError;
+
+  fn try_from(status: &PropMap) -> Result<Self, Self::Error> {
+    let status = Results::new(status);
+    Ok(Self {
+      available: status.required("available", Results::get_bool)?,
+      metered: status.required("metered", Results::get_bool)?,
+      connectivity: Connectivity::try_from(status.required("connectivity", Results::get_u32)?)?,
+    })
+  }
+}
+
+impl<'a, T: blocking::BlockingSender, C: std::ops::Deref<Target = T>> NetworkMonitor
+  for blocking::Proxy<'a, C>
+{
+  fn get_available(&self) -> Result<bool, PortalError> {
+    self
+      .method_call(INTERFACE, "GetAvailable", ())
+      .map(|r: (bool,)| r.0)
+      .map_err(Into::into)
+  }
+
+  fn get_metered(&self) -> Result<bool, PortalError> {
+    self
+      .method_call(INTERFACE, "GetMetered", ())
+      .map(|r: (bool,)| r.0)
+      .map_err(Into::into)
+  }
+
+  fn get_connectivity(&self) -> Result<Connectivity, PortalError> {
+    let connectivity = self
+      .method_call(INTERFACE, "GetConnectivity", ())
+      .map(|r: (u32,)| r.0)?;
+    Connectivity::try_from(connectivity)
+  }
+
+  fn get_status(&self) -> Result<NetworkStatus, PortalError> {
+    let status = self
+      .method_call(INTERFACE, "GetStatus", ())
+      .map(|r: (PropMap,)| r.0)?;
+    NetworkStatus::try_from(&status)
+  }
+
+  fn can_reach(&self, hostname: &str, port: u32) -> Result<bool, PortalError> {
+    self
+      .method_call(INTERFACE, "CanReach", (hostname, port))
+      .map(|r: (bool,)| r.0)
+      .map_err(Into::into)
+  }
+
+  fn on_changed<'c, F>(
+    &self,
+    connection: &'c Connection,
+    mut callback: F,
+  ) -> Result<SignalGuard<'c>, PortalError>
+  where
+    F: FnMut() + Send + 'static,
+  {
+    SignalGuard::for_events(
+      connection,
+      signal_rule(self, INTERFACE, "changed"),
+      move |event| {
+        if let PortalEvent::NetworkChanged = event {
+          callback();
+        }
+      },
+    )
+  }
+
+  fn version(&self) -> Result<u32, PortalError> {
+    <Self as org_freedesktop_dbus::Properties>::get(self, INTERFACE, "version").map_err(Into::into)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::{Connectivity, NetworkStatus};
+  use dbus::arg::{PropMap, Variant};
+
+  #[test]
+  fn network_status_from_prop_map() {
+    let mut status = PropMap::new();
+    status.insert("available".to_string(), Variant(Box::new(true)));
+    status.insert("metered".to_string(), Variant(Box::new(false)));
+    status.insert("connectivity".to_string(), Variant(Box::new(3u32)));
+
+    let status = NetworkStatus::try_from(&status).unwrap();
+    assert!(status.available);
+    assert!(!status.metered);
+    assert_eq!(status.connectivity, Connectivity::CaptivePortal);
+  }
+}
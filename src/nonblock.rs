@@ -0,0 +1,101 @@
+// Copyright 2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Non-blocking versions of the portal traits, for use with `dbus::nonblock` connections.
+
+mod global_shortcuts;
+mod location;
+mod open_uri;
+mod settings;
+mod signal;
+
+pub use global_shortcuts::*;
+pub use location::*;
+pub use open_uri::*;
+pub use settings::*;
+pub use signal::*;
+
+use crate::{trace, PortalError, PortalFuture, ResponseCode};
+
+use dbus::{
+  arg::PropMap,
+  message::MatchRule,
+  nonblock::{MsgMatch, Proxy, SyncConnection},
+  strings::BusName,
+  Message, Path,
+};
+use futures_channel::oneshot;
+use std::sync::Arc;
+
+const REQUEST_INTERFACE: &str = "org.freedesktop.portal.Request";
+
+/// Creates a rule matching the `member` signal of `interface`, emitted by the object `proxy` points to.
+fn signal_rule<C>(
+  proxy: &Proxy<'_, C>,
+  interface: &'static str,
+  member: &'static str,
+) -> MatchRule<'static> {
+  MatchRule::new_signal(interface, member)
+    .with_sender(proxy.destination.clone().into_static())
+    .with_path(proxy.path.clone().into_static())
+}
+
+/// Receives the `Response` signal of a single request, through a oneshot channel
+/// fired from the signal handler.
+pub(crate) struct AsyncResponse {
+  connection: Arc<SyncConnection>,
+  msg_match: MsgMatch,
+  receiver: oneshot::Receiver<Message>,
+}
+
+impl AsyncResponse {
+  /// Starts matching the `Response` signal of the request at `path` of the portal at `destination`,
+  /// which doesn't need to exist yet.
+  pub(crate) async fn listen(
+    connection: Arc<SyncConnection>,
+    destination: &BusName<'_>,
+    path: Path<'static>,
+  ) -> Result<Self, PortalError> {
+    let rule = MatchRule::new_signal(REQUEST_INTERFACE, "Response")
+      .with_sender(destination.clone().into_static())
+      .with_path(path);
+    let (sender, receiver) = oneshot::channel();
+    let mut sender = Some(sender);
+    let msg_match = connection.add_match(rule).await?.msg_cb(move |message| {
+      if let Some(sender) = sender.take() {
+        let _ = sender.send(message);
+      }
+      // Keep the callback registered, `remove_match` fails for callbacks that were already dropped.
+      true
+    });
+    Ok(Self {
+      connection,
+      msg_match,
+      receiver,
+    })
+  }
+
+  /// Resolves when the response arrives, then removes the signal match.
+  pub(crate) async fn wait(self) -> Result<(ResponseCode, PropMap), PortalError> {
+    let message = self.receiver.await;
+    self.connection.remove_match(self.msg_match.token()).await?;
+    let message = message.map_err(|_| {
+      PortalError::UnexpectedResponse("the Response signal match ended".to_string())
+    })?;
+    let (code, results): (u32, PropMap) = message.read_all()?;
+    if let Some(path) = message.path() {
+      trace::response_received(&path, code);
+    }
+    Ok((code.into(), results))
+  }
+
+  /// Removes the signal match without waiting for the response.
+  pub(crate) async fn stop(self) -> Result<(), PortalError> {
+    self
+      .connection
+      .remove_match(self.msg_match.token())
+      .await
+      .map_err(Into::into)
+  }
+}
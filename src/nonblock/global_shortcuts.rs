@@ -0,0 +1,36 @@
+// Copyright 2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use super::{signal_rule, EventStream, PortalFuture};
+
+use dbus::nonblock::{self, SyncConnection};
+use std::sync::Arc;
+
+const INTERFACE: &str = "org.freedesktop.portal.GlobalShortcuts";
+
+/// Non-blocking signals of the `org.freedesktop.portal.GlobalShortcuts` Portal API.
+/// See `GlobalShortcuts` for the blocking version and the methods of the portal.
+pub trait AsyncGlobalShortcuts {
+  /// Subscribes to the `Activated` signal, see `GlobalShortcuts::on_activated()`.
+  ///
+  /// The stream yields `PortalEvent::ShortcutActivated` events.
+  fn activated(&self) -> PortalFuture<'_, EventStream>;
+
+  /// Subscribes to the `Deactivated` signal, see `GlobalShortcuts::on_deactivated()`.
+  ///
+  /// The stream yields `PortalEvent::ShortcutDeactivated` events.
+  fn deactivated(&self) -> PortalFuture<'_, EventStream>;
+}
+
+impl<'p> AsyncGlobalShortcuts for nonblock::Proxy<'p, Arc<SyncConnection>> {
+  fn activated(&self) -> PortalFuture<'_, EventStream> {
+    let rule = signal_rule(self, INTERFACE, "Activated");
+    Box::pin(EventStream::listen(self.connection.clone(), rule))
+  }
+
+  fn deactivated(&self) -> PortalFuture<'_, EventStream> {
+    let rule = signal_rule(self, INTERFACE, "Deactivated");
+    Box::pin(EventStream::listen(self.connection.clone(), rule))
+  }
+}
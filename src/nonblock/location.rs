@@ -0,0 +1,26 @@
+// Copyright 2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use super::{signal_rule, EventStream, PortalFuture};
+
+use dbus::nonblock::{self, SyncConnection};
+use std::sync::Arc;
+
+const INTERFACE: &str = "org.freedesktop.portal.Location";
+
+/// Non-blocking signals of the `org.freedesktop.portal.Location` Portal API.
+/// See `LocationPortal` for the blocking version and the methods of the portal.
+pub trait AsyncLocation {
+  /// Subscribes to the `LocationUpdated` signal, see `LocationPortal::on_location_updated()`.
+  ///
+  /// The stream yields `PortalEvent::LocationUpdated` events.
+  fn location_updated(&self) -> PortalFuture<'_, EventStream>;
+}
+
+impl<'p> AsyncLocation for nonblock::Proxy<'p, Arc<SyncConnection>> {
+  fn location_updated(&self) -> PortalFuture<'_, EventStream> {
+    let rule = signal_rule(self, INTERFACE, "LocationUpdated");
+    Box::pin(EventStream::listen(self.connection.clone(), rule))
+  }
+}
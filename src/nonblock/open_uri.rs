@@ -0,0 +1,259 @@
+// Copyright 2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use super::{AsyncResponse, PortalFuture};
+use crate::{
+  request_path,
+  trace::{self, AsyncCallSpan},
+  IntoPortalFd, OpenURIOptions, ParentWindowArg, PortalError, ResponseCode,
+};
+
+use dbus::{
+  arg::PropMap,
+  nonblock::{self, stdintf::org_freedesktop_dbus, SyncConnection},
+  Path,
+};
+use std::{future::Future, sync::Arc};
+
+const INTERFACE: &str = "org.freedesktop.portal.OpenURI";
+
+/// Non-blocking implementation of the `org.freedesktop.portal.OpenURI` Portal API.
+/// See `OpenURI` for the blocking version and details about each method.
+pub trait AsyncOpenURI {
+  /// Asks to open a uri, see `OpenURI::open_uri()`.
+  fn open_uri<'a>(
+    &'a self,
+    parent_window: impl Into<ParentWindowArg>,
+    uri: &'a str,
+    options: OpenURIOptions,
+  ) -> PortalFuture<'a, Path<'static>>;
+
+  /// Asks to open a local file, see `OpenURI::open_file()`.
+  fn open_file<'a>(
+    &'a self,
+    parent_window: impl Into<ParentWindowArg>,
+    fd: impl IntoPortalFd,
+    options: OpenURIOptions,
+  ) -> PortalFuture<'a, Path<'static>>;
+
+  /// Asks to open the directory containing a local file, see `OpenURI::open_directory()`.
+  fn open_directory<'a>(
+    &'a self,
+    parent_window: impl Into<ParentWindowArg>,
+    fd: impl IntoPortalFd,
+    options: OpenURIOptions,
+  ) -> PortalFuture<'a, Path<'static>>;
+
+  /// Same as `AsyncOpenURI::open_uri()`, but resolves when the user interaction has ended,
+  /// with the response code and results of the `org.freedesktop.portal.Request::Response` signal.
+  ///
+  /// If no `handle_token` is set, one is generated, so the signal match for the predicted request path
+  /// can be added before the request is made. There is no timeout, as the user may take any amount of time.
+  fn open_uri_and_wait<'a>(
+    &'a self,
+    parent_window: impl Into<ParentWindowArg>,
+    uri: &'a str,
+    options: OpenURIOptions,
+  ) -> PortalFuture<'a, (ResponseCode, PropMap)>;
+
+  /// Same as `AsyncOpenURI::open_file()`, but resolves when the user interaction has ended.
+  ///
+  /// See `AsyncOpenURI::open_uri_and_wait()` for details.
+  fn open_file_and_wait<'a>(
+    &'a self,
+    parent_window: impl Into<ParentWindowArg>,
+    fd: impl IntoPortalFd,
+    options: OpenURIOptions,
+  ) -> PortalFuture<'a, (ResponseCode, PropMap)>;
+
+  /// Same as `AsyncOpenURI::open_directory()`, but resolves when the user interaction has ended.
+  ///
+  /// See `AsyncOpenURI::open_uri_and_wait()` for details.
+  fn open_directory_and_wait<'a>(
+    &'a self,
+    parent_window: impl Into<ParentWindowArg>,
+    fd: impl IntoPortalFd,
+    options: OpenURIOptions,
+  ) -> PortalFuture<'a, (ResponseCode, PropMap)>;
+
+  /// Reads the "version" property for this D-Bus interface.
+  fn version(&self) -> PortalFuture<'_, u32>;
+}
+
+type Proxy<'p> = nonblock::Proxy<'p, Arc<SyncConnection>>;
+
+/// Makes the request with `call` once the response is being matched, then waits for it.
+async fn and_wait<F>(
+  proxy: &Proxy<'_>,
+  mut options: OpenURIOptions,
+  call: impl FnOnce(OpenURIOptions) -> F,
+) -> Result<(ResponseCode, PropMap), PortalError>
+where
+  F: Future<Output = Result<Path<'static>, PortalError>>,
+{
+  let expected = request_path(
+    &proxy.connection.unique_name(),
+    &options.ensure_handle_token(),
+  )?;
+  let response = AsyncResponse::listen(
+    proxy.connection.clone(),
+    &proxy.destination,
+    expected.clone(),
+  )
+  .await?;
+  let path = call(options).await?;
+  if path != expected {
+    // Portals implementing versions of the spec before 0.9 don't derive the path from the handle_token,
+    // so the response can only be matched once the path is known.
+    response.stop().await?;
+    return AsyncResponse::listen(proxy.connection.clone(), &proxy.destination, path)
+      .await?
+      .wait()
+      .await;
+  }
+  response.wait().await
+}
+
+impl<'p> AsyncOpenURI for Proxy<'p> {
+  fn open_uri<'a>(
+    &'a self,
+    parent_window: impl Into<ParentWindowArg>,
+    uri: &'a str,
+    options: OpenURIOptions,
+  ) -> PortalFuture<'a, Path<'static>> {
+    let parent_window: ParentWindowArg = parent_window.into();
+    let options = PropMap::from(options);
+    let span = AsyncCallSpan::new(
+      INTERFACE,
+      "OpenURI",
+      parent_window.as_str(),
+      trace::handle_token(&options),
+    );
+    let reply = self.method_call(INTERFACE, "OpenURI", (parent_window.as_str(), uri, options));
+    Box::pin(span.instrument(async move {
+      reply
+        .await
+        .map(|r: (Path<'static>,)| trace::request_created(r.0))
+        .map_err(Into::into)
+    }))
+  }
+
+  fn open_file<'a>(
+    &'a self,
+    parent_window: impl Into<ParentWindowArg>,
+    fd: impl IntoPortalFd,
+    options: OpenURIOptions,
+  ) -> PortalFuture<'a, Path<'static>> {
+    let parent_window: ParentWindowArg = parent_window.into();
+    let options = PropMap::from(options);
+    let span = AsyncCallSpan::new(
+      INTERFACE,
+      "OpenFile",
+      parent_window.as_str(),
+      trace::handle_token(&options),
+    );
+    let reply = self.method_call(
+      INTERFACE,
+      "OpenFile",
+      (parent_window.as_str(), fd.into_portal_fd(), options),
+    );
+    Box::pin(span.instrument(async move {
+      reply
+        .await
+        .map(|r: (Path<'static>,)| trace::request_created(r.0))
+        .map_err(Into::into)
+    }))
+  }
+
+  fn open_directory<'a>(
+    &'a self,
+    parent_window: impl Into<ParentWindowArg>,
+    fd: impl IntoPortalFd,
+    options: OpenURIOptions,
+  ) -> PortalFuture<'a, Path<'static>> {
+    let parent_window: ParentWindowArg = parent_window.into();
+    let options = PropMap::from(options);
+    let span = AsyncCallSpan::new(
+      INTERFACE,
+      "OpenDirectory",
+      parent_window.as_str(),
+      trace::handle_token(&options),
+    );
+    let reply = self.method_call(
+      INTERFACE,
+      "OpenDirectory",
+      (parent_window.as_str(), fd.into_portal_fd(), options),
+    );
+    Box::pin(span.instrument(async move {
+      reply
+        .await
+        .map(|r: (Path<'static>,)| trace::request_created(r.0))
+        .map_err(Into::into)
+    }))
+  }
+
+  fn open_uri_and_wait<'a>(
+    &'a self,
+    parent_window: impl Into<ParentWindowArg>,
+    uri: &'a str,
+    mut options: OpenURIOptions,
+  ) -> PortalFuture<'a, (ResponseCode, PropMap)> {
+    // Converted up front, so the future doesn't depend on the argument type being `Send`.
+    let parent_window: ParentWindowArg = parent_window.into();
+    let span = AsyncCallSpan::new(
+      INTERFACE,
+      "OpenURI",
+      parent_window.as_str(),
+      Some(options.ensure_handle_token().as_str()),
+    );
+    Box::pin(span.instrument(and_wait(self, options, move |options| {
+      self.open_uri(parent_window, uri, options)
+    })))
+  }
+
+  fn open_file_and_wait<'a>(
+    &'a self,
+    parent_window: impl Into<ParentWindowArg>,
+    fd: impl IntoPortalFd,
+    mut options: OpenURIOptions,
+  ) -> PortalFuture<'a, (ResponseCode, PropMap)> {
+    // Converted up front, so the future doesn't depend on the argument type being `Send`.
+    let parent_window: ParentWindowArg = parent_window.into();
+    let span = AsyncCallSpan::new(
+      INTERFACE,
+      "OpenFile",
+      parent_window.as_str(),
+      Some(options.ensure_handle_token().as_str()),
+    );
+    let fd = fd.into_portal_fd();
+    Box::pin(span.instrument(and_wait(self, options, move |options| {
+      self.open_file(parent_window, fd, options)
+    })))
+  }
+
+  fn open_directory_and_wait<'a>(
+    &'a self,
+    parent_window: impl Into<ParentWindowArg>,
+    fd: impl IntoPortalFd,
+    mut options: OpenURIOptions,
+  ) -> PortalFuture<'a, (ResponseCode, PropMap)> {
+    // Converted up front, so the future doesn't depend on the argument type being `Send`.
+    let parent_window: ParentWindowArg = parent_window.into();
+    let span = AsyncCallSpan::new(
+      INTERFACE,
+      "OpenDirectory",
+      parent_window.as_str(),
+      Some(options.ensure_handle_token().as_str()),
+    );
+    let fd = fd.into_portal_fd();
+    Box::pin(span.instrument(and_wait(self, options, move |options| {
+      self.open_directory(parent_window, fd, options)
+    })))
+  }
+
+  fn version(&self) -> PortalFuture<'_, u32> {
+    let reply = <Self as org_freedesktop_dbus::Properties>::get(self, INTERFACE, "version");
+    Box::pin(async move { reply.await.map_err(Into::into) })
+  }
+}
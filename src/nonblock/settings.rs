@@ -0,0 +1,26 @@
+// Copyright 2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use super::{signal_rule, EventStream, PortalFuture};
+
+use dbus::nonblock::{self, SyncConnection};
+use std::sync::Arc;
+
+const INTERFACE: &str = "org.freedesktop.portal.Settings";
+
+/// Non-blocking signals of the `org.freedesktop.portal.Settings` Portal API.
+/// See `Settings` for the blocking version and the methods of the portal.
+pub trait AsyncSettings {
+  /// Subscribes to the `SettingChanged` signal, see `Settings::on_setting_changed()`.
+  ///
+  /// The stream yields `PortalEvent::SettingChanged` events.
+  fn setting_changed(&self) -> PortalFuture<'_, EventStream>;
+}
+
+impl<'p> AsyncSettings for nonblock::Proxy<'p, Arc<SyncConnection>> {
+  fn setting_changed(&self) -> PortalFuture<'_, EventStream> {
+    let rule = signal_rule(self, INTERFACE, "SettingChanged");
+    Box::pin(EventStream::listen(self.connection.clone(), rule))
+  }
+}
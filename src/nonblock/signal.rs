@@ -0,0 +1,69 @@
+// Copyright 2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::{PortalError, PortalEvent};
+
+use dbus::{
+  message::MatchRule,
+  nonblock::{MsgMatch, SyncConnection},
+};
+use futures_channel::mpsc;
+use futures_core::Stream;
+use std::{
+  pin::Pin,
+  sync::Arc,
+  task::{Context, Poll},
+};
+
+/// Portal signals as a `Stream` of `PortalEvent`s, the non-blocking counterpart of `SignalStream`.
+///
+/// Returned by the async signal subscriptions, such as `AsyncSettings::setting_changed()`,
+/// so events can be awaited with `while let Some(event) = stream.next().await`.
+///
+/// Dropping the stream stops the delivery of events. Use `EventStream::stop()` to also remove
+/// the signal match from the bus.
+pub struct EventStream {
+  connection: Arc<SyncConnection>,
+  msg_match: MsgMatch,
+  receiver: mpsc::UnboundedReceiver<PortalEvent>,
+}
+
+impl EventStream {
+  /// Starts matching the signals of `rule`, decoding them with `PortalEvent::from_message()`.
+  pub(crate) async fn listen(
+    connection: Arc<SyncConnection>,
+    rule: MatchRule<'static>,
+  ) -> Result<Self, PortalError> {
+    let (sender, receiver) = mpsc::unbounded();
+    let msg_match = connection.add_match(rule).await?.msg_cb(move |message| {
+      match PortalEvent::from_message(&message) {
+        // Ends the callback once the stream is dropped.
+        Some(event) => sender.unbounded_send(event).is_ok(),
+        None => true,
+      }
+    });
+    Ok(Self {
+      connection,
+      msg_match,
+      receiver,
+    })
+  }
+
+  /// Removes the signal match, ending the stream.
+  pub async fn stop(self) -> Result<(), PortalError> {
+    self
+      .connection
+      .remove_match(self.msg_match.token())
+      .await
+      .map_err(Into::into)
+  }
+}
+
+impl Stream for EventStream {
+  type Item = PortalEvent;
+
+  fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+    Pin::new(&mut self.receiver).poll_next(cx)
+  }
+}
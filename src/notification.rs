@@ -0,0 +1,592 @@
+// Copyright 2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::{signal_rule, PortalError, PortalEvent, SignalGuard, VersionedOptions};
+
+use dbus::{
+  arg::{OwnedFd, PropMap, RefArg, Variant},
+  blocking::{self, stdintf::org_freedesktop_dbus, Connection},
+};
+use std::{fmt, os::unix::io::AsRawFd};
+
+const INTERFACE: &str = "org.freedesktop.portal.Notification";
+
+/// Implementation of the `org.freedesktop.portal.Notification` Portal API.
+/// See also https://flatpak.github.io/xdg-desktop-portal/#gdbus-org.freedesktop.portal.Notification
+///
+/// Named `NotificationPortal` so it doesn't clash with the `Notification` builder.
+pub trait NotificationPortal {
+  /// Sends a notification.
+  ///
+  /// - `id`: Application-provided ID for this notification. Adding a notification with an ID
+  ///   that is already in use replaces the existing notification.
+  /// - `notification`: The notification to show.
+  fn add_notification(&self, id: &str, notification: Notification) -> Result<(), PortalError>;
+
+  /// Withdraws a notification.
+  ///
+  /// - `id`: Application-provided ID for this notification.
+  fn remove_notification(&self, id: &str) -> Result<(), PortalError>;
+
+  /// Subscribes to the `ActionInvoked` signal, emitted when the user activates a notification
+  /// or one of its buttons.
+  ///
+  /// The callback receives the `id` of the notification, the name of the `action` that was invoked,
+  /// and its `parameters`. The parameters contain the target that was set for the action with
+  /// `Notification::default_action_target()` or `Button::target()`, if any.
+  fn on_action_invoked<'c, F>(
+    &self,
+    connection: &'c Connection,
+    callback: F,
+  ) -> Result<SignalGuard<'c>, PortalError>
+  where
+    F: FnMut(String, String, Vec<Variant<Box<dyn RefArg>>>) + Send + 'static;
+
+  /// Reads the "version" property for this D-Bus interface.
+  fn version(&self) -> Result<u32, PortalError>;
+}
+
+/// The priority of a notification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Priority {
+  /// Low priority.
+  Low,
+  /// Normal priority.
+  Normal,
+  /// High priority.
+  High,
+  /// Urgent priority.
+  Urgent,
+}
+
+impl Priority {
+  fn as_str(&self) -> &'static str {
+    match self {
+      Self::Low => "low",
+      Self::Normal => "normal",
+      Self::High => "high",
+      Self::Urgent => "urgent",
+    }
+  }
+}
+
+/// The icon of a notification, or of a `DynamicLauncher` launcher.
+///
+/// Cloning an `Icon::File` duplicates its file descriptor, and panics if that fails.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Icon {
+  /// Names of themed icons, in order of preference.
+  Themed(Vec<String>),
+  /// The contents of an image file.
+  Bytes(Vec<u8>),
+  /// An open image file.
+  ///
+  /// Icons from a file descriptor are supported since version 2 of the interface.
+  #[cfg_attr(feature = "serde", serde(skip))]
+  File(OwnedFd),
+}
+
+// Written out as `OwnedFd` has no `Debug` impl in all dbus versions, the raw fd number is printed instead.
+impl fmt::Debug for Icon {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Themed(names) => f.debug_tuple("Themed").field(names).finish(),
+      Self::Bytes(bytes) => f.debug_tuple("Bytes").field(bytes).finish(),
+      Self::File(fd) => f.debug_tuple("File").field(&fd.as_raw_fd()).finish(),
+    }
+  }
+}
+
+// Written out as `OwnedFd` has no `PartialEq` impl, `Icon::File`s are equal if they hold the same fd number.
+impl PartialEq for Icon {
+  fn eq(&self, other: &Self) -> bool {
+    match (self, other) {
+      (Self::Themed(names), Self::Themed(other)) => names == other,
+      (Self::Bytes(bytes), Self::Bytes(other)) => bytes == other,
+      (Self::File(fd), Self::File(other)) => fd.as_raw_fd() == other.as_raw_fd(),
+      _ => false,
+    }
+  }
+}
+
+impl Eq for Icon {}
+
+impl Icon {
+  /// The wire format of an icon, a `(sv)` of the serialized `GIcon` kind and its data.
+  pub(crate) fn into_arg(self) -> (String, Variant<Box<dyn RefArg>>) {
+    match self {
+      Self::Themed(names) => ("themed".to_string(), Variant(Box::new(names))),
+      Self::Bytes(bytes) => ("bytes".to_string(), Variant(Box::new(bytes))),
+      Self::File(fd) => ("file-descriptor".to_string(), Variant(Box::new(fd))),
+    }
+  }
+}
+
+/// The sound played when a notification is shown.
+///
+/// The sound option was introduced in version 2 of the interface.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Sound {
+  /// The default sound for notifications.
+  Default,
+  /// No sound.
+  Silent,
+  /// An open sound file.
+  #[cfg_attr(feature = "serde", serde(skip))]
+  File(OwnedFd),
+  /// The contents of a sound file.
+  Bytes(Vec<u8>),
+}
+
+impl fmt::Debug for Sound {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Default => f.write_str("Default"),
+      Self::Silent => f.write_str("Silent"),
+      Self::File(fd) => f.debug_tuple("File").field(&fd.as_raw_fd()).finish(),
+      Self::Bytes(bytes) => f.debug_tuple("Bytes").field(bytes).finish(),
+    }
+  }
+}
+
+impl Sound {
+  /// The wire format of a sound, either a string or a `(sv)` of the kind and its data.
+  fn into_arg(self) -> Box<dyn RefArg> {
+    match self {
+      Self::Default => Box::new("default".to_string()),
+      Self::Silent => Box::new("silent".to_string()),
+      Self::File(fd) => Box::new((
+        "file-descriptor".to_string(),
+        Variant(Box::new(fd) as Box<dyn RefArg>),
+      )),
+      Self::Bytes(bytes) => Box::new((
+        "bytes".to_string(),
+        Variant(Box::new(bytes) as Box<dyn RefArg>),
+      )),
+    }
+  }
+}
+
+/// A button shown on a notification.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Button {
+  label: String,
+  action: String,
+  #[cfg_attr(
+    feature = "serde",
+    serde(default, with = "crate::serde_support::option_variant")
+  )]
+  target: Option<Variant<Box<dyn RefArg>>>,
+}
+
+impl Button {
+  /// Creates a new `Button`.
+  ///
+  /// - `label`: User-visible label for the button. Mandatory.
+  /// - `action`: Name of an action that is exported by the application. Mandatory.
+  pub fn new(label: String, action: String) -> Self {
+    Self {
+      label,
+      action,
+      target: None,
+    }
+  }
+
+  /// Target parameter to send along when activating the action.
+  pub fn target(mut self, target: Variant<Box<dyn RefArg>>) -> Self {
+    self.target = Some(target);
+    self
+  }
+}
+
+impl From<Button> for PropMap {
+  fn from(button: Button) -> Self {
+    let mut map = PropMap::new();
+    map.insert("label".to_string(), Variant(Box::new(button.label)));
+    map.insert("action".to_string(), Variant(Box::new(button.action)));
+    if let Some(target) = button.target {
+      map.insert("target".to_string(), target);
+    }
+    map
+  }
+}
+
+/// A notification to show with `NotificationPortal::add_notification()`.
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Notification {
+  title: Option<String>,
+  body: Option<String>,
+  icon: Option<Icon>,
+  sound: Option<Sound>,
+  priority: Option<Priority>,
+  default_action: Option<String>,
+  #[cfg_attr(
+    feature = "serde",
+    serde(default, with = "crate::serde_support::option_variant")
+  )]
+  default_action_target: Option<Variant<Box<dyn RefArg>>>,
+  buttons: Option<Vec<Button>>,
+  category: Option<String>,
+  display_hint: Option<Vec<String>>,
+}
+
+impl Notification {
+  /// Creates a new `Notification` struct with no fields set.
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  /// User-visible string to display as the title.
+  pub fn title(mut self, title: String) -> Self {
+    self.title = Some(title);
+    self
+  }
+
+  /// User-visible string to display as the body.
+  pub fn body(mut self, body: String) -> Self {
+    self.body = Some(body);
+    self
+  }
+
+  /// The icon for the notification.
+  pub fn icon(mut self, icon: Icon) -> Self {
+    self.icon = Some(icon);
+    self
+  }
+
+  /// The sound to play when the notification is shown.
+  ///
+  /// The sound option was introduced in version 2 of the interface.
+  pub fn sound(mut self, sound: Sound) -> Self {
+    self.sound = Some(sound);
+    self
+  }
+
+  /// The priority for the notification.
+  pub fn priority(mut self, priority: Priority) -> Self {
+    self.priority = Some(priority);
+    self
+  }
+
+  /// Name of an action that is exported by the application.
+  /// This action will be activated when the user clicks on the notification.
+  pub fn default_action(mut self, default_action: String) -> Self {
+    self.default_action = Some(default_action);
+    self
+  }
+
+  /// Target parameter to send along when activating the default action.
+  pub fn default_action_target(mut self, default_action_target: Variant<Box<dyn RefArg>>) -> Self {
+    self.default_action_target = Some(default_action_target);
+    self
+  }
+
+  /// Buttons to show on the notification.
+  pub fn buttons(mut self, buttons: Vec<Button>) -> Self {
+    self.buttons = Some(buttons);
+    self
+  }
+
+  /// The type of event the notification is about, such as `im.received` or `call.incoming`.
+  ///
+  /// GNOME Shell uses it to group notifications, other desktops may ignore it.
+  ///
+  /// The category option was introduced in version 2 of the interface.
+  pub fn category(mut self, category: String) -> Self {
+    self.category = Some(category);
+    self
+  }
+
+  /// Hints about how to show the notification, such as `transient`, `tray`, `persistent`,
+  /// `hide-on-lockscreen`, `hide-content-on-lockscreen` or `show-as-new`.
+  ///
+  /// These are honored by GNOME Shell, other desktops may ignore some or all of them.
+  ///
+  /// The display-hint option was introduced in version 2 of the interface.
+  pub fn display_hint(mut self, display_hint: Vec<String>) -> Self {
+    self.display_hint = Some(display_hint);
+    self
+  }
+}
+
+impl VersionedOptions for Notification {
+  fn versioned_options(&self) -> Vec<(&'static str, u32, bool)> {
+    vec![
+      ("sound", 2, self.sound.is_some()),
+      ("category", 2, self.category.is_some()),
+      ("display-hint", 2, self.display_hint.is_some()),
+    ]
+  }
+
+  fn unset_option(&mut self, option: &str) {
+    match option {
+      "sound" => self.sound = None,
+      "category" => self.category = None,
+      "display-hint" => self.display_hint = None,
+      _ => unreachable!("`{}` isn't validated", option),
+    }
+  }
+}
+
+impl From<Notification> for PropMap {
+  fn from(notification: Notification) -> Self {
+    let mut map = PropMap::new();
+    if let Some(title) = notification.title {
+      map.insert("title".to_string(), Variant(Box::new(title)));
+    }
+    if let Some(body) = notification.body {
+      map.insert("body".to_string(), Variant(Box::new(body)));
+    }
+    if let Some(icon) = notification.icon {
+      map.insert("icon".to_string(), Variant(Box::new(icon.into_arg())));
+    }
+    if let Some(sound) = notification.sound {
+      map.insert("sound".to_string(), Variant(sound.into_arg()));
+    }
+    if let Some(priority) = notification.priority {
+      map.insert(
+        "priority".to_string(),
+        Variant(Box::new(priority.as_str().to_string())),
+      );
+    }
+    if let Some(default_action) = notification.default_action {
+      map.insert(
+        "default-action".to_string(),
+        Variant(Box::new(default_action)),
+      );
+    }
+    if let Some(default_action_target) = notification.default_action_target {
+      map.insert("default-action-target".to_string(), default_action_target);
+    }
+    if let Some(buttons) = notification.buttons {
+      let buttons: Vec<PropMap> = buttons.into_iter().map(PropMap::from).collect();
+      map.insert("buttons".to_string(), Variant(Box::new(buttons)));
+    }
+    if let Some(category) = notification.category {
+      map.insert("category".to_string(), Variant(Box::new(category)));
+    }
+    if let Some(display_hint) = notification.display_hint {
+      map.insert("display-hint".to_string(), Variant(Box::new(display_hint)));
+    }
+    map
+  }
+}
+
+impl<'a, T: blocking::BlockingSender, C: std::ops::Deref<Target = T>> NotificationPortal
+  for blocking::Proxy<'a, C>
+{
+  fn add_notification(&self, id: &str, notification: Notification) -> Result<(), PortalError> {
+    self
+      .method_call(
+        INTERFACE,
+        "AddNotification",
+        (id, PropMap::from(notification)),
+      )
+      .map_err(Into::into)
+  }
+
+  fn remove_notification(&self, id: &str) -> Result<(), PortalError> {
+    self
+      .method_call(INTERFACE, "RemoveNotification", (id,))
+      .map_err(Into::into)
+  }
+
+  fn on_action_invoked<'c, F>(
+    &self,
+    connection: &'c Connection,
+    mut callback: F,
+  ) -> Result<SignalGuard<'c>, PortalError>
+  where
+    F: FnMut(String, String, Vec<Variant<Box<dyn RefArg>>>) + Send + 'static,
+  {
+    SignalGuard::for_events(
+      connection,
+      signal_rule(self, INTERFACE, "ActionInvoked"),
+      move |event| {
+        if let PortalEvent::ActionInvoked {
+          id,
+          action,
+          parameters,
+        } = event
+        {
+          callback(id, action, parameters);
+        }
+      },
+    )
+  }
+
+  fn version(&self) -> Result<u32, PortalError> {
+    <Self as org_freedesktop_dbus::Properties>::get(self, INTERFACE, "version").map_err(Into::into)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::{Icon, Notification};
+  use dbus::arg::{PropMap, RefArg};
+
+  #[test]
+  fn themed_icon_shape() {
+    let map = PropMap::from(Notification::new().icon(Icon::Themed(vec![
+      "dialog-information".to_string(),
+      "dialog-information-symbolic".to_string(),
+    ])));
+    let icon = &map["icon"];
+    assert_eq!(&*icon.0.signature(), "(sv)");
+
+    let mut fields = icon.0.as_iter().unwrap();
+    assert_eq!(fields.next().unwrap().as_str(), Some("themed"));
+    let names = fields.next().unwrap();
+    assert_eq!(&*names.signature(), "v");
+    let names: Vec<_> = names
+      .as_iter()
+      .unwrap()
+      .next()
+      .unwrap()
+      .as_iter()
+      .unwrap()
+      .map(|name| name.as_str().unwrap().to_string())
+      .collect();
+    assert_eq!(names, ["dialog-information", "dialog-information-symbolic"]);
+  }
+
+  #[test]
+  fn rich_notification_shape() {
+    use super::{Button, Priority};
+    use dbus::arg::{prop_cast, Variant};
+
+    let map = PropMap::from(
+      Notification::new()
+        .title("New message".to_string())
+        .body("Are you coming tonight?".to_string())
+        .priority(Priority::High)
+        .icon(Icon::Themed(vec!["mail-unread".to_string()]))
+        .buttons(vec![
+          Button::new("Reply".to_string(), "app.reply".to_string())
+            .target(Variant(Box::new("thread-7".to_string()))),
+          Button::new("Mute".to_string(), "app.mute".to_string()).target(Variant(Box::new(7u32))),
+        ]),
+    );
+
+    let mut keys: Vec<_> = map.keys().map(String::as_str).collect();
+    keys.sort_unstable();
+    assert_eq!(keys, ["body", "buttons", "icon", "priority", "title"]);
+    assert_eq!(prop_cast::<String>(&map, "title").unwrap(), "New message");
+    assert_eq!(
+      prop_cast::<String>(&map, "body").unwrap(),
+      "Are you coming tonight?"
+    );
+    assert_eq!(prop_cast::<String>(&map, "priority").unwrap(), "high");
+
+    assert_eq!(&*map["icon"].0.signature(), "(sv)");
+    let (kind, names) = prop_cast::<(String, Variant<Box<dyn RefArg>>)>(&map, "icon").unwrap();
+    assert_eq!(kind, "themed");
+    assert_eq!(
+      names.0.as_any().downcast_ref::<Vec<String>>().unwrap(),
+      &["mail-unread"]
+    );
+
+    assert_eq!(&*map["buttons"].0.signature(), "aa{sv}");
+    let buttons = prop_cast::<Vec<PropMap>>(&map, "buttons").unwrap();
+    assert_eq!(buttons.len(), 2);
+    assert_eq!(prop_cast::<String>(&buttons[0], "label").unwrap(), "Reply");
+    assert_eq!(
+      prop_cast::<String>(&buttons[0], "action").unwrap(),
+      "app.reply"
+    );
+    assert_eq!(buttons[0]["target"].0.as_str(), Some("thread-7"));
+    assert_eq!(prop_cast::<String>(&buttons[1], "label").unwrap(), "Mute");
+    assert_eq!(
+      prop_cast::<String>(&buttons[1], "action").unwrap(),
+      "app.mute"
+    );
+    assert_eq!(&*buttons[1]["target"].0.signature(), "u");
+    assert_eq!(buttons[1]["target"].0.as_u64(), Some(7));
+  }
+
+  #[test]
+  fn sound_shape() {
+    use super::Sound;
+
+    let map = PropMap::from(Notification::new().sound(Sound::Silent));
+    assert_eq!(map["sound"].0.as_str(), Some("silent"));
+
+    let map = PropMap::from(Notification::new().sound(Sound::Bytes(vec![1, 2])));
+    assert_eq!(&*map["sound"].0.signature(), "(sv)");
+  }
+
+  #[test]
+  fn category_and_display_hint_only_when_set() {
+    let map = PropMap::from(Notification::new().title("title".to_string()));
+    assert!(!map.contains_key("category"));
+    assert!(!map.contains_key("display-hint"));
+
+    let map = PropMap::from(
+      Notification::new()
+        .category("im.received".to_string())
+        .display_hint(vec!["transient".to_string(), "show-as-new".to_string()]),
+    );
+    assert_eq!(map["category"].0.as_str(), Some("im.received"));
+    assert_eq!(&*map["display-hint"].0.signature(), "as");
+    let hints: Vec<_> = map["display-hint"]
+      .0
+      .as_iter()
+      .unwrap()
+      .map(|hint| hint.as_str().unwrap().to_string())
+      .collect();
+    assert_eq!(hints, ["transient", "show-as-new"]);
+  }
+
+  #[test]
+  fn version_2_options_unset_for_version_1() {
+    use crate::{PortalError, VersionedOptions};
+
+    let notification = Notification::new()
+      .title("title".to_string())
+      .category("im.received".to_string())
+      .display_hint(vec!["transient".to_string()]);
+    assert!(matches!(
+      notification.validate_against(1),
+      Err(PortalError::UnsupportedOption {
+        option: "category",
+        required: 2,
+        version: 1,
+      })
+    ));
+    let map = PropMap::from(notification.supported_by(1));
+    assert!(map.contains_key("title"));
+    assert!(!map.contains_key("category"));
+    assert!(!map.contains_key("display-hint"));
+  }
+
+  #[test]
+  fn icon_eq_and_clone() {
+    let icon = Icon::Themed(vec!["dialog-information".to_string()]);
+    assert_eq!(icon.clone(), icon);
+    assert_ne!(icon, Icon::Bytes(vec![]));
+
+    let file = Icon::File(crate::fd::from_file(
+      std::fs::File::open(env!("CARGO_MANIFEST_DIR")).unwrap(),
+    ));
+    assert_eq!(file, file);
+    // The clone holds a duplicate of the fd, with another number.
+    assert_ne!(file.clone(), file);
+  }
+
+  #[test]
+  fn icon_debug_prints_raw_fd() {
+    use dbus::arg::OwnedFd;
+    use std::{fs::File, os::unix::io::IntoRawFd};
+
+    let fd = File::open(env!("CARGO_MANIFEST_DIR"))
+      .unwrap()
+      .into_raw_fd();
+    let icon = Icon::File(unsafe { OwnedFd::new(fd) });
+    assert_eq!(format!("{:?}", icon), format!("File({})", fd));
+  }
+}
@@ -2,18 +2,34 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use crate::PortalError;
+#[cfg(feature = "dbus")]
+use crate::{
+  fd::{from_file, open_path},
+  file_uri_path, generate_handle_token, insert_activation_token,
+  trace::{self, CallSpan},
+  CancelToken, IntoPortalFd, ParentWindowArg, Permission, PortalError, ResponseCode,
+  ResponseListener, Results,
+};
+use crate::{ActivationToken, VersionedOptions};
 
+#[cfg(feature = "dbus")]
 use dbus::{
-  arg::{OwnedFd, PropMap, Variant},
-  blocking::{self, stdintf::org_freedesktop_dbus},
+  arg::{PropMap, Variant},
+  blocking::{self, stdintf::org_freedesktop_dbus, Connection},
   Path,
 };
+#[cfg(feature = "dbus")]
+use std::{ffi::OsString, fs::File, io, os::unix::ffi::OsStringExt, path::PathBuf, time::Duration};
+
+#[cfg(feature = "dbus")]
+const INTERFACE: &str = "org.freedesktop.portal.OpenURI";
 
-const INTERFACE: &'static str = "org.freedesktop.portal.OpenURI";
+/// The schemes `OpenURI::can_open_scheme()` assumes a handler for, when the portal can't be asked.
+pub const DEFAULT_OPEN_SCHEMES: &[&str] = &["http", "https", "mailto"];
 
 /// Implementation of the `org.freedesktop.portal.OpenURI` Portal API.
 /// See also https://flatpak.github.io/xdg-desktop-portal/#gdbus-org.freedesktop.portal.OpenURI
+#[cfg(feature = "dbus")]
 pub trait OpenURI {
   /// Asks to open a uri.
   ///
@@ -24,7 +40,7 @@ pub trait OpenURI {
   /// - `uri`: The uri to open
   fn open_uri(
     &self,
-    parent_window: &str,
+    parent_window: impl Into<ParentWindowArg>,
     uri: &str,
     options: OpenURIOptions,
   ) -> Result<Path<'static>, PortalError>;
@@ -35,8 +51,8 @@ pub trait OpenURI {
   /// - `fd`: File descriptor for the file to open.
   fn open_file(
     &self,
-    parent_window: &str,
-    fd: OwnedFd,
+    parent_window: impl Into<ParentWindowArg>,
+    fd: impl IntoPortalFd,
     options: OpenURIOptions,
   ) -> Result<Path<'static>, PortalError>;
 
@@ -46,24 +62,175 @@ pub trait OpenURI {
   /// - `fd`: File descriptor a file.
   fn open_directory(
     &self,
-    parent_window: &str,
-    fd: OwnedFd,
+    parent_window: impl Into<ParentWindowArg>,
+    fd: impl IntoPortalFd,
+    options: OpenURIOptions,
+  ) -> Result<Path<'static>, PortalError>;
+
+  /// Opens `uri_or_path` with the method that supports it.
+  ///
+  /// A `file://` uri or an absolute path is opened read-only and passed to `OpenURI::open_file()`,
+  /// as `OpenURI::open_uri()` rejects local files. Anything else is passed to `OpenURI::open_uri()`.
+  fn open(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    uri_or_path: &str,
+    options: OpenURIOptions,
+  ) -> Result<Path<'static>, PortalError>;
+
+  /// Same as `OpenURI::open_uri()`, but first reads the version of the interface
+  /// and rejects options it doesn't support, see `VersionedOptions::validate_against()`.
+  fn open_uri_checked(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    uri: &str,
+    options: OpenURIOptions,
+  ) -> Result<Path<'static>, PortalError>;
+
+  /// Same as `OpenURI::open_file()`, but takes ownership of an open `file`.
+  ///
+  /// The file descriptor is duplicated into the portal when the message is sent,
+  /// so `file` being closed once the call returns doesn't affect the request.
+  fn open_file_owned(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    file: File,
+    options: OpenURIOptions,
+  ) -> Result<Path<'static>, PortalError>;
+
+  /// Same as `OpenURI::open_file()`, but opens the file at `path` read-only first.
+  fn open_file_path(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    path: impl AsRef<std::path::Path>,
     options: OpenURIOptions,
   ) -> Result<Path<'static>, PortalError>;
 
+  /// Same as `OpenURI::open_directory()`, but opens the file at `path` read-only first.
+  fn open_directory_path(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    path: impl AsRef<std::path::Path>,
+    options: OpenURIOptions,
+  ) -> Result<Path<'static>, PortalError>;
+
+  /// Exports the file at `path` to the document portal, and asks to open it in an application
+  /// that may write to it.
+  ///
+  /// The file is added to the document store for this session with read and write permissions for
+  /// `peer_app_id`, the sandboxed application expected to open it. Its path in the document portal's
+  /// fuse filesystem is then opened for reading and writing, and passed to `OpenURI::open_file()`
+  /// with the `writable` option set.
+  ///
+  /// - `documents`: A proxy for the document portal, see `new_documents_blocking()`.
+  /// - `parent_window`: Identifier for the application window, see crate comments for common conventions.
+  /// - `path`: The file to open, which must exist.
+  /// - `peer_app_id`: The application id to grant write access to, e.g. `org.gnome.TextEditor`.
+  fn open_writable_file(
+    &self,
+    documents: &impl crate::Documents,
+    parent_window: impl Into<ParentWindowArg>,
+    path: impl AsRef<std::path::Path>,
+    peer_app_id: &str,
+    options: OpenURIOptions,
+  ) -> Result<Path<'static>, PortalError>;
+
+  /// Shows the file at `path` in the file browser, with the file selected where the desktop supports it.
+  ///
+  /// Since version 3 of the interface, this passes the file itself to `OpenURI::open_directory()`,
+  /// and the backend opens its directory. File managers implementing `org.freedesktop.FileManager1`,
+  /// such as GNOME Files and Dolphin on KDE Plasma, also highlight the file; others just open the directory.
+  /// Older versions only have `OpenURI::open_file()`, so the parent directory is opened in the default
+  /// application for directories instead, without selecting the file.
+  ///
+  /// This reads the version of the interface first, which costs a round trip.
+  ///
+  /// - `parent_window`: Identifier for the application window, see crate comments for common conventions.
+  /// - `path`: The file to show, which must exist.
+  fn reveal_file(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    path: impl AsRef<std::path::Path>,
+    options: OpenURIOptions,
+  ) -> Result<Path<'static>, PortalError>;
+
+  /// Checks whether an application is available to open uris with `scheme`, such as `https`,
+  /// for example before offering an "open in browser" button.
+  ///
+  /// This is a best-effort check. Portals implementing version 5 of the interface are asked with `SchemeSupported`.
+  /// Older portals can't be asked, in which case only the schemes in `DEFAULT_OPEN_SCHEMES` are assumed to have
+  /// a handler, see `OpenURI::can_open_scheme_with()` to assume others.
+  fn can_open_scheme(&self, scheme: &str) -> Result<bool, PortalError>;
+
+  /// Same as `OpenURI::can_open_scheme()`, but assumes a handler for the schemes in `fallback`
+  /// when the portal can't be asked.
+  fn can_open_scheme_with(&self, scheme: &str, fallback: &[&str]) -> Result<bool, PortalError>;
+
+  /// Same as `OpenURI::open_uri()`, but blocks until the user interaction has ended.
+  ///
+  /// Returns the response code and results of the `org.freedesktop.portal.Request::Response` signal,
+  /// see `Request::wait_response()`. If no `handle_token` is set, one is generated, so the signal match
+  /// for the predicted request path can be added on `connection` before the request is made.
+  fn open_uri_and_wait(
+    &self,
+    connection: &Connection,
+    parent_window: impl Into<ParentWindowArg>,
+    uri: &str,
+    options: OpenURIOptions,
+    timeout: Duration,
+  ) -> Result<(ResponseCode, PropMap), PortalError>;
+
+  /// Same as `OpenURI::open_uri_and_wait()`, but can be cancelled from another thread with `cancel_token`,
+  /// for example when the application window closes.
+  ///
+  /// Once cancelled, the request is closed, which dismisses the dialog, and this returns `PortalError::Cancelled`
+  /// right away: the portal doesn't emit a `Response` for a closed request, so there is nothing left to wait for.
+  fn open_uri_and_wait_cancellable(
+    &self,
+    connection: &Connection,
+    parent_window: impl Into<ParentWindowArg>,
+    uri: &str,
+    options: OpenURIOptions,
+    timeout: Duration,
+    cancel_token: &CancelToken,
+  ) -> Result<(ResponseCode, PropMap), PortalError>;
+
+  /// Same as `OpenURI::open_file()`, but blocks until the user interaction has ended.
+  ///
+  /// See `OpenURI::open_uri_and_wait()` for details.
+  fn open_file_and_wait(
+    &self,
+    connection: &Connection,
+    parent_window: impl Into<ParentWindowArg>,
+    fd: impl IntoPortalFd,
+    options: OpenURIOptions,
+    timeout: Duration,
+  ) -> Result<(ResponseCode, PropMap), PortalError>;
+
+  /// Same as `OpenURI::open_directory()`, but blocks until the user interaction has ended.
+  ///
+  /// See `OpenURI::open_uri_and_wait()` for details.
+  fn open_directory_and_wait(
+    &self,
+    connection: &Connection,
+    parent_window: impl Into<ParentWindowArg>,
+    fd: impl IntoPortalFd,
+    options: OpenURIOptions,
+    timeout: Duration,
+  ) -> Result<(ResponseCode, PropMap), PortalError>;
+
   /// Reads the "version" property for this D-Bus interface.
   fn version(&self) -> Result<u32, PortalError>;
 }
 
 /// Optional arguments for the OpenURI methods.
-#[derive(Default)]
+#[derive(Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OpenURIOptions {
   handle_token: Option<String>,
   writable: Option<bool>,
-  #[cfg(feature = "spec-v3")]
   ask: Option<bool>,
-  #[cfg(feature = "spec-v4")]
-  activation_token: Option<String>,
+  activation_token: Option<ActivationToken>,
 }
 
 impl OpenURIOptions {
@@ -75,8 +242,8 @@ impl OpenURIOptions {
   /// A string that will be used as the last element of the @handle. Must be a valid
   /// object path element. See the #org.freedesktop.portal.Request documentation for
   /// more information about the @handle.
-  pub fn handle_token(mut self, handle_token: String) -> Self {
-    self.handle_token = Some(handle_token);
+  pub fn handle_token(mut self, handle_token: impl Into<String>) -> Self {
+    self.handle_token = Some(handle_token.into());
     self
   }
 
@@ -94,22 +261,48 @@ impl OpenURIOptions {
   /// the portal may use a default or pick the last choice.
   ///
   /// The ask option was introduced in version 3 of the interface.
-  #[cfg(feature = "spec-v3")]
   pub fn ask(mut self, ask: bool) -> Self {
     self.ask = Some(ask);
     self
   }
 
   /// A token that can be used to activate the chosen application.
+  /// See `ActivationToken::from_env()`, or with the `wayland` feature `ActivationToken::request_wayland()`.
   ///
   /// The activation_token option was introduced in version 4 of the interface.
-  #[cfg(feature = "spec-v4")]
-  pub fn activation_token(mut self, activation_token: String) -> Self {
-    self.activation_token = Some(activation_token);
+  pub fn activation_token(mut self, activation_token: impl Into<ActivationToken>) -> Self {
+    self.activation_token = Some(activation_token.into());
+    self
+  }
+
+  /// Returns the `handle_token`, generating one first if it's unset.
+  #[cfg(feature = "dbus")]
+  pub(crate) fn ensure_handle_token(&mut self) -> String {
     self
+      .handle_token
+      .get_or_insert_with(generate_handle_token)
+      .clone()
   }
 }
 
+impl VersionedOptions for OpenURIOptions {
+  fn versioned_options(&self) -> Vec<(&'static str, u32, bool)> {
+    vec![
+      ("ask", 3, self.ask.is_some()),
+      ("activation_token", 4, self.activation_token.is_some()),
+    ]
+  }
+
+  fn unset_option(&mut self, option: &str) {
+    match option {
+      "ask" => self.ask = None,
+      "activation_token" => self.activation_token = None,
+      _ => unreachable!("`{}` isn't validated", option),
+    }
+  }
+}
+
+#[cfg(feature = "dbus")]
 impl From<OpenURIOptions> for PropMap {
   fn from(options: OpenURIOptions) -> Self {
     let mut map = PropMap::new();
@@ -119,82 +312,387 @@ impl From<OpenURIOptions> for PropMap {
     if let Some(writable) = options.writable {
       map.insert("writable".to_string(), Variant(Box::new(writable)));
     }
-    #[cfg(feature = "spec-v3")]
     if let Some(ask) = options.ask {
       map.insert("ask".to_string(), Variant(Box::new(ask)));
     }
-    #[cfg(feature = "spec-v4")]
+    insert_activation_token(&mut map, options.activation_token);
+    map
+  }
+}
+
+#[cfg(feature = "zbus")]
+impl From<OpenURIOptions>
+  for std::collections::HashMap<&'static str, zbus::zvariant::Value<'static>>
+{
+  fn from(options: OpenURIOptions) -> Self {
+    let mut map = Self::new();
+    if let Some(handle_token) = options.handle_token {
+      map.insert("handle_token", handle_token.into());
+    }
+    if let Some(writable) = options.writable {
+      map.insert("writable", writable.into());
+    }
+    if let Some(ask) = options.ask {
+      map.insert("ask", ask.into());
+    }
     if let Some(activation_token) = options.activation_token {
-      map.insert(
-        "activation_token".to_string(),
-        Variant(Box::new(activation_token)),
-      );
+      map.insert("activation_token", activation_token.0.into());
     }
     map
   }
 }
 
+#[cfg(feature = "dbus")]
+impl TryFrom<&PropMap> for OpenURIOptions {
+  type Error = PortalError;
+
+  /// Reads back the options a caller sent, for example when implementing a fake portal.
+  /// Unknown keys are ignored.
+  fn try_from(map: &PropMap) -> Result<Self, Self::Error> {
+    let map = Results::new(map);
+    Ok(Self {
+      handle_token: map.get_str("handle_token")?.map(ToString::to_string),
+      writable: map.get_bool("writable")?,
+      ask: map.get_bool("ask")?,
+      activation_token: map.get_str("activation_token")?.map(ActivationToken::from),
+    })
+  }
+}
+
+/// The local path of a `file://` uri or an absolute path, with the percent-encoding of a uri decoded.
+#[cfg(feature = "dbus")]
+fn local_path(uri_or_path: &str) -> Option<PathBuf> {
+  if uri_or_path.starts_with('/') {
+    return Some(PathBuf::from(uri_or_path));
+  }
+  file_uri_path(uri_or_path)
+}
+
+/// The features the running `OpenURI` portal supports, from a single `OpenURI::version()` call,
+/// see `Portals::open_uri_capabilities()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OpenURICapabilities {
+  version: u32,
+}
+
+impl OpenURICapabilities {
+  /// The capabilities of the `version` of the interface the portal implements.
+  pub fn from_version(version: u32) -> Self {
+    Self { version }
+  }
+
+  /// The version of the interface the portal implements.
+  pub fn version(&self) -> u32 {
+    self.version
+  }
+
+  /// Whether `OpenURI::open_directory()` is supported.
+  pub fn supports_open_directory(&self) -> bool {
+    self.version >= 3
+  }
+
+  /// Whether the `ask` option is supported.
+  pub fn supports_ask(&self) -> bool {
+    self.version >= 3
+  }
+
+  /// Whether the `activation_token` option is supported.
+  pub fn supports_activation_token(&self) -> bool {
+    self.version >= 4
+  }
+}
+
+#[cfg(feature = "dbus")]
 impl<'a, T: blocking::BlockingSender, C: std::ops::Deref<Target = T>> OpenURI
   for blocking::Proxy<'a, C>
 {
   fn open_uri(
     &self,
-    parent_window: &str,
+    parent_window: impl Into<ParentWindowArg>,
     uri: &str,
     options: OpenURIOptions,
   ) -> Result<Path<'static>, PortalError> {
+    let parent_window: ParentWindowArg = parent_window.into();
+    let options = PropMap::from(options);
+    let _span = CallSpan::enter(
+      INTERFACE,
+      "OpenURI",
+      parent_window.as_str(),
+      trace::handle_token(&options),
+    );
     self
-      .method_call(
-        INTERFACE,
-        "OpenURI",
-        (parent_window, uri, PropMap::from(options)),
-      )
-      .and_then(|r: (Path<'static>,)| Ok(r.0))
+      .method_call(INTERFACE, "OpenURI", (parent_window.as_str(), uri, options))
+      .map(|r: (Path<'static>,)| trace::request_created(r.0))
       .map_err(Into::into)
   }
 
   fn open_file(
     &self,
-    parent_window: &str,
-    fd: OwnedFd,
+    parent_window: impl Into<ParentWindowArg>,
+    fd: impl IntoPortalFd,
     options: OpenURIOptions,
   ) -> Result<Path<'static>, PortalError> {
+    let parent_window: ParentWindowArg = parent_window.into();
+    let options = PropMap::from(options);
+    let _span = CallSpan::enter(
+      INTERFACE,
+      "OpenFile",
+      parent_window.as_str(),
+      trace::handle_token(&options),
+    );
     self
       .method_call(
         INTERFACE,
         "OpenFile",
-        (parent_window, fd, PropMap::from(options)),
+        (parent_window.as_str(), fd.into_portal_fd(), options),
       )
-      .and_then(|r: (Path<'static>,)| Ok(r.0))
+      .map(|r: (Path<'static>,)| trace::request_created(r.0))
       .map_err(Into::into)
   }
 
   fn open_directory(
     &self,
-    parent_window: &str,
-    fd: OwnedFd,
+    parent_window: impl Into<ParentWindowArg>,
+    fd: impl IntoPortalFd,
     options: OpenURIOptions,
   ) -> Result<Path<'static>, PortalError> {
+    let parent_window: ParentWindowArg = parent_window.into();
+    let options = PropMap::from(options);
+    let _span = CallSpan::enter(
+      INTERFACE,
+      "OpenDirectory",
+      parent_window.as_str(),
+      trace::handle_token(&options),
+    );
     self
       .method_call(
         INTERFACE,
         "OpenDirectory",
-        (parent_window, fd, PropMap::from(options)),
+        (parent_window.as_str(), fd.into_portal_fd(), options),
       )
-      .and_then(|r: (Path<'static>,)| Ok(r.0))
+      .map(|r: (Path<'static>,)| trace::request_created(r.0))
       .map_err(Into::into)
   }
 
+  fn open(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    uri_or_path: &str,
+    options: OpenURIOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    match local_path(uri_or_path) {
+      Some(path) => self.open_file(parent_window, open_path(&path, libc::O_RDONLY)?, options),
+      None => self.open_uri(parent_window, uri_or_path, options),
+    }
+  }
+
+  fn open_uri_checked(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    uri: &str,
+    options: OpenURIOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    options.validate_against(self.version()?)?;
+    self.open_uri(parent_window, uri, options)
+  }
+
+  fn open_file_owned(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    file: File,
+    options: OpenURIOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    self.open_file(parent_window, from_file(file), options)
+  }
+
+  fn open_file_path(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    path: impl AsRef<std::path::Path>,
+    options: OpenURIOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    self.open_file(
+      parent_window,
+      open_path(path.as_ref(), libc::O_RDONLY)?,
+      options,
+    )
+  }
+
+  fn open_directory_path(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    path: impl AsRef<std::path::Path>,
+    options: OpenURIOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    self.open_directory(
+      parent_window,
+      open_path(path.as_ref(), libc::O_RDONLY)?,
+      options,
+    )
+  }
+
+  fn open_writable_file(
+    &self,
+    documents: &impl crate::Documents,
+    parent_window: impl Into<ParentWindowArg>,
+    path: impl AsRef<std::path::Path>,
+    peer_app_id: &str,
+    options: OpenURIOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    let path = path.as_ref();
+    let file_name = path
+      .file_name()
+      .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "path has no file name"))?;
+    let doc_id = documents.add(open_path(path, libc::O_PATH)?, true, false)?;
+    documents.grant_permissions(&doc_id, peer_app_id, &[Permission::Read, Permission::Write])?;
+    let document = PathBuf::from(OsString::from_vec(documents.get_mount_point()?))
+      .join(doc_id)
+      .join(file_name);
+    self.open_file(
+      parent_window,
+      open_path(&document, libc::O_RDWR)?,
+      options.writable(true),
+    )
+  }
+
+  fn reveal_file(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    path: impl AsRef<std::path::Path>,
+    options: OpenURIOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    let path = path.as_ref();
+    if OpenURICapabilities::from_version(OpenURI::version(self)?).supports_open_directory() {
+      return self.open_directory(parent_window, open_path(path, libc::O_RDONLY)?, options);
+    }
+    let directory = path
+      .parent()
+      .filter(|directory| !directory.as_os_str().is_empty())
+      .unwrap_or_else(|| std::path::Path::new("."));
+    self.open_file(
+      parent_window,
+      open_path(directory, libc::O_RDONLY | libc::O_DIRECTORY)?,
+      options,
+    )
+  }
+
+  fn can_open_scheme(&self, scheme: &str) -> Result<bool, PortalError> {
+    self.can_open_scheme_with(scheme, DEFAULT_OPEN_SCHEMES)
+  }
+
+  fn can_open_scheme_with(&self, scheme: &str, fallback: &[&str]) -> Result<bool, PortalError> {
+    let reply: Result<(bool,), dbus::Error> =
+      self.method_call(INTERFACE, "SchemeSupported", (scheme, PropMap::new()));
+    match reply {
+      Ok((supported,)) => Ok(supported),
+      Err(err) if err.name() == Some("org.freedesktop.DBus.Error.UnknownMethod") => Ok(
+        fallback
+          .iter()
+          .any(|known| known.eq_ignore_ascii_case(scheme)),
+      ),
+      Err(err) => Err(err.into()),
+    }
+  }
+
+  fn open_uri_and_wait(
+    &self,
+    connection: &Connection,
+    parent_window: impl Into<ParentWindowArg>,
+    uri: &str,
+    mut options: OpenURIOptions,
+    timeout: Duration,
+  ) -> Result<(ResponseCode, PropMap), PortalError> {
+    let parent_window: ParentWindowArg = parent_window.into();
+    let listener = ResponseListener::for_call(
+      connection,
+      &self.destination,
+      INTERFACE,
+      "OpenURI",
+      parent_window.as_str(),
+      &options.ensure_handle_token(),
+    )?;
+    let path = self.open_uri(parent_window, uri, options)?;
+    listener.wait(&path, timeout)
+  }
+
+  fn open_uri_and_wait_cancellable(
+    &self,
+    connection: &Connection,
+    parent_window: impl Into<ParentWindowArg>,
+    uri: &str,
+    mut options: OpenURIOptions,
+    timeout: Duration,
+    cancel_token: &CancelToken,
+  ) -> Result<(ResponseCode, PropMap), PortalError> {
+    let parent_window: ParentWindowArg = parent_window.into();
+    let listener = ResponseListener::for_call(
+      connection,
+      &self.destination,
+      INTERFACE,
+      "OpenURI",
+      parent_window.as_str(),
+      &options.ensure_handle_token(),
+    )?;
+    let path = self.open_uri(parent_window, uri, options)?;
+    listener.wait_cancellable(&path, timeout, cancel_token)
+  }
+
+  fn open_file_and_wait(
+    &self,
+    connection: &Connection,
+    parent_window: impl Into<ParentWindowArg>,
+    fd: impl IntoPortalFd,
+    mut options: OpenURIOptions,
+    timeout: Duration,
+  ) -> Result<(ResponseCode, PropMap), PortalError> {
+    let parent_window: ParentWindowArg = parent_window.into();
+    let listener = ResponseListener::for_call(
+      connection,
+      &self.destination,
+      INTERFACE,
+      "OpenFile",
+      parent_window.as_str(),
+      &options.ensure_handle_token(),
+    )?;
+    let path = self.open_file(parent_window, fd, options)?;
+    listener.wait(&path, timeout)
+  }
+
+  fn open_directory_and_wait(
+    &self,
+    connection: &Connection,
+    parent_window: impl Into<ParentWindowArg>,
+    fd: impl IntoPortalFd,
+    mut options: OpenURIOptions,
+    timeout: Duration,
+  ) -> Result<(ResponseCode, PropMap), PortalError> {
+    let parent_window: ParentWindowArg = parent_window.into();
+    let listener = ResponseListener::for_call(
+      connection,
+      &self.destination,
+      INTERFACE,
+      "OpenDirectory",
+      parent_window.as_str(),
+      &options.ensure_handle_token(),
+    )?;
+    let path = self.open_directory(parent_window, fd, options)?;
+    listener.wait(&path, timeout)
+  }
+
   fn version(&self) -> Result<u32, PortalError> {
-    <Self as org_freedesktop_dbus::Properties>::get(&self, INTERFACE, "version").map_err(Into::into)
+    <Self as org_freedesktop_dbus::Properties>::get(self, INTERFACE, "version").map_err(Into::into)
   }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "dbus"))]
 mod test {
-  use super::{OpenURI, OpenURIOptions};
-  use crate::new_blocking;
-  use dbus::blocking::Connection;
+  use super::{local_path, OpenURI, OpenURIOptions};
+  use crate::{new_blocking, VersionedOptions};
+  use dbus::{
+    arg::{PropMap, Variant},
+    blocking::Connection,
+  };
   use std::time::Duration;
 
   #[test]
@@ -208,4 +706,318 @@ mod test {
       .open_uri("", "https://github.com/tauri-apps/tauri#open_uri_ask", opts)
       .unwrap();
   }
+
+  #[test]
+  #[cfg(feature = "testing")]
+  fn reveal_file_by_version() {
+    use crate::testing::{MockPortal, TestBus};
+
+    let file = std::env::temp_dir().join("xdg-portal-reveal-file.txt");
+    std::fs::write(&file, b"").unwrap();
+    for (version, member) in [(3, "OpenDirectory"), (2, "OpenFile")] {
+      let bus = TestBus::new().unwrap();
+      let mock = MockPortal::start(&bus, version).unwrap();
+      let conn = bus.connect().unwrap();
+      let portals = new_blocking(Duration::from_secs(2), &conn);
+      portals
+        .reveal_file("", &file, OpenURIOptions::new())
+        .unwrap();
+      let calls = mock.take_calls();
+      assert_eq!(calls.len(), 1);
+      assert_eq!(calls[0].member, member);
+    }
+    std::fs::remove_file(&file).unwrap();
+  }
+
+  #[test]
+  #[cfg(feature = "testing")]
+  fn can_open_scheme_by_version() {
+    use crate::testing::{MockPortal, TestBus};
+
+    let bus = TestBus::new().unwrap();
+    let _mock = MockPortal::start(&bus, 5).unwrap();
+    let conn = bus.connect().unwrap();
+    let portals = new_blocking(Duration::from_secs(2), &conn);
+    assert!(portals.can_open_scheme("https").unwrap());
+    assert!(!portals.can_open_scheme("mailto").unwrap());
+
+    let bus = TestBus::new().unwrap();
+    let _mock = MockPortal::start(&bus, 4).unwrap();
+    let conn = bus.connect().unwrap();
+    let portals = new_blocking(Duration::from_secs(2), &conn);
+    assert!(portals.can_open_scheme("HTTPS").unwrap());
+    assert!(portals.can_open_scheme("mailto").unwrap());
+    assert!(!portals.can_open_scheme("steam").unwrap());
+    assert!(portals.can_open_scheme_with("steam", &["steam"]).unwrap());
+    assert!(!portals.can_open_scheme_with("https", &[]).unwrap());
+  }
+
+  #[test]
+  #[cfg(feature = "testing")]
+  fn open_uri_ask_mocked() {
+    use crate::{
+      testing::{MockPortal, TestBus},
+      ResponseCode,
+    };
+
+    let bus = TestBus::new().unwrap();
+    let mock = MockPortal::start(&bus, 3).unwrap();
+    let conn = bus.connect().unwrap();
+    let timeout = Duration::from_secs(2);
+    let portals = new_blocking(timeout, &conn);
+
+    assert_eq!(portals.version().unwrap(), 3);
+    let opts = OpenURIOptions::new().ask(true);
+    let (code, _) = portals
+      .open_uri_and_wait(&conn, "x11:1f", "https://example.com", opts, timeout)
+      .unwrap();
+    assert_eq!(code, ResponseCode::Success);
+
+    let calls = mock.take_calls();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].member, "OpenURI");
+    let (parent_window, uri, options): (String, String, PropMap) =
+      calls[0].message.read3().unwrap();
+    assert_eq!(parent_window, "x11:1f");
+    assert_eq!(uri, "https://example.com");
+    let options = OpenURIOptions::try_from(&options).unwrap();
+    assert_eq!(
+      options,
+      OpenURIOptions::new()
+        .ask(true)
+        .handle_token(options.handle_token.clone().unwrap())
+    );
+  }
+
+  #[test]
+  #[cfg(feature = "testing")]
+  fn open_uri_and_wait_catches_immediate_response() {
+    use crate::{
+      testing::{MockPortal, TestBus},
+      ResponseCode,
+    };
+
+    // The mock emits the `Response` right after its reply, without any delay,
+    // so it's only received if the signal match was added before the call.
+    let bus = TestBus::new().unwrap();
+    let _mock = MockPortal::start(&bus, 4).unwrap();
+    let conn = bus.connect().unwrap();
+    let timeout = Duration::from_secs(2);
+    let portals = new_blocking(timeout, &conn);
+
+    for _ in 0..20 {
+      let (code, _) = portals
+        .open_uri_and_wait(
+          &conn,
+          "",
+          "https://example.com",
+          OpenURIOptions::new(),
+          timeout,
+        )
+        .unwrap();
+      assert_eq!(code, ResponseCode::Success);
+    }
+  }
+
+  #[test]
+  #[cfg(feature = "testing")]
+  fn open_uri_and_wait_on_other_destination() {
+    use crate::{
+      new_blocking_with,
+      testing::{MockPortal, TestBus},
+      ResponseCode, PATH,
+    };
+
+    let bus = TestBus::new().unwrap();
+    let mock = MockPortal::start_as(&bus, "org.example.Portal", 4).unwrap();
+    let conn = bus.connect().unwrap();
+    let timeout = Duration::from_secs(2);
+    let portals = new_blocking_with("org.example.Portal", PATH, timeout, &conn);
+
+    let (code, _) = portals
+      .open_uri_and_wait(
+        &conn,
+        "",
+        "https://example.com",
+        OpenURIOptions::new(),
+        timeout,
+      )
+      .unwrap();
+    assert_eq!(code, ResponseCode::Success);
+    assert_eq!(mock.take_calls().len(), 1);
+  }
+
+  #[test]
+  fn open_uri_options_round_trip() {
+    let opts = OpenURIOptions::new()
+      .handle_token("portal_test")
+      .writable(true);
+    let map = PropMap::from(opts);
+    let expected = OpenURIOptions::new()
+      .handle_token("portal_test".to_string())
+      .writable(true);
+    assert_eq!(OpenURIOptions::try_from(&map).unwrap(), expected);
+
+    let mut map = PropMap::new();
+    map.insert("writable".to_string(), Variant(Box::new("yes".to_string())));
+    assert!(OpenURIOptions::try_from(&map).is_err());
+  }
+
+  #[test]
+  fn validate_options_against_version() {
+    let opts = OpenURIOptions::new().activation_token("token".to_string());
+    assert!(opts.validate_against(4).is_ok());
+    assert!(opts.validate_against(3).is_err());
+    assert!(OpenURIOptions::new().validate_against(1).is_ok());
+  }
+
+  #[test]
+  fn supported_by_unsets_newer_options() {
+    let opts = || {
+      OpenURIOptions::new()
+        .writable(true)
+        .ask(true)
+        .activation_token("token".to_string())
+    };
+    assert_eq!(opts().supported_by(4), opts());
+    assert_eq!(
+      opts().supported_by(3),
+      OpenURIOptions::new().writable(true).ask(true)
+    );
+    assert_eq!(opts().supported_by(2), OpenURIOptions::new().writable(true));
+  }
+
+  #[test]
+  fn open_file_path_closes_fd_on_error() {
+    use crate::{fd::fds_open_on, DESTINATION, PATH};
+    use dbus::{
+      blocking::{BlockingSender, Proxy},
+      Message,
+    };
+    use std::{cell::RefCell, os::unix::io::RawFd, path::PathBuf};
+
+    /// Fails every call, after recording the descriptors open on `file` while the call is made.
+    struct Failing {
+      file: PathBuf,
+      fds: RefCell<Vec<RawFd>>,
+    }
+
+    impl BlockingSender for Failing {
+      fn send_with_reply_and_block(&self, _: Message, _: Duration) -> Result<Message, dbus::Error> {
+        self.fds.borrow_mut().extend(fds_open_on(&self.file));
+        Err(dbus::Error::new_custom(
+          "org.freedesktop.DBus.Error.Failed",
+          "failed",
+        ))
+      }
+    }
+
+    // Only this test opens the file, so descriptors of other tests running meanwhile don't interfere.
+    let file = std::env::temp_dir().join(format!("xdg-portal-closes-fd-{}", std::process::id()));
+    std::fs::write(&file, b"").unwrap();
+    let file = std::fs::canonicalize(&file).unwrap();
+    let sender = Failing {
+      file: file.clone(),
+      fds: RefCell::new(Vec::new()),
+    };
+    let proxy = Proxy::new(DESTINATION, PATH, Duration::from_secs(1), &sender);
+    assert!(proxy
+      .open_file_path("", &file, OpenURIOptions::new())
+      .is_err());
+    assert!(!sender.fds.borrow().is_empty());
+    assert!(fds_open_on(&file).is_empty());
+    assert!(proxy
+      .open_file_path("", "/nonexistent/file", OpenURIOptions::new())
+      .is_err());
+    std::fs::remove_file(&file).unwrap();
+  }
+
+  #[test]
+  fn local_path_of_uri() {
+    assert_eq!(
+      local_path("/tmp/a b.txt").unwrap().to_str(),
+      Some("/tmp/a b.txt")
+    );
+    assert_eq!(
+      local_path("file:///tmp/a%20b.txt").unwrap().to_str(),
+      Some("/tmp/a b.txt")
+    );
+    assert!(local_path("https://example.com/a.txt").is_none());
+    assert!(local_path("tmp/a.txt").is_none());
+  }
+
+  #[test]
+  fn capabilities_from_version() {
+    use super::OpenURICapabilities;
+
+    let caps = OpenURICapabilities::from_version(2);
+    assert!(!caps.supports_open_directory());
+    assert!(!caps.supports_ask());
+    let caps = OpenURICapabilities::from_version(3);
+    assert!(caps.supports_open_directory());
+    assert!(caps.supports_ask());
+    assert!(!caps.supports_activation_token());
+    assert!(OpenURICapabilities::from_version(4).supports_activation_token());
+  }
+
+  #[test]
+  #[cfg(feature = "testing")]
+  fn open_writable_file_opens_document() {
+    use super::INTERFACE;
+    use crate::{new_documents_blocking, testing::MockSender};
+    use dbus::{
+      arg::{prop_cast, OwnedFd},
+      Path,
+    };
+    use std::os::unix::{ffi::OsStrExt, io::AsRawFd};
+
+    let mount_point = std::env::temp_dir().join(format!("xdg-portal-doc-{}", std::process::id()));
+    std::fs::create_dir_all(mount_point.join("a1b2")).unwrap();
+    std::fs::write(mount_point.join("a1b2/notes.txt"), b"").unwrap();
+    let file = mount_point.join("notes.txt");
+    std::fs::write(&file, b"").unwrap();
+
+    let sender = MockSender::new();
+    let documents_interface = "org.freedesktop.portal.Documents";
+    sender.reply(documents_interface, "Add", |reply| reply.append1("a1b2"));
+    sender.reply(documents_interface, "GrantPermissions", |reply| reply);
+    let mut mount_point_bytes = mount_point.as_os_str().as_bytes().to_vec();
+    mount_point_bytes.push(0);
+    sender.reply(documents_interface, "GetMountPoint", move |reply| {
+      reply.append1(mount_point_bytes.clone())
+    });
+    let path = Path::new("/org/freedesktop/portal/desktop/request/1_1/token").unwrap();
+    let reply = path.clone();
+    sender.reply(INTERFACE, "OpenFile", move |message| {
+      message.append1(reply.clone())
+    });
+    let timeout = Duration::from_secs(2);
+    let documents = new_documents_blocking(timeout, &sender);
+    let portals = new_blocking(timeout, &sender);
+
+    let request = portals
+      .open_writable_file(
+        &documents,
+        "",
+        &file,
+        "org.gnome.TextEditor",
+        OpenURIOptions::new(),
+      )
+      .unwrap();
+    assert_eq!(request, path);
+
+    let calls = sender.take_calls();
+    let members: Vec<_> = calls.iter().map(|call| call.member.as_str()).collect();
+    assert_eq!(
+      members,
+      ["Add", "GrantPermissions", "GetMountPoint", "OpenFile"]
+    );
+    assert_eq!(calls[3].interface, INTERFACE);
+    let (_, fd, options): (String, OwnedFd, PropMap) = calls[3].message.read3().unwrap();
+    let flags = unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_GETFL) };
+    assert_eq!(flags & libc::O_ACCMODE, libc::O_RDWR);
+    assert_eq!(prop_cast::<bool>(&options, "writable"), Some(&true));
+
+    std::fs::remove_dir_all(&mount_point).unwrap();
+  }
 }
@@ -0,0 +1,46 @@
+// Copyright 2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Every portal Trait, so their methods can be called on a proxy after a single `use xdg_desktop_portal::prelude::*;`.
+//!
+//! The Traits are imported anonymously, so they don't clash with names of your own. Some methods are defined by
+//! several Traits, which the method syntax can't choose between: `version()`, `create_session()`, `start()`,
+//! `open_file()`, `open_pipe_wire_remote()`, `release()`, `on_activated()` and `on_deactivated()`.
+//! Call those through the Trait instead, such as `Screenshot::version(&proxy)`, or on the handles of `Portals`,
+//! such as `portals.screenshot().version()`.
+//!
+//! ```no_run
+//! # #[cfg(feature = "dbus")]
+//! # fn main() -> Result<(), xdg_desktop_portal::PortalError> {
+//! use std::time::Duration;
+//! use xdg_desktop_portal::{new_blocking_owned, prelude::*, OpenURIOptions, Screenshot};
+//!
+//! let proxy = new_blocking_owned(Duration::from_secs(5))?;
+//! proxy.open_uri("", "https://example.com", OpenURIOptions::new())?;
+//! if Screenshot::version(&proxy)? >= 2 {
+//!   proxy.pick_color(ParentWindow::None, Default::default())?;
+//! }
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "dbus"))]
+//! # fn main() {}
+//! ```
+
+#[cfg(feature = "dbus")]
+pub use crate::IntoPortalFd;
+#[cfg(feature = "dbus")]
+pub use crate::{
+  Account as _, Background as _, Camera as _, DevicePortal as _, Documents as _,
+  DynamicLauncher as _, Email as _, FileChooser as _, GlobalShortcuts as _, Inhibit as _,
+  InputCapture as _, LocationPortal as _, MemoryMonitor as _, NetworkMonitor as _,
+  NotificationPortal as _, OpenURI as _, Print as _, ProxyResolver as _, Realtime as _,
+  RemoteDesktop as _, ScreenCast as _, Screenshot as _, Secret as _, Settings as _, Wallpaper as _,
+};
+#[cfg(feature = "async")]
+pub use crate::{
+  AsyncGlobalShortcuts as _, AsyncLocation as _, AsyncOpenURI as _, AsyncSettings as _,
+};
+#[cfg(feature = "zbus")]
+pub use crate::{AsyncZbusOpenURI as _, ZbusOpenURI as _};
+pub use crate::{ParentWindow, PortalError, VersionedOptions as _};
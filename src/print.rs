@@ -0,0 +1,420 @@
+// Copyright 2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::{
+  trace::{self, CallSpan},
+  IntoPortalFd, ParentWindowArg, PortalError,
+};
+
+use dbus::{
+  arg::{PropMap, Variant},
+  blocking::{self, stdintf::org_freedesktop_dbus},
+  Path,
+};
+
+const INTERFACE: &str = "org.freedesktop.portal.Print";
+
+/// Implementation of the `org.freedesktop.portal.Print` Portal API.
+/// See also https://flatpak.github.io/xdg-desktop-portal/#gdbus-org.freedesktop.portal.Print
+pub trait Print {
+  /// Presents a print dialog to the user and returns print settings and page setup.
+  ///
+  /// The chosen `settings`, `page-setup` and a `token` to pass to `PrintOptions::token()`
+  /// are returned in the results of the `Response` signal.
+  ///
+  /// - `parent_window`: Identifier for the application window, see crate comments for common conventions.
+  /// - `title`: Title for the print dialog.
+  /// - `settings`: Serialized print settings, see `PrintSettings`.
+  /// - `page_setup`: Serialized page setup, see `PageSetup`.
+  fn prepare_print(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    title: &str,
+    settings: PropMap,
+    page_setup: PropMap,
+    options: PreparePrintOptions,
+  ) -> Result<Path<'static>, PortalError>;
+
+  /// Asks to print a file.
+  ///
+  /// - `parent_window`: Identifier for the application window, see crate comments for common conventions.
+  /// - `title`: Title for the print dialog.
+  /// - `fd`: File descriptor for reading the content to print.
+  fn print(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    title: &str,
+    fd: impl IntoPortalFd,
+    options: PrintOptions,
+  ) -> Result<Path<'static>, PortalError>;
+
+  /// Reads the "version" property for this D-Bus interface.
+  fn version(&self) -> Result<u32, PortalError>;
+}
+
+/// The orientation of the printed pages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Orientation {
+  /// Portrait orientation.
+  Portrait,
+  /// Landscape orientation.
+  Landscape,
+  /// Portrait orientation, upside down.
+  ReversePortrait,
+  /// Landscape orientation, upside down.
+  ReverseLandscape,
+}
+
+impl Orientation {
+  fn as_str(&self) -> &'static str {
+    match self {
+      Self::Portrait => "portrait",
+      Self::Landscape => "landscape",
+      Self::ReversePortrait => "reverse_portrait",
+      Self::ReverseLandscape => "reverse_landscape",
+    }
+  }
+}
+
+/// Print settings for the `Print::prepare_print()` method.
+///
+/// The portal expects all print settings as strings, which this builder takes care of.
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PrintSettings {
+  orientation: Option<Orientation>,
+  paper_format: Option<String>,
+  paper_size: Option<(f64, f64)>,
+  n_copies: Option<u32>,
+}
+
+impl PrintSettings {
+  /// Creates a new `PrintSettings` struct with no settings set.
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  /// The orientation of the printed pages.
+  pub fn orientation(mut self, orientation: Orientation) -> Self {
+    self.orientation = Some(orientation);
+    self
+  }
+
+  /// The paper format, as a PWG 5101.1-2002 paper name, like `iso_a4`.
+  pub fn paper_format(mut self, paper_format: String) -> Self {
+    self.paper_format = Some(paper_format);
+    self
+  }
+
+  /// The paper width and height, in millimeters.
+  pub fn paper_size(mut self, width: f64, height: f64) -> Self {
+    self.paper_size = Some((width, height));
+    self
+  }
+
+  /// The number of copies to print.
+  pub fn n_copies(mut self, n_copies: u32) -> Self {
+    self.n_copies = Some(n_copies);
+    self
+  }
+}
+
+impl From<PrintSettings> for PropMap {
+  fn from(settings: PrintSettings) -> Self {
+    let mut map = PropMap::new();
+    if let Some(orientation) = settings.orientation {
+      map.insert(
+        "orientation".to_string(),
+        Variant(Box::new(orientation.as_str().to_string())),
+      );
+    }
+    if let Some(paper_format) = settings.paper_format {
+      map.insert("paper-format".to_string(), Variant(Box::new(paper_format)));
+    }
+    if let Some((width, height)) = settings.paper_size {
+      map.insert(
+        "paper-width".to_string(),
+        Variant(Box::new(width.to_string())),
+      );
+      map.insert(
+        "paper-height".to_string(),
+        Variant(Box::new(height.to_string())),
+      );
+    }
+    if let Some(n_copies) = settings.n_copies {
+      map.insert(
+        "n-copies".to_string(),
+        Variant(Box::new(n_copies.to_string())),
+      );
+    }
+    map
+  }
+}
+
+/// Page setup for the `Print::prepare_print()` method.
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PageSetup {
+  ppd_name: Option<String>,
+  name: Option<String>,
+  display_name: Option<String>,
+  size: Option<(f64, f64)>,
+  margins: Option<(f64, f64, f64, f64)>,
+  orientation: Option<Orientation>,
+}
+
+impl PageSetup {
+  /// Creates a new `PageSetup` struct with nothing set.
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  /// The PPD name of the paper size.
+  pub fn ppd_name(mut self, ppd_name: String) -> Self {
+    self.ppd_name = Some(ppd_name);
+    self
+  }
+
+  /// The name of the paper size.
+  pub fn name(mut self, name: String) -> Self {
+    self.name = Some(name);
+    self
+  }
+
+  /// The user-visible name of the paper size.
+  pub fn display_name(mut self, display_name: String) -> Self {
+    self.display_name = Some(display_name);
+    self
+  }
+
+  /// The paper width and height, in millimeters.
+  pub fn size(mut self, width: f64, height: f64) -> Self {
+    self.size = Some((width, height));
+    self
+  }
+
+  /// The top, bottom, left and right margins, in millimeters.
+  pub fn margins(mut self, top: f64, bottom: f64, left: f64, right: f64) -> Self {
+    self.margins = Some((top, bottom, left, right));
+    self
+  }
+
+  /// The orientation of the page.
+  pub fn orientation(mut self, orientation: Orientation) -> Self {
+    self.orientation = Some(orientation);
+    self
+  }
+}
+
+impl From<PageSetup> for PropMap {
+  fn from(page_setup: PageSetup) -> Self {
+    let mut map = PropMap::new();
+    if let Some(ppd_name) = page_setup.ppd_name {
+      map.insert("PPDName".to_string(), Variant(Box::new(ppd_name)));
+    }
+    if let Some(name) = page_setup.name {
+      map.insert("Name".to_string(), Variant(Box::new(name)));
+    }
+    if let Some(display_name) = page_setup.display_name {
+      map.insert("DisplayName".to_string(), Variant(Box::new(display_name)));
+    }
+    if let Some((width, height)) = page_setup.size {
+      map.insert("Width".to_string(), Variant(Box::new(width)));
+      map.insert("Height".to_string(), Variant(Box::new(height)));
+    }
+    if let Some((top, bottom, left, right)) = page_setup.margins {
+      map.insert("MarginTop".to_string(), Variant(Box::new(top)));
+      map.insert("MarginBottom".to_string(), Variant(Box::new(bottom)));
+      map.insert("MarginLeft".to_string(), Variant(Box::new(left)));
+      map.insert("MarginRight".to_string(), Variant(Box::new(right)));
+    }
+    if let Some(orientation) = page_setup.orientation {
+      map.insert(
+        "Orientation".to_string(),
+        Variant(Box::new(orientation.as_str().to_string())),
+      );
+    }
+    map
+  }
+}
+
+/// Optional arguments for the `Print::prepare_print()` method.
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PreparePrintOptions {
+  handle_token: Option<String>,
+  modal: Option<bool>,
+}
+
+impl PreparePrintOptions {
+  /// Creates a new `PreparePrintOptions` struct with no arguments set.
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  /// A string that will be used as the last element of the @handle. Must be a valid
+  /// object path element. See the #org.freedesktop.portal.Request documentation for
+  /// more information about the @handle.
+  pub fn handle_token(mut self, handle_token: impl Into<String>) -> Self {
+    self.handle_token = Some(handle_token.into());
+    self
+  }
+
+  /// Whether to make the dialog modal. Default is yes.
+  pub fn modal(mut self, modal: bool) -> Self {
+    self.modal = Some(modal);
+    self
+  }
+}
+
+impl From<PreparePrintOptions> for PropMap {
+  fn from(options: PreparePrintOptions) -> Self {
+    let mut map = PropMap::new();
+    if let Some(handle_token) = options.handle_token {
+      map.insert("handle_token".to_string(), Variant(Box::new(handle_token)));
+    }
+    if let Some(modal) = options.modal {
+      map.insert("modal".to_string(), Variant(Box::new(modal)));
+    }
+    map
+  }
+}
+
+/// Optional arguments for the `Print::print()` method.
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PrintOptions {
+  handle_token: Option<String>,
+  modal: Option<bool>,
+  token: Option<u32>,
+}
+
+impl PrintOptions {
+  /// Creates a new `PrintOptions` struct with no arguments set.
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  /// A string that will be used as the last element of the @handle. Must be a valid
+  /// object path element. See the #org.freedesktop.portal.Request documentation for
+  /// more information about the @handle.
+  pub fn handle_token(mut self, handle_token: impl Into<String>) -> Self {
+    self.handle_token = Some(handle_token.into());
+    self
+  }
+
+  /// Whether to make the dialog modal. Default is yes.
+  pub fn modal(mut self, modal: bool) -> Self {
+    self.modal = Some(modal);
+    self
+  }
+
+  /// Token that was returned by a previous `Print::prepare_print()` call,
+  /// to print with those settings without showing a dialog.
+  pub fn token(mut self, token: u32) -> Self {
+    self.token = Some(token);
+    self
+  }
+}
+
+impl From<PrintOptions> for PropMap {
+  fn from(options: PrintOptions) -> Self {
+    let mut map = PropMap::new();
+    if let Some(handle_token) = options.handle_token {
+      map.insert("handle_token".to_string(), Variant(Box::new(handle_token)));
+    }
+    if let Some(modal) = options.modal {
+      map.insert("modal".to_string(), Variant(Box::new(modal)));
+    }
+    if let Some(token) = options.token {
+      map.insert("token".to_string(), Variant(Box::new(token)));
+    }
+    map
+  }
+}
+
+impl<'a, T: blocking::BlockingSender, C: std::ops::Deref<Target = T>> Print
+  for blocking::Proxy<'a, C>
+{
+  fn prepare_print(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    title: &str,
+    settings: PropMap,
+    page_setup: PropMap,
+    options: PreparePrintOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    let parent_window: ParentWindowArg = parent_window.into();
+    let options = PropMap::from(options);
+    let _span = CallSpan::enter(
+      INTERFACE,
+      "PreparePrint",
+      parent_window.as_str(),
+      trace::handle_token(&options),
+    );
+    self
+      .method_call(
+        INTERFACE,
+        "PreparePrint",
+        (parent_window.as_str(), title, settings, page_setup, options),
+      )
+      .map(|r: (Path<'static>,)| trace::request_created(r.0))
+      .map_err(Into::into)
+  }
+
+  fn print(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    title: &str,
+    fd: impl IntoPortalFd,
+    options: PrintOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    let parent_window: ParentWindowArg = parent_window.into();
+    let options = PropMap::from(options);
+    let _span = CallSpan::enter(
+      INTERFACE,
+      "Print",
+      parent_window.as_str(),
+      trace::handle_token(&options),
+    );
+    self
+      .method_call(
+        INTERFACE,
+        "Print",
+        (parent_window.as_str(), title, fd.into_portal_fd(), options),
+      )
+      .map(|r: (Path<'static>,)| trace::request_created(r.0))
+      .map_err(Into::into)
+  }
+
+  fn version(&self) -> Result<u32, PortalError> {
+    <Self as org_freedesktop_dbus::Properties>::get(self, INTERFACE, "version").map_err(Into::into)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::{Orientation, PrintSettings};
+  use dbus::arg::{prop_cast, PropMap};
+
+  #[test]
+  fn print_settings_are_strings() {
+    let settings = PropMap::from(
+      PrintSettings::new()
+        .orientation(Orientation::Landscape)
+        .paper_size(210.0, 297.0)
+        .n_copies(2),
+    );
+    assert_eq!(
+      prop_cast::<String>(&settings, "orientation").unwrap(),
+      "landscape"
+    );
+    assert_eq!(
+      prop_cast::<String>(&settings, "paper-width").unwrap(),
+      "210"
+    );
+    assert_eq!(prop_cast::<String>(&settings, "n-copies").unwrap(), "2");
+  }
+}
@@ -0,0 +1,39 @@
+// Copyright 2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::PortalError;
+
+use dbus::blocking::{self, stdintf::org_freedesktop_dbus};
+
+const INTERFACE: &str = "org.freedesktop.portal.ProxyResolver";
+
+/// Implementation of the `org.freedesktop.portal.ProxyResolver` Portal API.
+/// See also https://flatpak.github.io/xdg-desktop-portal/#gdbus-org.freedesktop.portal.ProxyResolver
+pub trait ProxyResolver {
+  /// Looks up which proxy to use to connect to `uri`.
+  ///
+  /// Returns a list of proxy uris of the form `protocol://[user[:password]@]host:port`,
+  /// where `direct://` is used when no proxy is needed.
+  ///
+  /// - `uri`: Destination to connect to.
+  fn lookup(&self, uri: &str) -> Result<Vec<String>, PortalError>;
+
+  /// Reads the "version" property for this D-Bus interface.
+  fn version(&self) -> Result<u32, PortalError>;
+}
+
+impl<'a, T: blocking::BlockingSender, C: std::ops::Deref<Target = T>> ProxyResolver
+  for blocking::Proxy<'a, C>
+{
+  fn lookup(&self, uri: &str) -> Result<Vec<String>, PortalError> {
+    self
+      .method_call(INTERFACE, "Lookup", (uri,))
+      .map(|r: (Vec<String>,)| r.0)
+      .map_err(Into::into)
+  }
+
+  fn version(&self) -> Result<u32, PortalError> {
+    <Self as org_freedesktop_dbus::Properties>::get(self, INTERFACE, "version").map_err(Into::into)
+  }
+}
@@ -0,0 +1,110 @@
+// Copyright 2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::PortalError;
+
+use dbus::blocking::{self, stdintf::org_freedesktop_dbus};
+
+const INTERFACE: &str = "org.freedesktop.portal.Realtime";
+
+/// Implementation of the `org.freedesktop.portal.Realtime` Portal API.
+/// See also https://flatpak.github.io/xdg-desktop-portal/#gdbus-org.freedesktop.portal.Realtime
+///
+/// Threads are identified by the process id, from `std::process::id()`, and the kernel thread id.
+/// The kernel thread id is not the `std::thread::ThreadId`, nor the `pthread_t` of the thread:
+/// read it on the thread itself, with `unsafe { libc::syscall(libc::SYS_gettid) } as u64`.
+pub trait Realtime {
+  /// Gives a thread of a process realtime scheduling, with the given `priority`.
+  ///
+  /// The `priority` must not exceed `Realtime::max_realtime_priority()`. The thread should set
+  /// a `RLIMIT_RTTIME` limit of at most `Realtime::rt_time_usec_max()` first, or the request is refused.
+  ///
+  /// - `process`: The process id.
+  /// - `thread`: The kernel thread id, see the trait documentation.
+  fn make_thread_realtime(
+    &self,
+    process: u64,
+    thread: u64,
+    priority: u32,
+  ) -> Result<(), PortalError>;
+
+  /// Gives a thread of a process a higher priority, with the given `nice_level`.
+  ///
+  /// The `nice_level` must not be below `Realtime::min_nice_level()`.
+  ///
+  /// - `process`: The process id.
+  /// - `thread`: The kernel thread id, see the trait documentation.
+  fn make_thread_high_priority(
+    &self,
+    process: u64,
+    thread: u64,
+    nice_level: i32,
+  ) -> Result<(), PortalError>;
+
+  /// Reads the "MaxRealtimePriority" property, the highest priority `Realtime::make_thread_realtime()` accepts.
+  fn max_realtime_priority(&self) -> Result<i32, PortalError>;
+
+  /// Reads the "MinNiceLevel" property, the lowest nice level `Realtime::make_thread_high_priority()` accepts.
+  fn min_nice_level(&self) -> Result<i32, PortalError>;
+
+  /// Reads the "RTTimeUSecMax" property, the highest `RLIMIT_RTTIME` limit in microseconds
+  /// a thread may have to be given realtime scheduling.
+  fn rt_time_usec_max(&self) -> Result<i64, PortalError>;
+
+  /// Reads the "version" property for this D-Bus interface.
+  fn version(&self) -> Result<u32, PortalError>;
+}
+
+impl<'a, T: blocking::BlockingSender, C: std::ops::Deref<Target = T>> Realtime
+  for blocking::Proxy<'a, C>
+{
+  fn make_thread_realtime(
+    &self,
+    process: u64,
+    thread: u64,
+    priority: u32,
+  ) -> Result<(), PortalError> {
+    self
+      .method_call(
+        INTERFACE,
+        "MakeThreadRealtimeWithPID",
+        (process, thread, priority),
+      )
+      .map_err(Into::into)
+  }
+
+  fn make_thread_high_priority(
+    &self,
+    process: u64,
+    thread: u64,
+    nice_level: i32,
+  ) -> Result<(), PortalError> {
+    self
+      .method_call(
+        INTERFACE,
+        "MakeThreadHighPriorityWithPID",
+        (process, thread, nice_level),
+      )
+      .map_err(Into::into)
+  }
+
+  fn max_realtime_priority(&self) -> Result<i32, PortalError> {
+    <Self as org_freedesktop_dbus::Properties>::get(self, INTERFACE, "MaxRealtimePriority")
+      .map_err(Into::into)
+  }
+
+  fn min_nice_level(&self) -> Result<i32, PortalError> {
+    <Self as org_freedesktop_dbus::Properties>::get(self, INTERFACE, "MinNiceLevel")
+      .map_err(Into::into)
+  }
+
+  fn rt_time_usec_max(&self) -> Result<i64, PortalError> {
+    <Self as org_freedesktop_dbus::Properties>::get(self, INTERFACE, "RTTimeUSecMax")
+      .map_err(Into::into)
+  }
+
+  fn version(&self) -> Result<u32, PortalError> {
+    <Self as org_freedesktop_dbus::Properties>::get(self, INTERFACE, "version").map_err(Into::into)
+  }
+}
@@ -0,0 +1,471 @@
+// Copyright 2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::{
+  trace::{self, CallSpan},
+  CreateSessionOptions, ParentWindowArg, PortalError, Session, StartOptions,
+};
+
+use dbus::{
+  arg::{PropMap, Variant},
+  blocking::{self, stdintf::org_freedesktop_dbus},
+  Path,
+};
+use std::ops::{BitOr, BitOrAssign};
+
+const INTERFACE: &str = "org.freedesktop.portal.RemoteDesktop";
+
+/// Implementation of the `org.freedesktop.portal.RemoteDesktop` Portal API.
+/// See also https://flatpak.github.io/xdg-desktop-portal/#gdbus-org.freedesktop.portal.RemoteDesktop
+///
+/// A remote desktop session goes through the same steps as a `ScreenCast` session,
+/// and can be combined with one by selecting sources on the same session before starting it.
+/// The `streams` of a combined session can be read with `parse_streams()`.
+///
+/// The input methods only work after the session was started,
+/// and only for the device types the user allowed.
+pub trait RemoteDesktop {
+  /// Creates a remote desktop session.
+  ///
+  /// The `session_handle` is returned in the results of the `Response` signal.
+  fn create_session(&self, options: CreateSessionOptions) -> Result<Path<'static>, PortalError>;
+
+  /// Selects the input devices to remote control.
+  fn select_devices(
+    &self,
+    session: &Session,
+    options: SelectDevicesOptions,
+  ) -> Result<Path<'static>, PortalError>;
+
+  /// Starts the remote desktop session, which asks the user for permission.
+  ///
+  /// The allowed `devices` are returned in the results of the `Response` signal.
+  ///
+  /// - `parent_window`: Identifier for the application window, see crate comments for common conventions.
+  fn start(
+    &self,
+    session: &Session,
+    parent_window: impl Into<ParentWindowArg>,
+    options: StartOptions,
+  ) -> Result<Path<'static>, PortalError>;
+
+  /// Notifies about a relative pointer motion event, in logical pixels.
+  fn notify_pointer_motion(
+    &self,
+    session: &Session,
+    options: PropMap,
+    dx: f64,
+    dy: f64,
+  ) -> Result<(), PortalError>;
+
+  /// Notifies about an absolute pointer motion event, in logical pixels within the given stream.
+  ///
+  /// - `stream`: The PipeWire stream node the coordinates are relative to.
+  fn notify_pointer_motion_absolute(
+    &self,
+    session: &Session,
+    options: PropMap,
+    stream: u32,
+    x: f64,
+    y: f64,
+  ) -> Result<(), PortalError>;
+
+  /// Notifies about a pointer button event.
+  ///
+  /// - `button`: The pointer button, as defined by evdev in `linux/input-event-codes.h`.
+  fn notify_pointer_button(
+    &self,
+    session: &Session,
+    options: PropMap,
+    button: i32,
+    state: KeyState,
+  ) -> Result<(), PortalError>;
+
+  /// Notifies about a keyboard event, by keycode.
+  ///
+  /// - `keycode`: The keyboard keycode, as defined by evdev in `linux/input-event-codes.h`.
+  fn notify_keyboard_keycode(
+    &self,
+    session: &Session,
+    options: PropMap,
+    keycode: i32,
+    state: KeyState,
+  ) -> Result<(), PortalError>;
+
+  /// Notifies about a keyboard event, by keysym.
+  ///
+  /// - `keysym`: The keyboard keysym, as defined by xkbcommon.
+  fn notify_keyboard_keysym(
+    &self,
+    session: &Session,
+    options: PropMap,
+    keysym: i32,
+    state: KeyState,
+  ) -> Result<(), PortalError>;
+
+  /// Notifies about a touch down event, in logical pixels within the given stream.
+  ///
+  /// - `stream`: The PipeWire stream node the coordinates are relative to.
+  /// - `slot`: Touch slot where the touch point appeared.
+  fn notify_touch_down(
+    &self,
+    session: &Session,
+    options: PropMap,
+    stream: u32,
+    slot: u32,
+    x: f64,
+    y: f64,
+  ) -> Result<(), PortalError>;
+
+  /// Notifies about a touch motion event, in logical pixels within the given stream.
+  ///
+  /// - `stream`: The PipeWire stream node the coordinates are relative to.
+  /// - `slot`: Touch slot where the touch point moved.
+  fn notify_touch_motion(
+    &self,
+    session: &Session,
+    options: PropMap,
+    stream: u32,
+    slot: u32,
+    x: f64,
+    y: f64,
+  ) -> Result<(), PortalError>;
+
+  /// Notifies about a touch up event.
+  ///
+  /// - `slot`: Touch slot where the touch point disappeared.
+  fn notify_touch_up(
+    &self,
+    session: &Session,
+    options: PropMap,
+    slot: u32,
+  ) -> Result<(), PortalError>;
+
+  /// Reads the "AvailableDeviceTypes" property, the device types that can be selected.
+  fn available_device_types(&self) -> Result<DeviceType, PortalError>;
+
+  /// Reads the "version" property for this D-Bus interface.
+  fn version(&self) -> Result<u32, PortalError>;
+}
+
+/// Types of input devices to remote control. Types can be combined with `|`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeviceType(u32);
+
+impl DeviceType {
+  /// Control the keyboard.
+  pub const KEYBOARD: Self = Self(1);
+  /// Control the pointer.
+  pub const POINTER: Self = Self(2);
+  /// Control the touchscreen.
+  pub const TOUCHSCREEN: Self = Self(4);
+
+  /// No types set.
+  pub fn empty() -> Self {
+    Self(0)
+  }
+
+  /// Creates a `DeviceType` from the raw bits sent by the portal.
+  pub fn from_bits(bits: u32) -> Self {
+    Self(bits)
+  }
+
+  /// The raw bits, as sent to the portal.
+  pub fn bits(&self) -> u32 {
+    self.0
+  }
+
+  /// Whether all types in `other` are set.
+  pub fn contains(&self, other: Self) -> bool {
+    self.0 & other.0 == other.0
+  }
+}
+
+impl BitOr for DeviceType {
+  type Output = Self;
+
+  fn bitor(self, rhs: Self) -> Self {
+    Self(self.0 | rhs.0)
+  }
+}
+
+impl BitOrAssign for DeviceType {
+  fn bitor_assign(&mut self, rhs: Self) {
+    self.0 |= rhs.0;
+  }
+}
+
+/// The new state of a pointer button or key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum KeyState {
+  /// The button or key was released.
+  Released,
+  /// The button or key was pressed.
+  Pressed,
+}
+
+impl KeyState {
+  fn bits(&self) -> u32 {
+    match self {
+      Self::Released => 0,
+      Self::Pressed => 1,
+    }
+  }
+}
+
+/// Optional arguments for the `RemoteDesktop::select_devices()` method.
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SelectDevicesOptions {
+  handle_token: Option<String>,
+  types: Option<DeviceType>,
+}
+
+impl SelectDevicesOptions {
+  /// Creates a new `SelectDevicesOptions` struct with no arguments set.
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  /// A string that will be used as the last element of the @handle. Must be a valid
+  /// object path element. See the #org.freedesktop.portal.Request documentation for
+  /// more information about the @handle.
+  pub fn handle_token(mut self, handle_token: impl Into<String>) -> Self {
+    self.handle_token = Some(handle_token.into());
+    self
+  }
+
+  /// What types of devices to remote control. Default is all available types.
+  pub fn types(mut self, types: DeviceType) -> Self {
+    self.types = Some(types);
+    self
+  }
+}
+
+impl From<SelectDevicesOptions> for PropMap {
+  fn from(options: SelectDevicesOptions) -> Self {
+    let mut map = PropMap::new();
+    if let Some(handle_token) = options.handle_token {
+      map.insert("handle_token".to_string(), Variant(Box::new(handle_token)));
+    }
+    if let Some(types) = options.types {
+      map.insert("types".to_string(), Variant(Box::new(types.bits())));
+    }
+    map
+  }
+}
+
+impl<'a, T: blocking::BlockingSender, C: std::ops::Deref<Target = T>> RemoteDesktop
+  for blocking::Proxy<'a, C>
+{
+  fn create_session(&self, options: CreateSessionOptions) -> Result<Path<'static>, PortalError> {
+    let options = PropMap::from(options);
+    let _span = CallSpan::enter(
+      INTERFACE,
+      "CreateSession",
+      "",
+      trace::handle_token(&options),
+    );
+    self
+      .method_call(INTERFACE, "CreateSession", (options,))
+      .map(|r: (Path<'static>,)| trace::request_created(r.0))
+      .map_err(Into::into)
+  }
+
+  fn select_devices(
+    &self,
+    session: &Session,
+    options: SelectDevicesOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    let options = PropMap::from(options);
+    let _span = CallSpan::enter(
+      INTERFACE,
+      "SelectDevices",
+      "",
+      trace::handle_token(&options),
+    );
+    self
+      .method_call(INTERFACE, "SelectDevices", (session.path(), options))
+      .map(|r: (Path<'static>,)| trace::request_created(r.0))
+      .map_err(Into::into)
+  }
+
+  fn start(
+    &self,
+    session: &Session,
+    parent_window: impl Into<ParentWindowArg>,
+    options: StartOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    let parent_window: ParentWindowArg = parent_window.into();
+    let options = PropMap::from(options);
+    let _span = CallSpan::enter(
+      INTERFACE,
+      "Start",
+      parent_window.as_str(),
+      trace::handle_token(&options),
+    );
+    self
+      .method_call(
+        INTERFACE,
+        "Start",
+        (session.path(), parent_window.as_str(), options),
+      )
+      .map(|r: (Path<'static>,)| trace::request_created(r.0))
+      .map_err(Into::into)
+  }
+
+  fn notify_pointer_motion(
+    &self,
+    session: &Session,
+    options: PropMap,
+    dx: f64,
+    dy: f64,
+  ) -> Result<(), PortalError> {
+    self
+      .method_call(
+        INTERFACE,
+        "NotifyPointerMotion",
+        (session.path(), options, dx, dy),
+      )
+      .map_err(Into::into)
+  }
+
+  fn notify_pointer_motion_absolute(
+    &self,
+    session: &Session,
+    options: PropMap,
+    stream: u32,
+    x: f64,
+    y: f64,
+  ) -> Result<(), PortalError> {
+    self
+      .method_call(
+        INTERFACE,
+        "NotifyPointerMotionAbsolute",
+        (session.path(), options, stream, x, y),
+      )
+      .map_err(Into::into)
+  }
+
+  fn notify_pointer_button(
+    &self,
+    session: &Session,
+    options: PropMap,
+    button: i32,
+    state: KeyState,
+  ) -> Result<(), PortalError> {
+    self
+      .method_call(
+        INTERFACE,
+        "NotifyPointerButton",
+        (session.path(), options, button, state.bits()),
+      )
+      .map_err(Into::into)
+  }
+
+  fn notify_keyboard_keycode(
+    &self,
+    session: &Session,
+    options: PropMap,
+    keycode: i32,
+    state: KeyState,
+  ) -> Result<(), PortalError> {
+    self
+      .method_call(
+        INTERFACE,
+        "NotifyKeyboardKeycode",
+        (session.path(), options, keycode, state.bits()),
+      )
+      .map_err(Into::into)
+  }
+
+  fn notify_keyboard_keysym(
+    &self,
+    session: &Session,
+    options: PropMap,
+    keysym: i32,
+    state: KeyState,
+  ) -> Result<(), PortalError> {
+    self
+      .method_call(
+        INTERFACE,
+        "NotifyKeyboardKeysym",
+        (session.path(), options, keysym, state.bits()),
+      )
+      .map_err(Into::into)
+  }
+
+  fn notify_touch_down(
+    &self,
+    session: &Session,
+    options: PropMap,
+    stream: u32,
+    slot: u32,
+    x: f64,
+    y: f64,
+  ) -> Result<(), PortalError> {
+    self
+      .method_call(
+        INTERFACE,
+        "NotifyTouchDown",
+        (session.path(), options, stream, slot, x, y),
+      )
+      .map_err(Into::into)
+  }
+
+  fn notify_touch_motion(
+    &self,
+    session: &Session,
+    options: PropMap,
+    stream: u32,
+    slot: u32,
+    x: f64,
+    y: f64,
+  ) -> Result<(), PortalError> {
+    self
+      .method_call(
+        INTERFACE,
+        "NotifyTouchMotion",
+        (session.path(), options, stream, slot, x, y),
+      )
+      .map_err(Into::into)
+  }
+
+  fn notify_touch_up(
+    &self,
+    session: &Session,
+    options: PropMap,
+    slot: u32,
+  ) -> Result<(), PortalError> {
+    self
+      .method_call(INTERFACE, "NotifyTouchUp", (session.path(), options, slot))
+      .map_err(Into::into)
+  }
+
+  fn available_device_types(&self) -> Result<DeviceType, PortalError> {
+    <Self as org_freedesktop_dbus::Properties>::get(self, INTERFACE, "AvailableDeviceTypes")
+      .map(DeviceType::from_bits)
+      .map_err(Into::into)
+  }
+
+  fn version(&self) -> Result<u32, PortalError> {
+    <Self as org_freedesktop_dbus::Properties>::get(self, INTERFACE, "version").map_err(Into::into)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::DeviceType;
+
+  #[test]
+  fn device_type_bits() {
+    let types = DeviceType::KEYBOARD | DeviceType::POINTER;
+    assert_eq!(types.bits(), 3);
+    assert!(types.contains(DeviceType::POINTER));
+    assert!(!types.contains(DeviceType::TOUCHSCREEN));
+    assert_eq!(DeviceType::from_bits(7), types | DeviceType::TOUCHSCREEN);
+  }
+}
@@ -0,0 +1,540 @@
+// Copyright 2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::PortalError;
+#[cfg(feature = "dbus")]
+use crate::{
+  trace::{self, CallSpan},
+  SignalGuard, DESTINATION, PATH,
+};
+
+#[cfg(feature = "dbus")]
+use dbus::{
+  arg::PropMap,
+  blocking::{BlockingSender, Connection},
+  message::{MatchRule, Message},
+  strings::BusName,
+  Path,
+};
+use std::{
+  collections::hash_map::RandomState,
+  hash::{BuildHasher, Hasher},
+  sync::{
+    atomic::{AtomicBool, AtomicU64, Ordering},
+    Arc,
+  },
+};
+#[cfg(feature = "dbus")]
+use std::{
+  sync::Mutex,
+  time::{Duration, Instant},
+};
+
+#[cfg(feature = "dbus")]
+const INTERFACE: &str = "org.freedesktop.portal.Request";
+
+/// How often a cancellable wait checks its `CancelToken` while no messages arrive.
+#[cfg(feature = "dbus")]
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The timeout of the `Close` call made when a wait is cancelled.
+#[cfg(feature = "dbus")]
+const CLOSE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The response code of the `org.freedesktop.portal.Request::Response` signal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ResponseCode {
+  /// Success, the request is carried out. Only in this case the results are meaningful.
+  Success,
+  /// The user cancelled the interaction.
+  Cancelled,
+  /// The user interaction was ended in some other way.
+  Other(u32),
+}
+
+impl From<u32> for ResponseCode {
+  fn from(code: u32) -> Self {
+    match code {
+      0 => Self::Success,
+      1 => Self::Cancelled,
+      code => Self::Other(code),
+    }
+  }
+}
+
+impl From<ResponseCode> for u32 {
+  fn from(code: ResponseCode) -> Self {
+    match code {
+      ResponseCode::Success => 0,
+      ResponseCode::Cancelled => 1,
+      ResponseCode::Other(code) => code,
+    }
+  }
+}
+
+/// A `org.freedesktop.portal.Request` object, as returned by most portal methods.
+/// See also https://flatpak.github.io/xdg-desktop-portal/#gdbus-org.freedesktop.portal.Request
+///
+/// The outcome of the user interaction is delivered through the `Response` signal of this object.
+#[cfg(feature = "dbus")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+  destination: BusName<'static>,
+  path: Path<'static>,
+}
+
+#[cfg(feature = "dbus")]
+impl Request {
+  /// Creates a new `Request` for the object at `path`, owned by the portal at `DESTINATION`.
+  pub fn new(path: Path<'static>) -> Self {
+    Self {
+      destination: DESTINATION.into(),
+      path,
+    }
+  }
+
+  /// Sets the bus name of the portal that owns this request,
+  /// for requests made through a proxy created with `new_blocking_with()`.
+  pub fn with_destination(mut self, destination: impl Into<BusName<'static>>) -> Self {
+    self.destination = destination.into();
+    self
+  }
+
+  /// The bus name of the portal that owns this request.
+  pub fn destination(&self) -> &BusName<'static> {
+    &self.destination
+  }
+
+  /// The object path of this request.
+  pub fn path(&self) -> &Path<'static> {
+    &self.path
+  }
+
+  /// Closes the request, which dismisses the dialog the user is interacting with.
+  ///
+  /// The portal doesn't emit the `Response` signal for a closed request, so don't wait for it afterwards.
+  /// To stop a blocking `_and_wait` call from another thread, use its `_cancellable` variant with a `CancelToken`.
+  pub fn close<S: BlockingSender>(
+    &self,
+    connection: &S,
+    timeout: Duration,
+  ) -> Result<(), PortalError> {
+    close_request(connection, &self.destination, &self.path, timeout)
+  }
+
+  /// Blocks until the `Response` signal for this request arrives, or `timeout` has passed.
+  ///
+  /// Returns the response code and the results map.
+  /// The results are only guaranteed to be meaningful for `ResponseCode::Success`.
+  ///
+  /// The signal match is only added when this is called, so a response emitted before that is missed.
+  /// Prefer the `_and_wait` portal methods, which start listening before making the request.
+  pub fn wait_response(
+    &self,
+    connection: &Connection,
+    timeout: Duration,
+  ) -> Result<(ResponseCode, PropMap), PortalError> {
+    ResponseListener::new(connection, &self.destination)?.wait(&self.path, timeout)
+  }
+}
+
+#[cfg(feature = "dbus")]
+impl From<Path<'static>> for Request {
+  fn from(path: Path<'static>) -> Self {
+    Self::new(path)
+  }
+}
+
+/// Cancels a blocking `_and_wait` call from another thread, see `OpenURI::open_uri_and_wait_cancellable()`.
+///
+/// Clones share the same state, so one can be kept by the thread that decides to cancel, such as the handler
+/// of the application window closing. The waiting thread checks the token at least every 100 milliseconds,
+/// so cancelling doesn't need the connection, which can't be shared between threads.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+  /// Creates a new `CancelToken` that isn't cancelled.
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  /// Cancels the waits using this token.
+  pub fn cancel(&self) {
+    self.0.store(true, Ordering::Relaxed);
+  }
+
+  /// Whether `CancelToken::cancel()` was called.
+  pub fn is_cancelled(&self) -> bool {
+    self.0.load(Ordering::Relaxed)
+  }
+}
+
+/// Generates a `handle_token` that is unique within this process, of the form `portal_<random>`.
+pub fn generate_handle_token() -> String {
+  static COUNTER: AtomicU64 = AtomicU64::new(0);
+  let mut hasher = RandomState::new().build_hasher();
+  hasher.write_u64(COUNTER.fetch_add(1, Ordering::Relaxed));
+  format!("portal_{:016x}", hasher.finish())
+}
+
+/// A `handle_token` checked to be a valid object path element, of ASCII letters, digits and underscores.
+///
+/// The `handle_token` setters of the options accept any string, a token the portal rejects only fails once
+/// the request is made. Creating the token with `HandleToken::try_from()` fails early instead:
+/// `OpenURIOptions::new().handle_token(HandleToken::try_from(token)?)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HandleToken(String);
+
+impl HandleToken {
+  /// The token, as sent to the portal.
+  pub fn as_str(&self) -> &str {
+    &self.0
+  }
+}
+
+impl TryFrom<String> for HandleToken {
+  type Error = PortalError;
+
+  /// Fails with `PortalError::InvalidArgument` if `handle_token` is empty, or has other characters than
+  /// ASCII letters, digits and underscores.
+  fn try_from(handle_token: String) -> Result<Self, Self::Error> {
+    validate_handle_token(&handle_token)?;
+    Ok(Self(handle_token))
+  }
+}
+
+impl TryFrom<&str> for HandleToken {
+  type Error = PortalError;
+
+  /// See `HandleToken::try_from(String)`.
+  fn try_from(handle_token: &str) -> Result<Self, Self::Error> {
+    Self::try_from(handle_token.to_string())
+  }
+}
+
+impl From<HandleToken> for String {
+  fn from(handle_token: HandleToken) -> Self {
+    handle_token.0
+  }
+}
+
+fn validate_handle_token(handle_token: &str) -> Result<(), PortalError> {
+  if handle_token.is_empty() {
+    return Err(PortalError::InvalidArgument(
+      "handle_token is empty".to_string(),
+    ));
+  }
+  match handle_token
+    .chars()
+    .find(|c| !c.is_ascii_alphanumeric() && *c != '_')
+  {
+    Some(c) => Err(PortalError::InvalidArgument(format!(
+      "handle_token `{}` contains `{}`, only ASCII letters, digits and underscores are allowed",
+      handle_token, c
+    ))),
+    None => Ok(()),
+  }
+}
+
+/// The object path of the `Request` a portal creates for `handle_token`,
+/// when called from the connection with the unique bus name `sender`.
+///
+/// Knowing the path in advance allows subscribing to its `Response` signal before making the request.
+/// Fails with `PortalError::InvalidArgument` if `handle_token` isn't a valid object path element,
+/// so the `_and_wait` methods fail before the request is made.
+#[cfg(feature = "dbus")]
+pub fn request_path(sender: &str, handle_token: &str) -> Result<Path<'static>, PortalError> {
+  validate_handle_token(handle_token)?;
+  let sender = sender.trim_start_matches(':').replace('.', "_");
+  Path::new(format!("{}/request/{}/{}", PATH, sender, handle_token))
+    .map_err(PortalError::UnexpectedResponse)
+}
+
+/// Closes the request at `path`, ending the user interaction or releasing what it holds.
+#[cfg(feature = "dbus")]
+pub(crate) fn close_request<S: BlockingSender + ?Sized>(
+  connection: &S,
+  destination: &BusName<'_>,
+  path: &Path<'_>,
+  timeout: Duration,
+) -> Result<(), PortalError> {
+  let message = Message::new_method_call(destination.clone(), path.clone(), INTERFACE, "Close")
+    .map_err(PortalError::UnexpectedResponse)?;
+  connection.send_with_reply_and_block(message, timeout)?;
+  Ok(())
+}
+
+/// Processes incoming messages on `connection` until `done` returns a value, or `timeout` has passed.
+///
+/// Signal handlers, such as those of `SignalGuard`, only run while the connection processes messages.
+/// Programs without an event loop, like command line tools, can use this to wait for what a handler receives.
+/// `done` is checked before each message is processed. Fails with `PortalError::Timeout` once `timeout` has passed,
+/// or with the error `done` returns.
+#[cfg(feature = "dbus")]
+pub fn process_until<T>(
+  connection: &Connection,
+  timeout: Duration,
+  done: impl FnMut() -> Result<Option<T>, PortalError>,
+) -> Result<T, PortalError> {
+  process_polling(connection, timeout, None, done)
+}
+
+/// Same as `process_until()`, but checks `done` at least every `poll_interval`, also while no messages arrive.
+#[cfg(feature = "dbus")]
+fn process_polling<T>(
+  connection: &Connection,
+  timeout: Duration,
+  poll_interval: Option<Duration>,
+  mut done: impl FnMut() -> Result<Option<T>, PortalError>,
+) -> Result<T, PortalError> {
+  let deadline = Instant::now() + timeout;
+  loop {
+    if let Some(value) = done()? {
+      return Ok(value);
+    }
+    let now = Instant::now();
+    if now >= deadline {
+      return Err(PortalError::Timeout);
+    }
+    let step = match poll_interval {
+      Some(poll_interval) => (deadline - now).min(poll_interval),
+      None => deadline - now,
+    };
+    connection.process(step)?;
+  }
+}
+
+/// Collects `Response` signals, so that matching can start before the request is made.
+#[cfg(feature = "dbus")]
+pub(crate) struct ResponseListener<'a> {
+  connection: &'a Connection,
+  destination: BusName<'static>,
+  expected: Option<Path<'static>>,
+  responses: Arc<Mutex<Vec<(Path<'static>, u32, PropMap)>>>,
+  _guard: SignalGuard<'a>,
+  // Dropped last, so the span covers removing the signal match.
+  _span: Option<CallSpan>,
+}
+
+#[cfg(feature = "dbus")]
+impl<'a> ResponseListener<'a> {
+  /// Collects the responses of all requests of the portal at `destination`.
+  pub(crate) fn new(
+    connection: &'a Connection,
+    destination: &BusName<'_>,
+  ) -> Result<Self, PortalError> {
+    let destination = destination.clone().into_static();
+    let rule = MatchRule::new_signal(INTERFACE, "Response").with_sender(destination.clone());
+    Self::with_rule(connection, destination, rule, None)
+  }
+
+  /// Only collects the response of the request the portal will create for the `interface.method` call
+  /// with `handle_token`, which is traced from now until the listener is dropped.
+  pub(crate) fn for_call(
+    connection: &'a Connection,
+    destination: &BusName<'_>,
+    interface: &'static str,
+    method: &'static str,
+    parent_window: &str,
+    handle_token: &str,
+  ) -> Result<Self, PortalError> {
+    let span = CallSpan::enter(interface, method, parent_window, Some(handle_token));
+    let expected = request_path(&connection.unique_name(), handle_token)?;
+    let destination = destination.clone().into_static();
+    let rule = MatchRule::new_signal(INTERFACE, "Response")
+      .with_sender(destination.clone())
+      .with_path(expected.clone());
+    let mut listener = Self::with_rule(connection, destination, rule, Some(expected))?;
+    listener._span = Some(span);
+    Ok(listener)
+  }
+
+  fn with_rule(
+    connection: &'a Connection,
+    destination: BusName<'static>,
+    rule: MatchRule<'static>,
+    expected: Option<Path<'static>>,
+  ) -> Result<Self, PortalError> {
+    let responses = Arc::new(Mutex::new(Vec::new()));
+    let received = responses.clone();
+    let guard = SignalGuard::new(
+      connection,
+      rule,
+      move |(code, results): (u32, PropMap), message: &Message| {
+        if let Some(path) = message.path() {
+          trace::response_received(&path, code);
+          received
+            .lock()
+            .unwrap()
+            .push((path.into_static(), code, results));
+        }
+      },
+    )?;
+    Ok(Self {
+      connection,
+      destination,
+      expected,
+      responses,
+      _guard: guard,
+      _span: None,
+    })
+  }
+
+  /// Processes incoming messages until the response for `path` arrives, or `timeout` has passed.
+  pub(crate) fn wait(
+    &self,
+    path: &Path<'_>,
+    timeout: Duration,
+  ) -> Result<(ResponseCode, PropMap), PortalError> {
+    self.wait_until(path, timeout, None)
+  }
+
+  /// Same as `ResponseListener::wait()`, but closes the request and fails with `PortalError::Cancelled`
+  /// once `cancel_token` is cancelled, without waiting for a response, as none follows `Close`.
+  pub(crate) fn wait_cancellable(
+    &self,
+    path: &Path<'_>,
+    timeout: Duration,
+    cancel_token: &CancelToken,
+  ) -> Result<(ResponseCode, PropMap), PortalError> {
+    self.wait_until(path, timeout, Some(cancel_token))
+  }
+
+  fn wait_until(
+    &self,
+    path: &Path<'_>,
+    timeout: Duration,
+    cancel_token: Option<&CancelToken>,
+  ) -> Result<(ResponseCode, PropMap), PortalError> {
+    if matches!(&self.expected, Some(expected) if expected != path) {
+      // Portals implementing versions of the spec before 0.9 don't derive the path from the handle_token,
+      // so the response can only be matched once the path is known.
+      return ResponseListener::new(self.connection, &self.destination)?.wait_until(
+        path,
+        timeout,
+        cancel_token,
+      );
+    }
+    let poll_interval = cancel_token.map(|_| CANCEL_POLL_INTERVAL);
+    process_polling(self.connection, timeout, poll_interval, || {
+      if let Some(response) = self.take(path) {
+        return Ok(Some(response));
+      }
+      if cancel_token.is_some_and(CancelToken::is_cancelled) {
+        close_request(self.connection, &self.destination, path, CLOSE_TIMEOUT)?;
+        return Err(PortalError::Cancelled);
+      }
+      Ok(None)
+    })
+  }
+
+  /// Same as `ResponseListener::wait()`, but returns the results only if the request succeeded.
+  pub(crate) fn wait_success(
+    &self,
+    path: &Path<'_>,
+    timeout: Duration,
+  ) -> Result<PropMap, PortalError> {
+    match self.wait(path, timeout)? {
+      (ResponseCode::Success, results) => Ok(results),
+      (ResponseCode::Cancelled, _) => Err(PortalError::Cancelled),
+      (code, _) => Err(PortalError::Response(code)),
+    }
+  }
+
+  fn take(&self, path: &Path<'_>) -> Option<(ResponseCode, PropMap)> {
+    let mut responses = self.responses.lock().unwrap();
+    let index = responses.iter().position(|(p, _, _)| p == path)?;
+    let (_, code, results) = responses.remove(index);
+    Some((code.into(), results))
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::{generate_handle_token, CancelToken, HandleToken, ResponseCode};
+  use crate::PortalError;
+
+  #[test]
+  #[cfg(feature = "testing")]
+  fn process_until_done_or_timeout() {
+    use super::process_until;
+    use crate::testing::TestBus;
+    use std::time::Duration;
+
+    let bus = TestBus::new().unwrap();
+    let conn = bus.connect().unwrap();
+    assert_eq!(
+      process_until(&conn, Duration::from_secs(2), || Ok(Some(3))).unwrap(),
+      3
+    );
+    assert!(matches!(
+      process_until(&conn, Duration::from_millis(50), || Ok(None::<()>)),
+      Err(PortalError::Timeout)
+    ));
+    assert!(matches!(
+      process_until(&conn, Duration::from_secs(2), || Err::<Option<()>, _>(
+        PortalError::Cancelled
+      )),
+      Err(PortalError::Cancelled)
+    ));
+  }
+
+  #[test]
+  fn response_code_from_u32() {
+    assert_eq!(ResponseCode::from(0), ResponseCode::Success);
+    assert_eq!(ResponseCode::from(1), ResponseCode::Cancelled);
+    assert_eq!(ResponseCode::from(2), ResponseCode::Other(2));
+    assert_eq!(u32::from(ResponseCode::Other(2)), 2);
+  }
+
+  #[test]
+  fn cancel_token_shared_by_clones() {
+    let token = CancelToken::new();
+    let other = token.clone();
+    assert!(!other.is_cancelled());
+    token.cancel();
+    assert!(other.is_cancelled());
+  }
+
+  #[test]
+  #[cfg(feature = "dbus")]
+  fn predicted_request_path() {
+    use super::request_path;
+
+    let path = request_path(":1.42", "portal_token").unwrap();
+    assert_eq!(
+      &*path,
+      "/org/freedesktop/portal/desktop/request/1_42/portal_token"
+    );
+
+    let token = generate_handle_token();
+    assert!(token.starts_with("portal_"));
+    assert_ne!(token, generate_handle_token());
+    assert!(request_path(":1.42", &token).is_ok());
+    assert!(matches!(
+      request_path(":1.42", "portal/token"),
+      Err(PortalError::InvalidArgument(_))
+    ));
+  }
+
+  #[test]
+  fn handle_token_characters() {
+    assert_eq!(
+      HandleToken::try_from("portal_Token1").unwrap().as_str(),
+      "portal_Token1"
+    );
+    assert_eq!(
+      String::from(HandleToken::try_from(generate_handle_token()).unwrap()).len(),
+      23
+    );
+    for invalid in ["portal-token", "portal/token", "", "pörtal"] {
+      assert!(matches!(
+        HandleToken::try_from(invalid),
+        Err(PortalError::InvalidArgument(_))
+      ));
+    }
+  }
+}
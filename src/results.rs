@@ -0,0 +1,183 @@
+// Copyright 2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::PortalError;
+
+use dbus::arg::{prop_cast, ArgType, OwnedFd, PropMap, RefArg};
+
+/// Typed access to a vardict sent by a portal, such as the results of a `Response` signal.
+///
+/// Each getter returns `Ok(None)` if the key is missing, and `PortalError::TypeMismatch` if the value
+/// has another type. Values wrapped in another variant, as sent by some portals, are unwrapped first.
+#[derive(Debug, Clone, Copy)]
+pub struct Results<'a> {
+  map: &'a PropMap,
+}
+
+impl<'a> Results<'a> {
+  /// Wraps the vardict `map`.
+  pub fn new(map: &'a PropMap) -> Self {
+    Self { map }
+  }
+
+  /// Reads an `s` value. Object paths are accepted too.
+  pub fn get_str(&self, key: &str) -> Result<Option<&'a str>, PortalError> {
+    self.get(key, "s", |value| value.as_str())
+  }
+
+  /// Reads a `u` value.
+  pub fn get_u32(&self, key: &str) -> Result<Option<u32>, PortalError> {
+    self.get(key, "u", |value| match value.arg_type() {
+      ArgType::UInt32 => value.as_u64().map(|value| value as u32),
+      _ => None,
+    })
+  }
+
+  /// Reads a `b` value.
+  pub fn get_bool(&self, key: &str) -> Result<Option<bool>, PortalError> {
+    self.get(key, "b", |value| match value.arg_type() {
+      ArgType::Boolean => value.as_u64().map(|value| value != 0),
+      _ => None,
+    })
+  }
+
+  /// Reads an `as` value.
+  pub fn get_str_array(&self, key: &str) -> Result<Option<Vec<String>>, PortalError> {
+    self.get(key, "as", |value| match value.arg_type() {
+      ArgType::Array => value
+        .as_iter()?
+        .map(|item| item.as_str().map(ToString::to_string))
+        .collect(),
+      _ => None,
+    })
+  }
+
+  /// Reads an `h` value, a file descriptor. The returned descriptor is a duplicate,
+  /// so it stays open when the results are dropped.
+  pub fn get_fd(&self, key: &str) -> Result<Option<OwnedFd>, PortalError> {
+    let value = match self.map.get(key) {
+      Some(value) => value,
+      None => return Ok(None),
+    };
+    prop_cast::<OwnedFd>(self.map, key)
+      .cloned()
+      .map(Some)
+      .ok_or_else(|| mismatch(key, "h", &*value.0))
+  }
+
+  /// Turns a missing value read with one of the getters into a `PortalError::UnexpectedResponse`:
+  /// `results.required("uri", Results::get_str)`.
+  pub fn required<T>(
+    &self,
+    key: &str,
+    get: impl FnOnce(&Self, &str) -> Result<Option<T>, PortalError>,
+  ) -> Result<T, PortalError> {
+    get(self, key)?
+      .ok_or_else(|| PortalError::UnexpectedResponse(format!("missing result `{}`", key)))
+  }
+
+  fn get<T>(
+    &self,
+    key: &str,
+    expected: &'static str,
+    convert: impl FnOnce(&'a dyn RefArg) -> Option<T>,
+  ) -> Result<Option<T>, PortalError> {
+    let value: &'a dyn RefArg = match self.map.get(key) {
+      Some(value) => &*value.0,
+      None => return Ok(None),
+    };
+    let mut inner = value;
+    while inner.arg_type() == ArgType::Variant {
+      match inner.as_iter().and_then(|mut items| items.next()) {
+        Some(item) => inner = item,
+        None => break,
+      }
+    }
+    convert(inner)
+      .map(Some)
+      .ok_or_else(|| mismatch(key, expected, value))
+  }
+}
+
+impl<'a> From<&'a PropMap> for Results<'a> {
+  fn from(map: &'a PropMap) -> Self {
+    Self::new(map)
+  }
+}
+
+fn mismatch(key: &str, expected: &'static str, value: &dyn RefArg) -> PortalError {
+  PortalError::TypeMismatch {
+    key: key.to_string(),
+    expected,
+    found: value.signature().to_string(),
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::Results;
+  use crate::PortalError;
+  use dbus::arg::{PropMap, RefArg, Variant};
+
+  fn results() -> PropMap {
+    let mut map = PropMap::new();
+    map.insert(
+      "uri".to_string(),
+      Variant(Box::new("file:///a".to_string())),
+    );
+    map.insert("count".to_string(), Variant(Box::new(2u32)));
+    map.insert("writable".to_string(), Variant(Box::new(true)));
+    map.insert(
+      "uris".to_string(),
+      Variant(Box::new(vec!["file:///a".to_string()])),
+    );
+    map.insert(
+      "nested".to_string(),
+      Variant(Box::new(Variant(Box::new(3u32) as Box<dyn RefArg>))),
+    );
+    map
+  }
+
+  #[test]
+  fn typed_getters() {
+    let map = results();
+    let results = Results::new(&map);
+    assert_eq!(results.get_str("uri").unwrap(), Some("file:///a"));
+    assert_eq!(results.get_u32("count").unwrap(), Some(2));
+    assert_eq!(results.get_bool("writable").unwrap(), Some(true));
+    assert_eq!(
+      results.get_str_array("uris").unwrap(),
+      Some(vec!["file:///a".to_string()])
+    );
+    assert_eq!(results.get_u32("nested").unwrap(), Some(3));
+    assert_eq!(results.get_str("missing").unwrap(), None);
+    assert!(results.get_fd("missing").unwrap().is_none());
+  }
+
+  #[test]
+  fn missing_and_mismatched() {
+    let map = results();
+    let results = Results::new(&map);
+    assert!(matches!(
+      results.get_u32("writable"),
+      Err(PortalError::TypeMismatch { expected: "u", found, .. }) if found == "b"
+    ));
+    assert!(matches!(
+      results.get_bool("count"),
+      Err(PortalError::TypeMismatch { expected: "b", .. })
+    ));
+    assert!(matches!(
+      results.get_fd("uri"),
+      Err(PortalError::TypeMismatch { expected: "h", .. })
+    ));
+    assert!(matches!(
+      results.required("missing", Results::get_str),
+      Err(PortalError::UnexpectedResponse(_))
+    ));
+    assert_eq!(
+      results.required("uri", Results::get_str).unwrap(),
+      "file:///a"
+    );
+  }
+}
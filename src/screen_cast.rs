@@ -0,0 +1,616 @@
+// Copyright 2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::{
+  generate_handle_token, parse_streams,
+  trace::{self, CallSpan},
+  ParentWindowArg, PortalError, ResponseListener, Session, Stream, VersionedOptions,
+};
+
+#[cfg(feature = "spec-v4")]
+use crate::Results;
+use dbus::{
+  arg::{OwnedFd, PropMap, Variant},
+  blocking::{self, stdintf::org_freedesktop_dbus, Connection},
+  Path,
+};
+use std::{
+  ops::{BitOr, BitOrAssign},
+  time::Duration,
+};
+
+const INTERFACE: &str = "org.freedesktop.portal.ScreenCast";
+
+/// Implementation of the `org.freedesktop.portal.ScreenCast` Portal API.
+/// See also https://flatpak.github.io/xdg-desktop-portal/#gdbus-org.freedesktop.portal.ScreenCast
+///
+/// A screen cast goes through these steps:
+/// 1. `ScreenCast::create_session()`, then read the session with `Session::from_results()`.
+/// 2. `ScreenCast::select_sources()` to configure what to share.
+/// 3. `ScreenCast::start()`, which asks the user for permission and returns the `streams`,
+///    see `parse_streams()`.
+/// 4. `ScreenCast::open_pipe_wire_remote()` to get access to the streams.
+pub trait ScreenCast {
+  /// Creates a screen cast session.
+  ///
+  /// The `session_handle` is returned in the results of the `Response` signal.
+  fn create_session(&self, options: CreateSessionOptions) -> Result<Path<'static>, PortalError>;
+
+  /// Configures what to share in the screen cast session.
+  fn select_sources(
+    &self,
+    session: &Session,
+    options: SelectSourcesOptions,
+  ) -> Result<Path<'static>, PortalError>;
+
+  /// Starts the screen cast session, which lets the user pick what to share.
+  ///
+  /// The `streams` are returned in the results of the `Response` signal.
+  ///
+  /// - `parent_window`: Identifier for the application window, see crate comments for common conventions.
+  fn start(
+    &self,
+    session: &Session,
+    parent_window: impl Into<ParentWindowArg>,
+    options: StartOptions,
+  ) -> Result<Path<'static>, PortalError>;
+
+  /// Opens a file descriptor to the PipeWire remote where the screen cast streams are available.
+  ///
+  /// - `options`: Vardict with optional further information. There are currently no options.
+  fn open_pipe_wire_remote(
+    &self,
+    session: &Session,
+    options: PropMap,
+  ) -> Result<OwnedFd, PortalError>;
+
+  /// Starts the screen cast session, blocks until the user picked what to share, and opens the PipeWire remote.
+  ///
+  /// Returns the PipeWire remote fd along with the streams from the `Response` of `ScreenCast::start()`,
+  /// so their node ids can be connected to right away. The sources must already be selected with
+  /// `ScreenCast::select_sources()`, and the remote can only be opened once the session is started.
+  ///
+  /// Fails with `PortalError::Cancelled` when the user dismissed the dialog. If no `handle_token` is set,
+  /// one is generated, so the signal match for the predicted request path can be added on `connection`
+  /// before the request is made.
+  ///
+  /// - `parent_window`: Identifier for the application window, see crate comments for common conventions.
+  fn connect(
+    &self,
+    connection: &Connection,
+    session: &Session,
+    parent_window: impl Into<ParentWindowArg>,
+    options: StartOptions,
+    timeout: Duration,
+  ) -> Result<(OwnedFd, Vec<Stream>), PortalError>;
+
+  /// Reads the "AvailableSourceTypes" property, the source types that can be selected.
+  fn available_source_types(&self) -> Result<SourceType, PortalError>;
+
+  /// Reads the "AvailableCursorModes" property, the cursor modes that can be selected.
+  ///
+  /// The AvailableCursorModes property was introduced in version 2 of the interface.
+  #[cfg(feature = "spec-v2")]
+  fn available_cursor_modes(&self) -> Result<u32, PortalError>;
+
+  /// Reads the "version" property for this D-Bus interface.
+  fn version(&self) -> Result<u32, PortalError>;
+}
+
+/// Types of content to share. Types can be combined with `|`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SourceType(u32);
+
+impl SourceType {
+  /// Share existing monitors.
+  pub const MONITOR: Self = Self(1);
+  /// Share application windows.
+  pub const WINDOW: Self = Self(2);
+  /// Extend with new virtual monitors. Introduced in version 3 of the interface.
+  pub const VIRTUAL: Self = Self(4);
+
+  /// No types set.
+  pub fn empty() -> Self {
+    Self(0)
+  }
+
+  /// Creates a `SourceType` from the raw bits sent by the portal.
+  pub fn from_bits(bits: u32) -> Self {
+    Self(bits)
+  }
+
+  /// The raw bits, as sent to the portal.
+  pub fn bits(&self) -> u32 {
+    self.0
+  }
+
+  /// Whether all types in `other` are set.
+  pub fn contains(&self, other: Self) -> bool {
+    self.0 & other.0 == other.0
+  }
+}
+
+impl BitOr for SourceType {
+  type Output = Self;
+
+  fn bitor(self, rhs: Self) -> Self {
+    Self(self.0 | rhs.0)
+  }
+}
+
+impl BitOrAssign for SourceType {
+  fn bitor_assign(&mut self, rhs: Self) {
+    self.0 |= rhs.0;
+  }
+}
+
+/// How the cursor is drawn in the screen cast streams.
+///
+/// The cursor_mode option was introduced in version 2 of the interface.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CursorMode {
+  /// The cursor is not part of the screen cast stream.
+  Hidden,
+  /// The cursor is embedded as part of the stream buffers.
+  Embedded,
+  /// The cursor is not part of the screen cast stream, but sent as PipeWire stream metadata.
+  Metadata,
+}
+
+impl CursorMode {
+  fn bits(&self) -> u32 {
+    match self {
+      Self::Hidden => 1,
+      Self::Embedded => 2,
+      Self::Metadata => 4,
+    }
+  }
+}
+
+/// How the permission to share should be remembered.
+///
+/// The persist_mode option was introduced in version 4 of the interface.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PersistMode {
+  /// Do not persist.
+  None,
+  /// Persist while the application is running.
+  Transient,
+  /// Persist until explicitly revoked.
+  Persistent,
+}
+
+impl PersistMode {
+  fn bits(&self) -> u32 {
+    match self {
+      Self::None => 0,
+      Self::Transient => 1,
+      Self::Persistent => 2,
+    }
+  }
+}
+
+/// Reads the `restore_token` from the results of a successful `ScreenCast::start()` response.
+///
+/// The portal only returns a token if `SelectSourcesOptions::persist_mode()` was set. A token can be used once:
+/// pass it to `SelectSourcesOptions::restore_token()` of the next session, and store the new token that session returns.
+///
+/// ```no_run
+/// use dbus::arg::PropMap;
+/// use xdg_desktop_portal::{parse_restore_token, PersistMode, SelectSourcesOptions};
+///
+/// let token_file = "screen-cast-token";
+/// let mut options = SelectSourcesOptions::new().persist_mode(PersistMode::Persistent);
+/// if let Ok(restore_token) = std::fs::read_to_string(token_file) {
+///   options = options.restore_token(restore_token);
+/// }
+/// // Call `ScreenCast::select_sources()` with the options, then `ScreenCast::start()`.
+/// # let results = PropMap::new();
+/// if let Some(restore_token) = parse_restore_token(&results) {
+///   std::fs::write(token_file, restore_token)?;
+/// }
+/// # Ok::<(), std::io::Error>(())
+/// ```
+///
+/// The restore_token result was introduced in version 4 of the interface.
+#[cfg(feature = "spec-v4")]
+pub fn parse_restore_token(results: &PropMap) -> Option<String> {
+  Results::new(results)
+    .get_str("restore_token")
+    .ok()
+    .flatten()
+    .map(ToString::to_string)
+}
+
+/// Optional arguments for the `ScreenCast::create_session()`, `RemoteDesktop::create_session()` and
+/// `GlobalShortcuts::create_session()` methods.
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CreateSessionOptions {
+  handle_token: Option<String>,
+  session_handle_token: Option<String>,
+}
+
+impl CreateSessionOptions {
+  /// Creates a new `CreateSessionOptions` struct with no arguments set.
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  /// A string that will be used as the last element of the @handle. Must be a valid
+  /// object path element. See the #org.freedesktop.portal.Request documentation for
+  /// more information about the @handle.
+  pub fn handle_token(mut self, handle_token: impl Into<String>) -> Self {
+    self.handle_token = Some(handle_token.into());
+    self
+  }
+
+  /// A string that will be used as the last element of the session handle. Must be a valid
+  /// object path element. See the #org.freedesktop.portal.Session documentation for
+  /// more information about the session handle.
+  pub fn session_handle_token(mut self, session_handle_token: impl Into<String>) -> Self {
+    self.session_handle_token = Some(session_handle_token.into());
+    self
+  }
+}
+
+impl From<CreateSessionOptions> for PropMap {
+  fn from(options: CreateSessionOptions) -> Self {
+    let mut map = PropMap::new();
+    if let Some(handle_token) = options.handle_token {
+      map.insert("handle_token".to_string(), Variant(Box::new(handle_token)));
+    }
+    if let Some(session_handle_token) = options.session_handle_token {
+      map.insert(
+        "session_handle_token".to_string(),
+        Variant(Box::new(session_handle_token)),
+      );
+    }
+    map
+  }
+}
+
+/// Optional arguments for the `ScreenCast::select_sources()` method.
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SelectSourcesOptions {
+  handle_token: Option<String>,
+  types: Option<SourceType>,
+  multiple: Option<bool>,
+  cursor_mode: Option<CursorMode>,
+  restore_token: Option<String>,
+  persist_mode: Option<PersistMode>,
+}
+
+impl SelectSourcesOptions {
+  /// Creates a new `SelectSourcesOptions` struct with no arguments set.
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  /// A string that will be used as the last element of the @handle. Must be a valid
+  /// object path element. See the #org.freedesktop.portal.Request documentation for
+  /// more information about the @handle.
+  pub fn handle_token(mut self, handle_token: impl Into<String>) -> Self {
+    self.handle_token = Some(handle_token.into());
+    self
+  }
+
+  /// What types of content to record. Default is `SourceType::MONITOR`.
+  pub fn types(mut self, types: SourceType) -> Self {
+    self.types = Some(types);
+    self
+  }
+
+  /// Whether to allow selecting multiple sources. Default is no.
+  pub fn multiple(mut self, multiple: bool) -> Self {
+    self.multiple = Some(multiple);
+    self
+  }
+
+  /// Determines how the cursor will be drawn in the screen cast stream.
+  /// Must be one of the modes in `ScreenCast::available_cursor_modes()`.
+  ///
+  /// The cursor_mode option was introduced in version 2 of the interface.
+  pub fn cursor_mode(mut self, cursor_mode: CursorMode) -> Self {
+    self.cursor_mode = Some(cursor_mode);
+    self
+  }
+
+  /// The token to restore a previous session, as returned by a previous `ScreenCast::start()`.
+  ///
+  /// The restore_token option was introduced in version 4 of the interface.
+  pub fn restore_token(mut self, restore_token: String) -> Self {
+    self.restore_token = Some(restore_token);
+    self
+  }
+
+  /// How this session should persist. Default is `PersistMode::None`.
+  ///
+  /// The persist_mode option was introduced in version 4 of the interface.
+  pub fn persist_mode(mut self, persist_mode: PersistMode) -> Self {
+    self.persist_mode = Some(persist_mode);
+    self
+  }
+}
+
+impl VersionedOptions for SelectSourcesOptions {
+  fn versioned_options(&self) -> Vec<(&'static str, u32, bool)> {
+    vec![
+      ("cursor_mode", 2, self.cursor_mode.is_some()),
+      ("restore_token", 4, self.restore_token.is_some()),
+      ("persist_mode", 4, self.persist_mode.is_some()),
+    ]
+  }
+
+  fn unset_option(&mut self, option: &str) {
+    match option {
+      "cursor_mode" => self.cursor_mode = None,
+      "restore_token" => self.restore_token = None,
+      "persist_mode" => self.persist_mode = None,
+      _ => unreachable!("`{}` isn't validated", option),
+    }
+  }
+}
+
+impl From<SelectSourcesOptions> for PropMap {
+  fn from(options: SelectSourcesOptions) -> Self {
+    let mut map = PropMap::new();
+    if let Some(handle_token) = options.handle_token {
+      map.insert("handle_token".to_string(), Variant(Box::new(handle_token)));
+    }
+    if let Some(types) = options.types {
+      map.insert("types".to_string(), Variant(Box::new(types.bits())));
+    }
+    if let Some(multiple) = options.multiple {
+      map.insert("multiple".to_string(), Variant(Box::new(multiple)));
+    }
+    if let Some(cursor_mode) = options.cursor_mode {
+      map.insert(
+        "cursor_mode".to_string(),
+        Variant(Box::new(cursor_mode.bits())),
+      );
+    }
+    if let Some(restore_token) = options.restore_token {
+      map.insert(
+        "restore_token".to_string(),
+        Variant(Box::new(restore_token)),
+      );
+    }
+    if let Some(persist_mode) = options.persist_mode {
+      map.insert(
+        "persist_mode".to_string(),
+        Variant(Box::new(persist_mode.bits())),
+      );
+    }
+    map
+  }
+}
+
+/// Optional arguments for the `ScreenCast::start()`, `RemoteDesktop::start()` and
+/// `LocationPortal::start()` methods.
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StartOptions {
+  handle_token: Option<String>,
+}
+
+impl StartOptions {
+  /// Creates a new `StartOptions` struct with no arguments set.
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  /// A string that will be used as the last element of the @handle. Must be a valid
+  /// object path element. See the #org.freedesktop.portal.Request documentation for
+  /// more information about the @handle.
+  pub fn handle_token(mut self, handle_token: impl Into<String>) -> Self {
+    self.handle_token = Some(handle_token.into());
+    self
+  }
+
+  /// Returns the `handle_token`, generating one first if it's unset.
+  pub(crate) fn ensure_handle_token(&mut self) -> String {
+    self
+      .handle_token
+      .get_or_insert_with(generate_handle_token)
+      .clone()
+  }
+}
+
+impl From<StartOptions> for PropMap {
+  fn from(options: StartOptions) -> Self {
+    let mut map = PropMap::new();
+    if let Some(handle_token) = options.handle_token {
+      map.insert("handle_token".to_string(), Variant(Box::new(handle_token)));
+    }
+    map
+  }
+}
+
+/// The features the running `ScreenCast` portal supports, from a single `ScreenCast::version()` call,
+/// see `Portals::screen_cast_capabilities()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ScreenCastCapabilities {
+  version: u32,
+}
+
+impl ScreenCastCapabilities {
+  /// The capabilities of the `version` of the interface the portal implements.
+  pub fn from_version(version: u32) -> Self {
+    Self { version }
+  }
+
+  /// The version of the interface the portal implements.
+  pub fn version(&self) -> u32 {
+    self.version
+  }
+
+  /// Whether the `cursor_mode` option and the "AvailableCursorModes" property are supported.
+  pub fn supports_cursor_mode(&self) -> bool {
+    self.version >= 2
+  }
+
+  /// Whether `SourceType::VIRTUAL` can be selected.
+  pub fn supports_virtual_source(&self) -> bool {
+    self.version >= 3
+  }
+
+  /// Whether sessions can be restored, with the `restore_token` and `persist_mode` options.
+  pub fn supports_restore_token(&self) -> bool {
+    self.version >= 4
+  }
+}
+
+impl<'a, T: blocking::BlockingSender, C: std::ops::Deref<Target = T>> ScreenCast
+  for blocking::Proxy<'a, C>
+{
+  fn create_session(&self, options: CreateSessionOptions) -> Result<Path<'static>, PortalError> {
+    let options = PropMap::from(options);
+    let _span = CallSpan::enter(
+      INTERFACE,
+      "CreateSession",
+      "",
+      trace::handle_token(&options),
+    );
+    self
+      .method_call(INTERFACE, "CreateSession", (options,))
+      .map(|r: (Path<'static>,)| trace::request_created(r.0))
+      .map_err(Into::into)
+  }
+
+  fn select_sources(
+    &self,
+    session: &Session,
+    options: SelectSourcesOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    let options = PropMap::from(options);
+    let _span = CallSpan::enter(
+      INTERFACE,
+      "SelectSources",
+      "",
+      trace::handle_token(&options),
+    );
+    self
+      .method_call(INTERFACE, "SelectSources", (session.path(), options))
+      .map(|r: (Path<'static>,)| trace::request_created(r.0))
+      .map_err(Into::into)
+  }
+
+  fn start(
+    &self,
+    session: &Session,
+    parent_window: impl Into<ParentWindowArg>,
+    options: StartOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    let parent_window: ParentWindowArg = parent_window.into();
+    let options = PropMap::from(options);
+    let _span = CallSpan::enter(
+      INTERFACE,
+      "Start",
+      parent_window.as_str(),
+      trace::handle_token(&options),
+    );
+    self
+      .method_call(
+        INTERFACE,
+        "Start",
+        (session.path(), parent_window.as_str(), options),
+      )
+      .map(|r: (Path<'static>,)| trace::request_created(r.0))
+      .map_err(Into::into)
+  }
+
+  fn open_pipe_wire_remote(
+    &self,
+    session: &Session,
+    options: PropMap,
+  ) -> Result<OwnedFd, PortalError> {
+    self
+      .method_call(INTERFACE, "OpenPipeWireRemote", (session.path(), options))
+      .map(|r: (OwnedFd,)| r.0)
+      .map_err(Into::into)
+  }
+
+  fn connect(
+    &self,
+    connection: &Connection,
+    session: &Session,
+    parent_window: impl Into<ParentWindowArg>,
+    mut options: StartOptions,
+    timeout: Duration,
+  ) -> Result<(OwnedFd, Vec<Stream>), PortalError> {
+    let parent_window: ParentWindowArg = parent_window.into();
+    let listener = ResponseListener::for_call(
+      connection,
+      &self.destination,
+      INTERFACE,
+      "Start",
+      parent_window.as_str(),
+      &options.ensure_handle_token(),
+    )?;
+    let path = self.start(session, parent_window, options)?;
+    let streams = parse_streams(&listener.wait_success(&path, timeout)?)?;
+    let fd = self.open_pipe_wire_remote(session, PropMap::new())?;
+    Ok((fd, streams))
+  }
+
+  fn available_source_types(&self) -> Result<SourceType, PortalError> {
+    <Self as org_freedesktop_dbus::Properties>::get(self, INTERFACE, "AvailableSourceTypes")
+      .map(SourceType::from_bits)
+      .map_err(Into::into)
+  }
+
+  #[cfg(feature = "spec-v2")]
+  fn available_cursor_modes(&self) -> Result<u32, PortalError> {
+    <Self as org_freedesktop_dbus::Properties>::get(self, INTERFACE, "AvailableCursorModes")
+      .map_err(Into::into)
+  }
+
+  fn version(&self) -> Result<u32, PortalError> {
+    <Self as org_freedesktop_dbus::Properties>::get(self, INTERFACE, "version").map_err(Into::into)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  #[test]
+  #[cfg(feature = "spec-v4")]
+  fn parse_restore_token_results() {
+    use super::parse_restore_token;
+    use dbus::arg::{PropMap, Variant};
+
+    let mut results = PropMap::new();
+    assert_eq!(parse_restore_token(&results), None);
+    results.insert(
+      "restore_token".to_string(),
+      Variant(Box::new("token".to_string())),
+    );
+    assert_eq!(parse_restore_token(&results).as_deref(), Some("token"));
+  }
+  #[test]
+  fn select_sources_options_supported_by() {
+    use super::{CursorMode, PersistMode, SelectSourcesOptions};
+    use crate::VersionedOptions;
+    use dbus::arg::{prop_cast, PropMap};
+
+    let options = || {
+      SelectSourcesOptions::new()
+        .multiple(true)
+        .cursor_mode(CursorMode::Embedded)
+        .persist_mode(PersistMode::Transient)
+    };
+    assert!(options().validate_against(4).is_ok());
+    let map = PropMap::from(options().supported_by(3));
+    assert_eq!(prop_cast::<u32>(&map, "cursor_mode"), Some(&2));
+    assert!(!map.contains_key("persist_mode"));
+    let map = PropMap::from(options().supported_by(1));
+    assert_eq!(prop_cast::<bool>(&map, "multiple"), Some(&true));
+    assert!(!map.contains_key("cursor_mode"));
+  }
+}
@@ -0,0 +1,653 @@
+// Copyright 2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::{
+  file_uri_path, generate_handle_token,
+  trace::{self, CallSpan},
+  ParentWindowArg, PortalError, ResponseListener, Results, VersionedOptions,
+};
+
+use dbus::{
+  arg::{ArgType, PropMap, RefArg, Variant},
+  blocking::{self, stdintf::org_freedesktop_dbus, Connection},
+  Path,
+};
+use std::{fs, path::PathBuf, time::Duration};
+
+const INTERFACE: &str = "org.freedesktop.portal.Screenshot";
+
+/// Implementation of the `org.freedesktop.portal.Screenshot` Portal API.
+/// See also https://flatpak.github.io/xdg-desktop-portal/#gdbus-org.freedesktop.portal.Screenshot
+pub trait Screenshot {
+  /// Takes a screenshot.
+  ///
+  /// The `uri` of the screenshot is returned in the results of the `Response` signal.
+  ///
+  /// - `parent_window`: Identifier for the application window, see crate comments for common conventions.
+  fn screenshot(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    options: ScreenshotOptions,
+  ) -> Result<Path<'static>, PortalError>;
+
+  /// Same as `Screenshot::screenshot()`, but blocks until the user interaction has ended,
+  /// and returns the `uri` of the screenshot.
+  ///
+  /// If no `handle_token` is set, one is generated, so the signal match for the predicted
+  /// request path can be added on `connection` before the request is made.
+  /// Fails with `PortalError::Cancelled` if the user cancelled the interaction.
+  /// When `interactive` is set, fails with `PortalError::Unsupported` before the request is made
+  /// if the portal implements version 1 of the interface, which would take an immediate screenshot instead.
+  fn screenshot_and_wait(
+    &self,
+    connection: &Connection,
+    parent_window: impl Into<ParentWindowArg>,
+    options: ScreenshotOptions,
+    timeout: Duration,
+  ) -> Result<String, PortalError>;
+
+  /// Same as `Screenshot::screenshot_and_wait()`, but copies the screenshot to `dest`, and returns that path.
+  ///
+  /// The file the portal returns may be removed later, or be in a location the application doesn't control.
+  /// Fails with `PortalError::UnexpectedResponse` if the screenshot isn't a local file.
+  fn screenshot_to_path(
+    &self,
+    connection: &Connection,
+    parent_window: impl Into<ParentWindowArg>,
+    dest: impl AsRef<std::path::Path>,
+    options: ScreenshotOptions,
+    timeout: Duration,
+  ) -> Result<PathBuf, PortalError>;
+
+  /// Obtains the color of a single pixel.
+  ///
+  /// The `color` is returned in the results of the `Response` signal, as RGB values in the range [0,1].
+  /// It can be read with `Color::try_from()`.
+  ///
+  /// - `parent_window`: Identifier for the application window, see crate comments for common conventions.
+  fn pick_color(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    options: PickColorOptions,
+  ) -> Result<Path<'static>, PortalError>;
+
+  /// Same as `Screenshot::pick_color()`, but blocks until the user interaction has ended,
+  /// and returns the picked color.
+  ///
+  /// See `Screenshot::screenshot_and_wait()` for details.
+  fn pick_color_and_wait(
+    &self,
+    connection: &Connection,
+    parent_window: impl Into<ParentWindowArg>,
+    options: PickColorOptions,
+    timeout: Duration,
+  ) -> Result<Color, PortalError>;
+
+  /// Reads the "version" property for this D-Bus interface.
+  fn version(&self) -> Result<u32, PortalError>;
+}
+
+/// Optional arguments for the `Screenshot::screenshot()` method.
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ScreenshotOptions {
+  handle_token: Option<String>,
+  modal: Option<bool>,
+  interactive: Option<bool>,
+}
+
+impl ScreenshotOptions {
+  /// Creates a new `ScreenshotOptions` struct with no arguments set.
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  /// A string that will be used as the last element of the @handle. Must be a valid
+  /// object path element. See the #org.freedesktop.portal.Request documentation for
+  /// more information about the @handle.
+  pub fn handle_token(mut self, handle_token: impl Into<String>) -> Self {
+    self.handle_token = Some(handle_token.into());
+    self
+  }
+
+  /// Whether the dialog should be modal. Default is yes.
+  pub fn modal(mut self, modal: bool) -> Self {
+    self.modal = Some(modal);
+    self
+  }
+
+  /// Hint whether the dialog should offer customization before taking a screenshot.
+  /// Default is no.
+  ///
+  /// The interactive option was introduced in version 2 of the interface.
+  pub fn interactive(mut self, interactive: bool) -> Self {
+    self.interactive = Some(interactive);
+    self
+  }
+
+  /// Returns the `handle_token`, generating one first if it's unset.
+  pub(crate) fn ensure_handle_token(&mut self) -> String {
+    self
+      .handle_token
+      .get_or_insert_with(generate_handle_token)
+      .clone()
+  }
+
+  /// Checks that an interactive screenshot, if asked for, is supported by the `version` of the interface
+  /// the portal implements. Older portals silently take an immediate screenshot instead.
+  fn check_interactive(&self, version: u32) -> Result<(), PortalError> {
+    if self.interactive == Some(true) && version < 2 {
+      return Err(PortalError::Unsupported {
+        feature: "interactive",
+        required: 2,
+        version,
+      });
+    }
+    Ok(())
+  }
+}
+
+impl VersionedOptions for ScreenshotOptions {
+  fn versioned_options(&self) -> Vec<(&'static str, u32, bool)> {
+    vec![("interactive", 2, self.interactive.is_some())]
+  }
+
+  fn unset_option(&mut self, option: &str) {
+    match option {
+      "interactive" => self.interactive = None,
+      _ => unreachable!("`{}` isn't validated", option),
+    }
+  }
+}
+
+impl From<ScreenshotOptions> for PropMap {
+  fn from(options: ScreenshotOptions) -> Self {
+    let mut map = PropMap::new();
+    if let Some(handle_token) = options.handle_token {
+      map.insert("handle_token".to_string(), Variant(Box::new(handle_token)));
+    }
+    if let Some(modal) = options.modal {
+      map.insert("modal".to_string(), Variant(Box::new(modal)));
+    }
+    if let Some(interactive) = options.interactive {
+      map.insert("interactive".to_string(), Variant(Box::new(interactive)));
+    }
+    map
+  }
+}
+
+/// Reads the `uri` from the results of a successful `Screenshot::screenshot()` request.
+fn parse_screenshot_uri(results: &PropMap) -> Result<String, PortalError> {
+  Results::new(results)
+    .required("uri", Results::get_str)
+    .map(ToString::to_string)
+}
+
+/// Copies the screenshot at the `file://` `uri` to `dest`, unless it's already there.
+fn copy_screenshot(uri: &str, dest: &std::path::Path) -> Result<PathBuf, PortalError> {
+  let source = file_uri_path(uri).ok_or_else(|| {
+    PortalError::UnexpectedResponse(format!("screenshot `{}` is not a local file", uri))
+  })?;
+  let same_file = match (fs::canonicalize(&source), fs::canonicalize(dest)) {
+    (Ok(source), Ok(dest)) => source == dest,
+    _ => false,
+  };
+  if !same_file {
+    fs::copy(&source, dest)?;
+  }
+  Ok(dest.to_path_buf())
+}
+
+/// Optional arguments for the `Screenshot::pick_color()` method.
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PickColorOptions {
+  handle_token: Option<String>,
+}
+
+impl PickColorOptions {
+  /// Creates a new `PickColorOptions` struct with no arguments set.
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  /// A string that will be used as the last element of the @handle. Must be a valid
+  /// object path element. See the #org.freedesktop.portal.Request documentation for
+  /// more information about the @handle.
+  pub fn handle_token(mut self, handle_token: impl Into<String>) -> Self {
+    self.handle_token = Some(handle_token.into());
+    self
+  }
+
+  /// Returns the `handle_token`, generating one first if it's unset.
+  pub(crate) fn ensure_handle_token(&mut self) -> String {
+    self
+      .handle_token
+      .get_or_insert_with(generate_handle_token)
+      .clone()
+  }
+}
+
+impl From<PickColorOptions> for PropMap {
+  fn from(options: PickColorOptions) -> Self {
+    let mut map = PropMap::new();
+    if let Some(handle_token) = options.handle_token {
+      map.insert("handle_token".to_string(), Variant(Box::new(handle_token)));
+    }
+    map
+  }
+}
+
+/// A color picked with `Screenshot::pick_color()`, as RGB values in the range [0,1].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Color {
+  /// The red component.
+  pub r: f64,
+  /// The green component.
+  pub g: f64,
+  /// The blue component.
+  pub b: f64,
+}
+
+impl Color {
+  /// The color as 8-bit RGB values. Components outside of the range [0,1] are clamped.
+  pub fn to_rgb8(&self) -> [u8; 3] {
+    [self.r, self.g, self.b].map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8)
+  }
+
+  /// The color in hexadecimal notation, like `#ff8000`.
+  pub fn to_hex(&self) -> String {
+    let [r, g, b] = self.to_rgb8();
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+  }
+}
+
+impl TryFrom<&PropMap> for Color {
+  type Error = PortalError;
+
+  /// Reads the `(ddd)` color from the results of a successful `Screenshot::pick_color()` request.
+  ///
+  /// An array of three doubles is accepted as well, as some backends send one.
+  fn try_from(results: &PropMap) -> Result<Self, Self::Error> {
+    let value = results
+      .get("color")
+      .ok_or_else(|| PortalError::UnexpectedResponse("missing result `color`".to_string()))?;
+    parse_color(&*value.0).ok_or_else(|| {
+      PortalError::UnexpectedResponse(format!(
+        "result `color` has signature `{}`, expected three doubles",
+        value.0.signature()
+      ))
+    })
+  }
+}
+
+/// Reads exactly three doubles from a struct or an array, possibly wrapped in a variant.
+fn parse_color(value: &dyn RefArg) -> Option<Color> {
+  let mut value = value;
+  while value.arg_type() == ArgType::Variant {
+    value = value.as_iter()?.next()?;
+  }
+  if !matches!(value.arg_type(), ArgType::Struct | ArgType::Array) {
+    return None;
+  }
+  let mut fields = value.as_iter()?;
+  // `as_f64()` also converts integers, which would misread a `(uuu)` color.
+  let mut component = || {
+    fields
+      .next()
+      .filter(|field| field.arg_type() == ArgType::Double)?
+      .as_f64()
+  };
+  let color = Color {
+    r: component()?,
+    g: component()?,
+    b: component()?,
+  };
+  fields.next().is_none().then_some(color)
+}
+
+/// The features the running `Screenshot` portal supports, from a single `Screenshot::version()` call,
+/// see `Portals::screenshot_capabilities()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ScreenshotCapabilities {
+  version: u32,
+}
+
+impl ScreenshotCapabilities {
+  /// The capabilities of the `version` of the interface the portal implements.
+  pub fn from_version(version: u32) -> Self {
+    Self { version }
+  }
+
+  /// The version of the interface the portal implements.
+  pub fn version(&self) -> u32 {
+    self.version
+  }
+
+  /// Whether the `interactive` option of `Screenshot::screenshot()` is supported.
+  pub fn supports_interactive(&self) -> bool {
+    self.version >= 2
+  }
+
+  /// Whether the `modal` option of `Screenshot::screenshot()` is supported.
+  pub fn supports_modal(&self) -> bool {
+    self.version >= 2
+  }
+
+  /// Whether `Screenshot::pick_color()` is supported.
+  pub fn supports_pick_color(&self) -> bool {
+    self.version >= 2
+  }
+}
+
+impl<'a, T: blocking::BlockingSender, C: std::ops::Deref<Target = T>> Screenshot
+  for blocking::Proxy<'a, C>
+{
+  fn screenshot(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    options: ScreenshotOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    let parent_window: ParentWindowArg = parent_window.into();
+    let options = PropMap::from(options);
+    let _span = CallSpan::enter(
+      INTERFACE,
+      "Screenshot",
+      parent_window.as_str(),
+      trace::handle_token(&options),
+    );
+    self
+      .method_call(INTERFACE, "Screenshot", (parent_window.as_str(), options))
+      .map(|r: (Path<'static>,)| trace::request_created(r.0))
+      .map_err(Into::into)
+  }
+
+  fn screenshot_and_wait(
+    &self,
+    connection: &Connection,
+    parent_window: impl Into<ParentWindowArg>,
+    mut options: ScreenshotOptions,
+    timeout: Duration,
+  ) -> Result<String, PortalError> {
+    if options.interactive == Some(true) {
+      options.check_interactive(self.version()?)?;
+    }
+    let parent_window: ParentWindowArg = parent_window.into();
+    let listener = ResponseListener::for_call(
+      connection,
+      &self.destination,
+      INTERFACE,
+      "Screenshot",
+      parent_window.as_str(),
+      &options.ensure_handle_token(),
+    )?;
+    let path = self.screenshot(parent_window, options)?;
+    parse_screenshot_uri(&listener.wait_success(&path, timeout)?)
+  }
+
+  fn screenshot_to_path(
+    &self,
+    connection: &Connection,
+    parent_window: impl Into<ParentWindowArg>,
+    dest: impl AsRef<std::path::Path>,
+    options: ScreenshotOptions,
+    timeout: Duration,
+  ) -> Result<PathBuf, PortalError> {
+    let uri = self.screenshot_and_wait(connection, parent_window, options, timeout)?;
+    copy_screenshot(&uri, dest.as_ref())
+  }
+
+  fn pick_color(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    options: PickColorOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    let parent_window: ParentWindowArg = parent_window.into();
+    let options = PropMap::from(options);
+    let _span = CallSpan::enter(
+      INTERFACE,
+      "PickColor",
+      parent_window.as_str(),
+      trace::handle_token(&options),
+    );
+    self
+      .method_call(INTERFACE, "PickColor", (parent_window.as_str(), options))
+      .map(|r: (Path<'static>,)| trace::request_created(r.0))
+      .map_err(Into::into)
+  }
+
+  fn pick_color_and_wait(
+    &self,
+    connection: &Connection,
+    parent_window: impl Into<ParentWindowArg>,
+    mut options: PickColorOptions,
+    timeout: Duration,
+  ) -> Result<Color, PortalError> {
+    let parent_window: ParentWindowArg = parent_window.into();
+    let listener = ResponseListener::for_call(
+      connection,
+      &self.destination,
+      INTERFACE,
+      "PickColor",
+      parent_window.as_str(),
+      &options.ensure_handle_token(),
+    )?;
+    let path = self.pick_color(parent_window, options)?;
+    Color::try_from(&listener.wait_success(&path, timeout)?)
+  }
+
+  fn version(&self) -> Result<u32, PortalError> {
+    <Self as org_freedesktop_dbus::Properties>::get(self, INTERFACE, "version").map_err(Into::into)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::{copy_screenshot, parse_screenshot_uri, Color};
+  use crate::PortalError;
+  use dbus::arg::{PropMap, RefArg, Variant};
+
+  #[test]
+  fn parse_screenshot_uri_results() {
+    let mut results = PropMap::new();
+    assert!(matches!(
+      parse_screenshot_uri(&results),
+      Err(PortalError::UnexpectedResponse(_))
+    ));
+
+    results.insert("uri".to_string(), Variant(Box::new(1u32)));
+    assert!(matches!(
+      parse_screenshot_uri(&results),
+      Err(PortalError::TypeMismatch { expected: "s", .. })
+    ));
+
+    let uri = "file:///home/user/Pictures/Screenshot.png";
+    results.insert("uri".to_string(), Variant(Box::new(uri.to_string())));
+    assert_eq!(parse_screenshot_uri(&results).unwrap(), uri);
+  }
+
+  #[test]
+  fn color_from_results() {
+    let mut results = PropMap::new();
+    results.insert(
+      "color".to_string(),
+      Variant(Box::new((1.0f64, 0.5f64, 0.0f64))),
+    );
+    let color = Color::try_from(&results).unwrap();
+    assert_eq!(
+      color,
+      Color {
+        r: 1.0,
+        g: 0.5,
+        b: 0.0
+      }
+    );
+    assert_eq!(color.to_rgb8(), [255, 128, 0]);
+    assert_eq!(color.to_hex(), "#ff8000");
+
+    results.insert(
+      "color".to_string(),
+      Variant(Box::new(vec![0.25f64, 1.5, -0.5])),
+    );
+    let color = Color::try_from(&results).unwrap();
+    assert_eq!(
+      color,
+      Color {
+        r: 0.25,
+        g: 1.5,
+        b: -0.5
+      }
+    );
+    assert_eq!(color.to_rgb8(), [64, 255, 0]);
+
+    results.insert(
+      "color".to_string(),
+      Variant(Box::new(Variant(Box::new((0.0f64, 0.0f64, 1.0f64))))),
+    );
+    assert_eq!(Color::try_from(&results).unwrap().to_hex(), "#0000ff");
+
+    for wrong in [
+      Variant(Box::new((1u32, 2u32, 3u32)) as Box<dyn RefArg>),
+      Variant(Box::new(vec![0.0f64, 0.5])),
+      Variant(Box::new(vec![0.0f64, 0.5, 1.0, 1.0])),
+      Variant(Box::new(0.5f64)),
+    ] {
+      let found = wrong.0.signature().to_string();
+      results.insert("color".to_string(), wrong);
+      match Color::try_from(&results) {
+        Err(PortalError::UnexpectedResponse(message)) => {
+          assert!(message.contains(&format!("`{}`", found)), "{}", message)
+        }
+        other => panic!("unexpected {:?}", other),
+      }
+    }
+  }
+
+  #[test]
+  fn copy_screenshot_to_dest() {
+    let dir = std::env::temp_dir().join(format!("screenshot-test-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let source = dir.join("Screenshot.png");
+    let dest = dir.join("copy.png");
+    std::fs::write(&source, b"png").unwrap();
+    let uri = format!("file://{}", source.display());
+
+    assert_eq!(copy_screenshot(&uri, &dest).unwrap(), dest);
+    assert_eq!(std::fs::read(&dest).unwrap(), b"png");
+    assert_eq!(copy_screenshot(&uri, &source).unwrap(), source);
+    assert_eq!(std::fs::read(&source).unwrap(), b"png");
+    assert!(matches!(
+      copy_screenshot("https://example.com/a.png", &dest),
+      Err(PortalError::UnexpectedResponse(_))
+    ));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+  }
+
+  #[test]
+  fn interactive_requires_version_2() {
+    use super::ScreenshotOptions;
+
+    let options = ScreenshotOptions::new().interactive(true);
+    assert!(matches!(
+      options.check_interactive(1),
+      Err(PortalError::Unsupported {
+        feature: "interactive",
+        required: 2,
+        version: 1,
+      })
+    ));
+    assert!(options.check_interactive(2).is_ok());
+    assert!(ScreenshotOptions::new()
+      .interactive(false)
+      .check_interactive(1)
+      .is_ok());
+    assert!(ScreenshotOptions::new().check_interactive(1).is_ok());
+  }
+
+  #[test]
+  #[cfg(feature = "testing")]
+  fn screenshot_on_mock_sender() {
+    use super::{PickColorOptions, Screenshot, ScreenshotOptions, INTERFACE};
+    use crate::{new_blocking, testing::MockSender};
+    use dbus::{arg::prop_cast, Path};
+    use std::time::Duration;
+
+    let sender = MockSender::new();
+    sender.version(INTERFACE, 2);
+    let path = Path::new("/org/freedesktop/portal/desktop/request/1_1/token").unwrap();
+    let reply = path.clone();
+    sender.reply(INTERFACE, "Screenshot", move |message| {
+      message.append1(reply.clone())
+    });
+    let portals = new_blocking(Duration::from_secs(2), &sender);
+
+    assert_eq!(portals.version().unwrap(), 2);
+    let options = ScreenshotOptions::new()
+      .handle_token("token")
+      .interactive(true);
+    assert_eq!(portals.screenshot("x11:1f", options).unwrap(), path);
+    assert!(matches!(
+      portals.pick_color("", PickColorOptions::new()),
+      Err(PortalError::Dbus(_))
+    ));
+
+    let calls = sender.take_calls();
+    assert_eq!(calls.len(), 3);
+    assert_eq!(calls[1].member, "Screenshot");
+    let (parent_window, options): (String, PropMap) = calls[1].message.read2().unwrap();
+    assert_eq!(parent_window, "x11:1f");
+    assert_eq!(prop_cast::<bool>(&options, "interactive"), Some(&true));
+    assert_eq!(
+      prop_cast::<String>(&options, "handle_token").map(String::as_str),
+      Some("token")
+    );
+  }
+
+  #[test]
+  #[cfg(feature = "testing")]
+  fn screenshot_and_wait_interactive_on_version_1() {
+    use super::{Screenshot, ScreenshotOptions, INTERFACE};
+    use crate::{
+      new_blocking,
+      testing::{MockSender, TestBus},
+    };
+    use std::time::Duration;
+
+    let bus = TestBus::new().unwrap();
+    let conn = bus.connect().unwrap();
+    let sender = MockSender::new();
+    sender.version(INTERFACE, 1);
+    let portals = new_blocking(Duration::from_secs(2), &sender);
+
+    let options = ScreenshotOptions::new().interactive(true);
+    assert!(matches!(
+      portals.screenshot_and_wait(&conn, "", options, Duration::from_secs(2)),
+      Err(PortalError::Unsupported {
+        feature: "interactive",
+        required: 2,
+        version: 1,
+      })
+    ));
+    let calls = sender.take_calls();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].member, "Get");
+  }
+
+  #[test]
+  fn capabilities_from_version() {
+    use super::ScreenshotCapabilities;
+
+    let caps = ScreenshotCapabilities::from_version(1);
+    assert!(!caps.supports_interactive());
+    assert!(!caps.supports_pick_color());
+    let caps = ScreenshotCapabilities::from_version(2);
+    assert_eq!(caps.version(), 2);
+    assert!(caps.supports_interactive());
+    assert!(caps.supports_modal());
+    assert!(caps.supports_pick_color());
+  }
+}
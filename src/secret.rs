@@ -0,0 +1,138 @@
+// Copyright 2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::{
+  fd::{into_file, pipe},
+  generate_handle_token,
+  trace::{self, CallSpan},
+  IntoPortalFd, PortalError, ResponseListener,
+};
+
+use dbus::{
+  arg::{PropMap, Variant},
+  blocking::{self, stdintf::org_freedesktop_dbus, Connection},
+  Path,
+};
+use std::{io::Read, time::Duration};
+
+const INTERFACE: &str = "org.freedesktop.portal.Secret";
+
+/// Implementation of the `org.freedesktop.portal.Secret` Portal API.
+/// See also https://flatpak.github.io/xdg-desktop-portal/#gdbus-org.freedesktop.portal.Secret
+pub trait Secret {
+  /// Retrieves a master secret for a sandboxed application.
+  ///
+  /// The master secret is unique per application and does not change as long as the application
+  /// is installed. It is written to `fd` before the `Response` signal is emitted.
+  ///
+  /// - `fd`: Writable file descriptor for transporting the secret, usually the write end of a pipe.
+  fn retrieve_secret(
+    &self,
+    fd: impl IntoPortalFd,
+    options: SecretOptions,
+  ) -> Result<Path<'static>, PortalError>;
+
+  /// Retrieves the master secret through a pipe, blocking until the portal has responded.
+  ///
+  /// The signal match for the `Response` is added on `connection` before the request is made.
+  fn retrieve_secret_bytes(
+    &self,
+    connection: &Connection,
+    timeout: Duration,
+  ) -> Result<Vec<u8>, PortalError>;
+
+  /// Reads the "version" property for this D-Bus interface.
+  fn version(&self) -> Result<u32, PortalError>;
+}
+
+/// Optional arguments for the `Secret::retrieve_secret()` method.
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SecretOptions {
+  handle_token: Option<String>,
+  token: Option<String>,
+}
+
+impl SecretOptions {
+  /// Creates a new `SecretOptions` struct with no arguments set.
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  /// A string that will be used as the last element of the @handle. Must be a valid
+  /// object path element. See the #org.freedesktop.portal.Request documentation for
+  /// more information about the @handle.
+  pub fn handle_token(mut self, handle_token: impl Into<String>) -> Self {
+    self.handle_token = Some(handle_token.into());
+    self
+  }
+
+  /// An opaque string returned by a previous `Secret::retrieve_secret()` call.
+  pub fn token(mut self, token: String) -> Self {
+    self.token = Some(token);
+    self
+  }
+}
+
+impl From<SecretOptions> for PropMap {
+  fn from(options: SecretOptions) -> Self {
+    let mut map = PropMap::new();
+    if let Some(handle_token) = options.handle_token {
+      map.insert("handle_token".to_string(), Variant(Box::new(handle_token)));
+    }
+    if let Some(token) = options.token {
+      map.insert("token".to_string(), Variant(Box::new(token)));
+    }
+    map
+  }
+}
+
+impl<'a, T: blocking::BlockingSender, C: std::ops::Deref<Target = T>> Secret
+  for blocking::Proxy<'a, C>
+{
+  fn retrieve_secret(
+    &self,
+    fd: impl IntoPortalFd,
+    options: SecretOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    let options = PropMap::from(options);
+    let _span = CallSpan::enter(
+      INTERFACE,
+      "RetrieveSecret",
+      "",
+      trace::handle_token(&options),
+    );
+    self
+      .method_call(INTERFACE, "RetrieveSecret", (fd.into_portal_fd(), options))
+      .map(|r: (Path<'static>,)| trace::request_created(r.0))
+      .map_err(Into::into)
+  }
+
+  fn retrieve_secret_bytes(
+    &self,
+    connection: &Connection,
+    timeout: Duration,
+  ) -> Result<Vec<u8>, PortalError> {
+    let (reader, writer) = pipe()?;
+    let handle_token = generate_handle_token();
+    let listener = ResponseListener::for_call(
+      connection,
+      &self.destination,
+      INTERFACE,
+      "RetrieveSecret",
+      "",
+      &handle_token,
+    )?;
+    // The write end is closed on our side once the call returns, so reading ends when the portal closes its copy.
+    let path = self.retrieve_secret(writer, SecretOptions::new().handle_token(handle_token))?;
+    listener.wait_success(&path, timeout)?;
+    let mut secret = Vec::new();
+    into_file(reader).read_to_end(&mut secret)?;
+    Ok(secret)
+  }
+
+  fn version(&self) -> Result<u32, PortalError> {
+    <Self as org_freedesktop_dbus::Properties>::get(self, INTERFACE, "version").map_err(Into::into)
+  }
+}
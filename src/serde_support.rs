@@ -0,0 +1,222 @@
+// Copyright 2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! `serde` support for the `Variant` values held by notifications and settings, enabled with the `serde` feature.
+//!
+//! A `Variant` is serialized as the plain value it holds: arrays and structs as sequences, dicts as maps.
+//! As the D-Bus type isn't kept, it's deserialized into the closest D-Bus type instead: `b`, `x` or `t`, `d`, `s`,
+//! `av` for sequences and `a{sv}` for maps. Deserializing requires a self-describing format, such as JSON.
+
+use dbus::arg::{ArgType, PropMap, RefArg, Variant};
+use serde::{
+  de::{self, Deserializer, MapAccess, SeqAccess, Visitor},
+  ser::{self, SerializeMap, SerializeSeq, Serializer},
+  Deserialize, Serialize,
+};
+use std::fmt;
+
+/// For `#[serde(with = "crate::serde_support::variant")]` on a `Variant<Box<dyn RefArg>>` field.
+pub(crate) mod variant {
+  use super::{Owned, Value};
+  use dbus::arg::{RefArg, Variant};
+  use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+  pub(crate) fn serialize<S: Serializer>(
+    value: &Variant<Box<dyn RefArg>>,
+    serializer: S,
+  ) -> Result<S::Ok, S::Error> {
+    Value(&*value.0).serialize(serializer)
+  }
+
+  pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+    deserializer: D,
+  ) -> Result<Variant<Box<dyn RefArg>>, D::Error> {
+    Owned::deserialize(deserializer).map(|value| Variant(value.0))
+  }
+}
+
+/// For `#[serde(default, with = "crate::serde_support::option_variant")]` on an `Option<Variant<Box<dyn RefArg>>>` field.
+pub(crate) mod option_variant {
+  use super::{Owned, Value};
+  use dbus::arg::{RefArg, Variant};
+  use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+  pub(crate) fn serialize<S: Serializer>(
+    value: &Option<Variant<Box<dyn RefArg>>>,
+    serializer: S,
+  ) -> Result<S::Ok, S::Error> {
+    value
+      .as_ref()
+      .map(|value| Value(&*value.0))
+      .serialize(serializer)
+  }
+
+  pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+    deserializer: D,
+  ) -> Result<Option<Variant<Box<dyn RefArg>>>, D::Error> {
+    Option::<Owned>::deserialize(deserializer).map(|value| value.map(|value| Variant(value.0)))
+  }
+}
+
+/// A borrowed D-Bus value, serialized as the plain value it holds.
+struct Value<'a>(&'a dyn RefArg);
+
+impl Serialize for Value<'_> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let value = self.0;
+    let unsupported = || {
+      <S::Error as ser::Error>::custom(format!("can't serialize a `{}` value", value.signature()))
+    };
+    match value.arg_type() {
+      ArgType::Boolean => serializer.serialize_bool(value.as_u64().ok_or_else(unsupported)? != 0),
+      ArgType::Byte | ArgType::UInt16 | ArgType::UInt32 | ArgType::UInt64 => {
+        serializer.serialize_u64(value.as_u64().ok_or_else(unsupported)?)
+      }
+      ArgType::Int16 | ArgType::Int32 | ArgType::Int64 => {
+        serializer.serialize_i64(value.as_i64().ok_or_else(unsupported)?)
+      }
+      ArgType::Double => serializer.serialize_f64(value.as_f64().ok_or_else(unsupported)?),
+      ArgType::String | ArgType::ObjectPath | ArgType::Signature => {
+        serializer.serialize_str(value.as_str().ok_or_else(unsupported)?)
+      }
+      ArgType::Variant => {
+        let inner = value
+          .as_iter()
+          .and_then(|mut items| items.next())
+          .ok_or_else(unsupported)?;
+        Value(inner).serialize(serializer)
+      }
+      ArgType::Array if value.signature().starts_with("a{") => {
+        let items: Vec<_> = value.as_iter().ok_or_else(unsupported)?.collect();
+        let mut map = serializer.serialize_map(Some(items.len() / 2))?;
+        for entry in items.chunks(2) {
+          if let [key, value] = entry {
+            map.serialize_entry(&Value(*key), &Value(*value))?;
+          }
+        }
+        map.end()
+      }
+      ArgType::Array | ArgType::Struct => {
+        let items: Vec<_> = value.as_iter().ok_or_else(unsupported)?.collect();
+        let mut seq = serializer.serialize_seq(Some(items.len()))?;
+        for item in items {
+          seq.serialize_element(&Value(item))?;
+        }
+        seq.end()
+      }
+      ArgType::UnixFd | ArgType::DictEntry | ArgType::Invalid => Err(unsupported()),
+    }
+  }
+}
+
+/// A D-Bus value of the closest type to the deserialized value.
+struct Owned(Box<dyn RefArg>);
+
+impl<'de> Deserialize<'de> for Owned {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    deserializer.deserialize_any(OwnedVisitor)
+  }
+}
+
+struct OwnedVisitor;
+
+impl<'de> Visitor<'de> for OwnedVisitor {
+  type Value = Owned;
+
+  fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str("a value representable as a D-Bus variant")
+  }
+
+  fn visit_bool<E: de::Error>(self, value: bool) -> Result<Owned, E> {
+    Ok(Owned(Box::new(value)))
+  }
+
+  fn visit_i64<E: de::Error>(self, value: i64) -> Result<Owned, E> {
+    Ok(Owned(Box::new(value)))
+  }
+
+  fn visit_u64<E: de::Error>(self, value: u64) -> Result<Owned, E> {
+    Ok(Owned(Box::new(value)))
+  }
+
+  fn visit_f64<E: de::Error>(self, value: f64) -> Result<Owned, E> {
+    Ok(Owned(Box::new(value)))
+  }
+
+  fn visit_str<E: de::Error>(self, value: &str) -> Result<Owned, E> {
+    Ok(Owned(Box::new(value.to_string())))
+  }
+
+  fn visit_string<E: de::Error>(self, value: String) -> Result<Owned, E> {
+    Ok(Owned(Box::new(value)))
+  }
+
+  fn visit_bytes<E: de::Error>(self, value: &[u8]) -> Result<Owned, E> {
+    Ok(Owned(Box::new(value.to_vec())))
+  }
+
+  fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Owned, A::Error> {
+    let mut items: Vec<Variant<Box<dyn RefArg>>> = Vec::new();
+    while let Some(item) = seq.next_element::<Owned>()? {
+      items.push(Variant(item.0));
+    }
+    Ok(Owned(Box::new(items)))
+  }
+
+  fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Owned, A::Error> {
+    let mut entries = PropMap::new();
+    while let Some((key, value)) = map.next_entry::<String, Owned>()? {
+      entries.insert(key, Variant(value.0));
+    }
+    Ok(Owned(Box::new(entries)))
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::{option_variant, Owned};
+  use serde::de::{
+    value::{Error, MapDeserializer, UnitDeserializer},
+    IntoDeserializer,
+  };
+  use serde::Deserialize;
+
+  #[test]
+  fn owned_closest_types() {
+    let value = Owned::deserialize(IntoDeserializer::<Error>::into_deserializer("dark")).unwrap();
+    assert_eq!(&*value.0.signature(), "s");
+    assert_eq!(value.0.as_str(), Some("dark"));
+
+    let value = Owned::deserialize(IntoDeserializer::<Error>::into_deserializer(3u32)).unwrap();
+    assert_eq!(&*value.0.signature(), "t");
+    assert_eq!(value.0.as_u64(), Some(3));
+
+    let value = Owned::deserialize(IntoDeserializer::<Error>::into_deserializer(-3i32)).unwrap();
+    assert_eq!(&*value.0.signature(), "x");
+
+    let value = Owned::deserialize(IntoDeserializer::<Error>::into_deserializer(vec![
+      "a".to_string(),
+      "b".to_string(),
+    ]))
+    .unwrap();
+    assert_eq!(&*value.0.signature(), "av");
+
+    let map = MapDeserializer::<_, Error>::new(
+      vec![("accent".to_string(), "blue".to_string())].into_iter(),
+    );
+    let value = Owned::deserialize(map).unwrap();
+    assert_eq!(&*value.0.signature(), "a{sv}");
+
+    assert!(Owned::deserialize(UnitDeserializer::<Error>::new()).is_err());
+  }
+
+  #[test]
+  fn option_variant_unit_is_none() {
+    assert!(
+      option_variant::deserialize(UnitDeserializer::<Error>::new())
+        .unwrap()
+        .is_none()
+    );
+  }
+}
@@ -0,0 +1,101 @@
+// Copyright 2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::{PortalError, PortalEvent, Results, SignalGuard, DESTINATION};
+
+use dbus::{
+  arg::PropMap,
+  blocking::{BlockingSender, Connection, Proxy},
+  message::MatchRule,
+  strings::BusName,
+  Path,
+};
+use std::time::Duration;
+
+const INTERFACE: &str = "org.freedesktop.portal.Session";
+
+/// A `org.freedesktop.portal.Session` object, as created by the session based portals.
+/// See also https://flatpak.github.io/xdg-desktop-portal/#gdbus-org.freedesktop.portal.Session
+///
+/// A session stays active until it is closed by the application with `Session::close()`,
+/// or by the portal, which emits the `Closed` signal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Session {
+  destination: BusName<'static>,
+  path: Path<'static>,
+}
+
+impl Session {
+  /// Creates a new `Session` for the object at `path`, owned by the portal at `DESTINATION`.
+  pub fn new(path: Path<'static>) -> Self {
+    Self {
+      destination: DESTINATION.into(),
+      path,
+    }
+  }
+
+  /// Sets the bus name of the portal that owns this session,
+  /// for sessions created through a proxy created with `new_blocking_with()`.
+  pub fn with_destination(mut self, destination: impl Into<BusName<'static>>) -> Self {
+    self.destination = destination.into();
+    self
+  }
+
+  /// Reads the `session_handle` from the results of a successful `CreateSession` response.
+  pub fn from_results(results: &PropMap) -> Result<Self, PortalError> {
+    // Depending on the portal, the handle is sent as a string or as an object path.
+    let handle = Results::new(results).required("session_handle", Results::get_str)?;
+    Path::new(handle)
+      .map(Self::new)
+      .map_err(PortalError::UnexpectedResponse)
+  }
+
+  /// The bus name of the portal that owns this session.
+  pub fn destination(&self) -> &BusName<'static> {
+    &self.destination
+  }
+
+  /// The object path of this session.
+  pub fn path(&self) -> &Path<'static> {
+    &self.path
+  }
+
+  /// Closes the session.
+  pub fn close<S: BlockingSender>(
+    &self,
+    connection: &S,
+    timeout: Duration,
+  ) -> Result<(), PortalError> {
+    Proxy::new(&self.destination, &self.path, timeout, connection)
+      .method_call(INTERFACE, "Close", ())
+      .map_err(Into::into)
+  }
+
+  /// Subscribes to the `Closed` signal, emitted when the session was closed by the portal.
+  ///
+  /// The callback receives a map of details about the closed session.
+  pub fn on_closed<'c, F>(
+    &self,
+    connection: &'c Connection,
+    mut callback: F,
+  ) -> Result<SignalGuard<'c>, PortalError>
+  where
+    F: FnMut(PropMap) + Send + 'static,
+  {
+    let rule = MatchRule::new_signal(INTERFACE, "Closed")
+      .with_sender(self.destination.clone())
+      .with_path(self.path.clone());
+    SignalGuard::for_events(connection, rule, move |event| {
+      if let PortalEvent::SessionClosed { details, .. } = event {
+        callback(details);
+      }
+    })
+  }
+}
+
+impl From<Path<'static>> for Session {
+  fn from(path: Path<'static>) -> Self {
+    Self::new(path)
+  }
+}
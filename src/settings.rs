@@ -0,0 +1,314 @@
+// Copyright 2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::{signal_rule, PortalError, PortalEvent, SignalGuard};
+
+use dbus::{
+  arg::{PropMap, RefArg, Variant},
+  blocking::{self, stdintf::org_freedesktop_dbus, Connection},
+};
+use std::collections::HashMap;
+
+const INTERFACE: &str = "org.freedesktop.portal.Settings";
+
+const APPEARANCE_NAMESPACE: &str = "org.freedesktop.appearance";
+const COLOR_SCHEME_KEY: &str = "color-scheme";
+
+/// Implementation of the `org.freedesktop.portal.Settings` Portal API.
+/// See also https://flatpak.github.io/xdg-desktop-portal/#gdbus-org.freedesktop.portal.Settings
+pub trait Settings {
+  /// Reads all the settings in the given namespaces.
+  ///
+  /// - `namespaces`: List of namespaces to filter results by. Supports globbing at the end,
+  ///   e.g. `org.example.*`. An empty list returns all namespaces.
+  fn read_all(&self, namespaces: &[&str]) -> Result<HashMap<String, PropMap>, PortalError>;
+
+  /// Reads a single setting.
+  ///
+  /// - `namespace`: Namespace to look up `key` in.
+  /// - `key`: The key to get.
+  fn read(&self, namespace: &str, key: &str) -> Result<SettingValue, PortalError>;
+
+  /// Reads a single `b` setting, see `Settings::read()`.
+  /// Fails with `PortalError::TypeMismatch` if the value has another type.
+  fn read_bool(&self, namespace: &str, key: &str) -> Result<bool, PortalError>;
+
+  /// Reads a single `u` setting, see `Settings::read()`.
+  /// Fails with `PortalError::TypeMismatch` if the value has another type.
+  fn read_u32(&self, namespace: &str, key: &str) -> Result<u32, PortalError>;
+
+  /// Reads a single `s` setting, see `Settings::read()`.
+  /// Fails with `PortalError::TypeMismatch` if the value has another type.
+  fn read_string(&self, namespace: &str, key: &str) -> Result<String, PortalError>;
+
+  /// Reads the `color-scheme` key of the `org.freedesktop.appearance` namespace.
+  fn color_scheme(&self) -> Result<ColorScheme, PortalError>;
+
+  /// Subscribes to the `SettingChanged` signal, emitted when a setting changes.
+  ///
+  /// The callback receives the `namespace` and `key` of the changed setting, and its new `value`.
+  fn on_setting_changed<'c, F>(
+    &self,
+    connection: &'c Connection,
+    callback: F,
+  ) -> Result<SignalGuard<'c>, PortalError>
+  where
+    F: FnMut(String, String, Variant<Box<dyn RefArg>>) + Send + 'static;
+
+  /// Subscribes to changes of the `color-scheme` key of the `org.freedesktop.appearance` namespace.
+  ///
+  /// The callback receives the new color scheme. Values that aren't an integer are ignored.
+  fn on_color_scheme_changed<'c, F>(
+    &self,
+    connection: &'c Connection,
+    callback: F,
+  ) -> Result<SignalGuard<'c>, PortalError>
+  where
+    F: FnMut(ColorScheme) + Send + 'static;
+
+  /// Reads the "version" property for this D-Bus interface.
+  fn version(&self) -> Result<u32, PortalError>;
+}
+
+/// The system-wide color scheme preference, from the `org.freedesktop.appearance` namespace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ColorScheme {
+  /// No preference.
+  NoPreference,
+  /// Prefer dark appearance.
+  PreferDark,
+  /// Prefer light appearance.
+  PreferLight,
+}
+
+impl From<u32> for ColorScheme {
+  /// Unknown values are treated as `ColorScheme::NoPreference`, as the spec requires.
+  fn from(value: u32) -> Self {
+    match value {
+      1 => Self::PreferDark,
+      2 => Self::PreferLight,
+      _ => Self::NoPreference,
+    }
+  }
+}
+
+/// The value of a setting, by its D-Bus type.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SettingValue {
+  /// A `u` value.
+  U32(u32),
+  /// An `i` value.
+  I32(i32),
+  /// A `b` value.
+  Bool(bool),
+  /// An `s` value.
+  String(String),
+  /// A value of any other type.
+  #[cfg_attr(feature = "serde", serde(with = "crate::serde_support::variant"))]
+  Other(Variant<Box<dyn RefArg>>),
+}
+
+// Written out as `Box<dyn RefArg>` has no `Clone` impl, `SettingValue::Other` is cloned with `RefArg::box_clone()`.
+impl Clone for SettingValue {
+  fn clone(&self) -> Self {
+    match self {
+      Self::U32(value) => Self::U32(*value),
+      Self::I32(value) => Self::I32(*value),
+      Self::Bool(value) => Self::Bool(*value),
+      Self::String(value) => Self::String(value.clone()),
+      Self::Other(value) => Self::Other(Variant(value.0.box_clone())),
+    }
+  }
+}
+
+impl SettingValue {
+  /// The value, if it's a `u` value.
+  pub fn as_u32(&self) -> Option<u32> {
+    match self {
+      Self::U32(value) => Some(*value),
+      _ => None,
+    }
+  }
+
+  /// The value, if it's a `b` value.
+  pub fn as_bool(&self) -> Option<bool> {
+    match self {
+      Self::Bool(value) => Some(*value),
+      _ => None,
+    }
+  }
+
+  /// The value, if it's an `s` value.
+  pub fn as_str(&self) -> Option<&str> {
+    match self {
+      Self::String(value) => Some(value),
+      _ => None,
+    }
+  }
+
+  /// The `TypeMismatch` error for reading `key` as another type than this value has.
+  fn mismatch(&self, key: &str, expected: &'static str) -> PortalError {
+    PortalError::TypeMismatch {
+      key: key.to_string(),
+      expected,
+      found: self.signature(),
+    }
+  }
+
+  fn signature(&self) -> String {
+    match self {
+      Self::U32(_) => "u".to_string(),
+      Self::I32(_) => "i".to_string(),
+      Self::Bool(_) => "b".to_string(),
+      Self::String(_) => "s".to_string(),
+      Self::Other(value) => value.0.signature().to_string(),
+    }
+  }
+}
+
+impl From<Variant<Box<dyn RefArg>>> for SettingValue {
+  fn from(mut value: Variant<Box<dyn RefArg>>) -> Self {
+    // Some portal versions wrap the value of `Read` in another variant.
+    while &*value.0.signature() == "v" {
+      match value.0.as_iter().and_then(|mut inner| inner.next()) {
+        Some(inner) => value = Variant(inner.box_clone()),
+        None => break,
+      }
+    }
+    let converted = match &*value.0.signature() {
+      "u" => value.0.as_u64().map(|v| Self::U32(v as u32)),
+      "i" => value.0.as_i64().map(|v| Self::I32(v as i32)),
+      "b" => value.0.as_u64().map(|v| Self::Bool(v != 0)),
+      "s" => value.0.as_str().map(|v| Self::String(v.to_string())),
+      _ => None,
+    };
+    converted.unwrap_or(Self::Other(value))
+  }
+}
+
+impl<'a, T: blocking::BlockingSender, C: std::ops::Deref<Target = T>> Settings
+  for blocking::Proxy<'a, C>
+{
+  fn read_all(&self, namespaces: &[&str]) -> Result<HashMap<String, PropMap>, PortalError> {
+    self
+      .method_call(INTERFACE, "ReadAll", (namespaces,))
+      .map(|r: (HashMap<String, PropMap>,)| r.0)
+      .map_err(Into::into)
+  }
+
+  fn read(&self, namespace: &str, key: &str) -> Result<SettingValue, PortalError> {
+    self
+      .method_call(INTERFACE, "Read", (namespace, key))
+      .map(|r: (Variant<Box<dyn RefArg>>,)| r.0.into())
+      .map_err(Into::into)
+  }
+
+  fn read_bool(&self, namespace: &str, key: &str) -> Result<bool, PortalError> {
+    let value = self.read(namespace, key)?;
+    value.as_bool().ok_or_else(|| value.mismatch(key, "b"))
+  }
+
+  fn read_u32(&self, namespace: &str, key: &str) -> Result<u32, PortalError> {
+    let value = self.read(namespace, key)?;
+    value.as_u32().ok_or_else(|| value.mismatch(key, "u"))
+  }
+
+  fn read_string(&self, namespace: &str, key: &str) -> Result<String, PortalError> {
+    match self.read(namespace, key)? {
+      SettingValue::String(value) => Ok(value),
+      value => Err(value.mismatch(key, "s")),
+    }
+  }
+
+  fn color_scheme(&self) -> Result<ColorScheme, PortalError> {
+    self
+      .read_u32(APPEARANCE_NAMESPACE, COLOR_SCHEME_KEY)
+      .map(ColorScheme::from)
+  }
+
+  fn on_setting_changed<'c, F>(
+    &self,
+    connection: &'c Connection,
+    mut callback: F,
+  ) -> Result<SignalGuard<'c>, PortalError>
+  where
+    F: FnMut(String, String, Variant<Box<dyn RefArg>>) + Send + 'static,
+  {
+    SignalGuard::for_events(
+      connection,
+      signal_rule(self, INTERFACE, "SettingChanged"),
+      move |event| {
+        if let PortalEvent::SettingChanged {
+          namespace,
+          key,
+          value,
+        } = event
+        {
+          callback(namespace, key, value);
+        }
+      },
+    )
+  }
+
+  fn on_color_scheme_changed<'c, F>(
+    &self,
+    connection: &'c Connection,
+    mut callback: F,
+  ) -> Result<SignalGuard<'c>, PortalError>
+  where
+    F: FnMut(ColorScheme) + Send + 'static,
+  {
+    self.on_setting_changed(connection, move |namespace, key, value| {
+      if namespace != APPEARANCE_NAMESPACE || key != COLOR_SCHEME_KEY {
+        return;
+      }
+      if let Some(value) = value.as_u64() {
+        callback(ColorScheme::from(value as u32));
+      }
+    })
+  }
+
+  fn version(&self) -> Result<u32, PortalError> {
+    <Self as org_freedesktop_dbus::Properties>::get(self, INTERFACE, "version").map_err(Into::into)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::SettingValue;
+  use crate::PortalError;
+  use dbus::arg::{RefArg, Variant};
+
+  fn setting(value: Box<dyn RefArg>) -> SettingValue {
+    SettingValue::from(Variant(value))
+  }
+
+  #[test]
+  fn setting_value_by_signature() {
+    assert!(matches!(setting(Box::new(1u32)), SettingValue::U32(1)));
+    assert!(matches!(setting(Box::new(-1i32)), SettingValue::I32(-1)));
+    assert!(matches!(setting(Box::new(true)), SettingValue::Bool(true)));
+    assert!(
+      matches!(setting(Box::new("dark".to_string())), SettingValue::String(s) if s == "dark")
+    );
+    assert!(matches!(setting(Box::new(1.5f64)), SettingValue::Other(_)));
+
+    let nested = setting(Box::new(Variant(Box::new(2u32) as Box<dyn RefArg>)));
+    assert_eq!(nested.as_u32(), Some(2));
+    assert_eq!(setting(Box::new(true)).as_u32(), None);
+    assert_eq!(setting(Box::new(true)).as_bool(), Some(true));
+    assert_eq!(setting(Box::new("dark".to_string())).as_str(), Some("dark"));
+    assert_eq!(setting(Box::new(1u32)).as_str(), None);
+  }
+
+  #[test]
+  fn setting_value_mismatch() {
+    assert!(matches!(
+      setting(Box::new(1u32)).mismatch("key", "b"),
+      PortalError::TypeMismatch { key, expected: "b", found } if key == "key" && found == "u"
+    ));
+  }
+}
@@ -0,0 +1,423 @@
+// Copyright 2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+#[cfg(feature = "spec-v3")]
+use crate::SessionState;
+use crate::{CaptureActivation, Location, PortalError, Session, DESTINATION};
+
+use dbus::{
+  arg::{PropMap, ReadAll, RefArg, Variant},
+  blocking::{Connection, Proxy},
+  channel::Token,
+  message::{MatchRule, Message, MessageType},
+  strings::BusName,
+  Path,
+};
+use std::{
+  collections::VecDeque,
+  sync::{Arc, Mutex},
+  time::{Duration, Instant},
+};
+
+/// A portal signal, decoded by `PortalEvent::from_message()`.
+#[derive(Debug)]
+pub enum PortalEvent {
+  /// `org.freedesktop.portal.Settings::SettingChanged`, see `Settings::on_setting_changed()`.
+  SettingChanged {
+    /// The namespace of the changed setting.
+    namespace: String,
+    /// The key of the changed setting.
+    key: String,
+    /// The new value, which can be read with `SettingValue::from()`.
+    value: Variant<Box<dyn RefArg>>,
+  },
+  /// `org.freedesktop.portal.Location::LocationUpdated`, see `LocationPortal::on_location_updated()`.
+  LocationUpdated {
+    /// The session the location was updated for.
+    session: Session,
+    /// The new location.
+    location: Location,
+  },
+  /// `org.freedesktop.portal.GlobalShortcuts::Activated`, see `GlobalShortcuts::on_activated()`.
+  ShortcutActivated {
+    /// The session the shortcut is bound in.
+    session: Session,
+    /// The identifier of the shortcut.
+    shortcut_id: String,
+    /// The timestamp of the activation, in milliseconds.
+    timestamp: u64,
+    /// Additional details.
+    options: PropMap,
+  },
+  /// `org.freedesktop.portal.GlobalShortcuts::Deactivated`, see `GlobalShortcuts::on_deactivated()`.
+  ShortcutDeactivated {
+    /// The session the shortcut is bound in.
+    session: Session,
+    /// The identifier of the shortcut.
+    shortcut_id: String,
+    /// The timestamp of the deactivation, in milliseconds.
+    timestamp: u64,
+    /// Additional details.
+    options: PropMap,
+  },
+  /// `org.freedesktop.portal.Notification::ActionInvoked`, see `NotificationPortal::on_action_invoked()`.
+  ActionInvoked {
+    /// The identifier of the notification.
+    id: String,
+    /// The name of the action that was invoked.
+    action: String,
+    /// The parameters of the action.
+    parameters: Vec<Variant<Box<dyn RefArg>>>,
+  },
+  /// `org.freedesktop.portal.NetworkMonitor::changed`, see `NetworkMonitor::on_changed()`.
+  NetworkChanged,
+  /// `org.freedesktop.portal.MemoryMonitor::LowMemoryWarning`, see `MemoryMonitor::on_low_memory_warning()`.
+  LowMemoryWarning {
+    /// The level of the warning.
+    level: u8,
+  },
+  /// `org.freedesktop.portal.Inhibit::StateChanged`, see `Inhibit::on_state_changed()`.
+  #[cfg(feature = "spec-v3")]
+  InhibitStateChanged {
+    /// The monitoring session.
+    session: Session,
+    /// The new state.
+    state: SessionState,
+  },
+  /// `org.freedesktop.portal.InputCapture::Disabled`, see `InputCapture::on_disabled()`.
+  CaptureDisabled {
+    /// The input capture session.
+    session: Session,
+    /// Additional details.
+    options: PropMap,
+  },
+  /// `org.freedesktop.portal.InputCapture::Activated`, see `InputCapture::on_activated()`.
+  CaptureActivated {
+    /// The input capture session.
+    session: Session,
+    /// The details of the capture.
+    activation: CaptureActivation,
+  },
+  /// `org.freedesktop.portal.InputCapture::Deactivated`, see `InputCapture::on_deactivated()`.
+  CaptureDeactivated {
+    /// The input capture session.
+    session: Session,
+    /// The details of the capture.
+    activation: CaptureActivation,
+  },
+  /// `org.freedesktop.portal.InputCapture::ZonesChanged`, see `InputCapture::on_zones_changed()`.
+  ZonesChanged {
+    /// The input capture session.
+    session: Session,
+    /// The `zone_set` that is no longer valid.
+    zone_set: Option<u32>,
+  },
+  /// `org.freedesktop.portal.Session::Closed`, see `Session::on_closed()`.
+  SessionClosed {
+    /// The session that was closed.
+    session: Session,
+    /// Details about the closed session.
+    details: PropMap,
+  },
+}
+
+impl PortalEvent {
+  /// Decodes a portal signal. Returns `None` for other messages, and for signals with unexpected arguments.
+  pub fn from_message(message: &Message) -> Option<Self> {
+    if message.msg_type() != MessageType::Signal {
+      return None;
+    }
+    let interface = message.interface()?;
+    let member = message.member()?;
+    // Signals are sent from the unique name of the portal, which also owns the sessions they refer to.
+    let session = |path: Path<'static>| match message.sender() {
+      Some(sender) => Session::new(path).with_destination(sender.into_static()),
+      None => Session::new(path),
+    };
+    let path = || message.path().map(|path| session(path.into_static()));
+    let session_options = || {
+      message
+        .read2::<Path<'static>, PropMap>()
+        .ok()
+        .map(|(handle, options)| (session(handle), options))
+    };
+    let shortcut = || {
+      message
+        .read_all::<(Path<'static>, String, u64, PropMap)>()
+        .ok()
+    };
+    match (&*interface, &*member) {
+      ("org.freedesktop.portal.Settings", "SettingChanged") => {
+        let (namespace, key, value) = message.read3().ok()?;
+        Some(Self::SettingChanged {
+          namespace,
+          key,
+          value,
+        })
+      }
+      ("org.freedesktop.portal.Location", "LocationUpdated") => {
+        let (handle, location): (Path<'static>, PropMap) = message.read2().ok()?;
+        Some(Self::LocationUpdated {
+          session: session(handle),
+          location: Location::try_from(&location).ok()?,
+        })
+      }
+      ("org.freedesktop.portal.GlobalShortcuts", "Activated") => {
+        let (handle, shortcut_id, timestamp, options) = shortcut()?;
+        Some(Self::ShortcutActivated {
+          session: session(handle),
+          shortcut_id,
+          timestamp,
+          options,
+        })
+      }
+      ("org.freedesktop.portal.GlobalShortcuts", "Deactivated") => {
+        let (handle, shortcut_id, timestamp, options) = shortcut()?;
+        Some(Self::ShortcutDeactivated {
+          session: session(handle),
+          shortcut_id,
+          timestamp,
+          options,
+        })
+      }
+      ("org.freedesktop.portal.Notification", "ActionInvoked") => {
+        let (id, action, parameters) = message.read3().ok()?;
+        Some(Self::ActionInvoked {
+          id,
+          action,
+          parameters,
+        })
+      }
+      ("org.freedesktop.portal.NetworkMonitor", "changed") => Some(Self::NetworkChanged),
+      ("org.freedesktop.portal.MemoryMonitor", "LowMemoryWarning") => {
+        Some(Self::LowMemoryWarning {
+          level: message.read1().ok()?,
+        })
+      }
+      #[cfg(feature = "spec-v3")]
+      ("org.freedesktop.portal.Inhibit", "StateChanged") => {
+        let (handle, state): (Path<'static>, PropMap) = message.read2().ok()?;
+        Some(Self::InhibitStateChanged {
+          session: session(handle),
+          state: SessionState::try_from(&state).ok()?,
+        })
+      }
+      ("org.freedesktop.portal.InputCapture", "Disabled") => {
+        let (session, options) = session_options()?;
+        Some(Self::CaptureDisabled { session, options })
+      }
+      ("org.freedesktop.portal.InputCapture", "Activated") => {
+        let (session, options) = session_options()?;
+        Some(Self::CaptureActivated {
+          session,
+          activation: CaptureActivation::from(&options),
+        })
+      }
+      ("org.freedesktop.portal.InputCapture", "Deactivated") => {
+        let (session, options) = session_options()?;
+        Some(Self::CaptureDeactivated {
+          session,
+          activation: CaptureActivation::from(&options),
+        })
+      }
+      ("org.freedesktop.portal.InputCapture", "ZonesChanged") => {
+        let (session, options) = session_options()?;
+        Some(Self::ZonesChanged {
+          session,
+          zone_set: options
+            .get("zone_set")
+            .and_then(|zone_set| zone_set.as_u64())
+            .map(|zone_set| zone_set as u32),
+        })
+      }
+      ("org.freedesktop.portal.Session", "Closed") => Some(Self::SessionClosed {
+        session: path()?,
+        details: message.read1().ok()?,
+      }),
+      _ => None,
+    }
+  }
+}
+
+/// A subscription to a portal signal. The signal match is removed when this is dropped.
+///
+/// Received signals are only dispatched to the callback while the connection is processing
+/// incoming messages, see `dbus::blocking::Connection::process()`.
+#[must_use = "the signal subscription is removed when the guard is dropped"]
+pub struct SignalGuard<'a> {
+  connection: &'a Connection,
+  token: Option<Token>,
+}
+
+impl<'a> SignalGuard<'a> {
+  /// Adds `rule` to `connection`, invoking `callback` with the decoded arguments of each matching signal.
+  pub(crate) fn new<S, F>(
+    connection: &'a Connection,
+    rule: MatchRule<'static>,
+    mut callback: F,
+  ) -> Result<Self, PortalError>
+  where
+    S: ReadAll,
+    F: FnMut(S, &Message) + Send + 'static,
+  {
+    let token = connection.add_match(rule, move |args: S, _: &Connection, message: &Message| {
+      callback(args, message);
+      true
+    })?;
+    Ok(Self {
+      connection,
+      token: Some(token),
+    })
+  }
+}
+
+impl<'a> SignalGuard<'a> {
+  /// Adds `rule` to `connection`, invoking `callback` with each matching signal decoded as a `PortalEvent`.
+  pub(crate) fn for_events<F>(
+    connection: &'a Connection,
+    rule: MatchRule<'static>,
+    mut callback: F,
+  ) -> Result<Self, PortalError>
+  where
+    F: FnMut(PortalEvent) + Send + 'static,
+  {
+    Self::new(connection, rule, move |(): (), message: &Message| {
+      if let Some(event) = PortalEvent::from_message(message) {
+        callback(event);
+      }
+    })
+  }
+}
+
+impl<'a> Drop for SignalGuard<'a> {
+  fn drop(&mut self) {
+    if let Some(token) = self.token.take() {
+      let _ = self.connection.remove_match(token);
+    }
+  }
+}
+
+/// Creates a rule matching the `member` signal of `interface`, emitted by the object `proxy` points to.
+pub(crate) fn signal_rule<C>(
+  proxy: &Proxy<'_, C>,
+  interface: &'static str,
+  member: &'static str,
+) -> MatchRule<'static> {
+  MatchRule::new_signal(interface, member)
+    .with_sender(proxy.destination.clone().into_static())
+    .with_path(proxy.path.clone().into_static())
+}
+
+/// The signals of all portals on a connection, as an `Iterator` of `PortalEvent`s.
+///
+/// Iterating blocks while processing incoming messages on the connection, until the next event arrives.
+/// Iteration ends if processing fails, use `SignalStream::next_timeout()` to handle the error or stop waiting.
+///
+/// ```no_run
+/// use dbus::blocking::Connection;
+/// use xdg_desktop_portal::{PortalEvent, SignalStream};
+///
+/// let connection = Connection::new_session()?;
+/// for event in SignalStream::new(&connection)? {
+///   if let PortalEvent::SettingChanged { namespace, key, .. } = event {
+///     println!("{}.{} changed", namespace, key);
+///   }
+/// }
+/// # Ok::<(), xdg_desktop_portal::PortalError>(())
+/// ```
+pub struct SignalStream<'a> {
+  connection: &'a Connection,
+  events: Arc<Mutex<VecDeque<PortalEvent>>>,
+  _guard: SignalGuard<'a>,
+}
+
+impl<'a> SignalStream<'a> {
+  /// Subscribes to all signals sent by the `org.freedesktop.portal.Desktop` bus.
+  pub fn new(connection: &'a Connection) -> Result<Self, PortalError> {
+    Self::with_destination(connection, DESTINATION)
+  }
+
+  /// Subscribes to all signals sent by the portal at `destination`,
+  /// such as the destination of a proxy created with `new_blocking_with()`.
+  pub fn with_destination(
+    connection: &'a Connection,
+    destination: impl Into<BusName<'static>>,
+  ) -> Result<Self, PortalError> {
+    let events = Arc::new(Mutex::new(VecDeque::new()));
+    let received = events.clone();
+    let rule = MatchRule::new()
+      .with_type(MessageType::Signal)
+      .with_sender(destination.into());
+    let guard = SignalGuard::for_events(connection, rule, move |event| {
+      received.lock().unwrap().push_back(event);
+    })?;
+    Ok(Self {
+      connection,
+      events,
+      _guard: guard,
+    })
+  }
+
+  /// Processes incoming messages until the next event arrives, or `timeout` has passed.
+  pub fn next_timeout(&mut self, timeout: Duration) -> Result<Option<PortalEvent>, PortalError> {
+    let deadline = Instant::now() + timeout;
+    loop {
+      if let Some(event) = self.events.lock().unwrap().pop_front() {
+        return Ok(Some(event));
+      }
+      let now = Instant::now();
+      if now >= deadline {
+        return Ok(None);
+      }
+      self.connection.process(deadline - now)?;
+    }
+  }
+}
+
+impl<'a> Iterator for SignalStream<'a> {
+  type Item = PortalEvent;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    loop {
+      match self.next_timeout(Duration::from_secs(60)) {
+        Ok(Some(event)) => return Some(event),
+        Ok(None) => {}
+        Err(_) => return None,
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::PortalEvent;
+  use dbus::{arg::Variant, Message};
+
+  #[test]
+  fn portal_event_from_message() {
+    let message = Message::new_signal(
+      "/org/freedesktop/portal/desktop",
+      "org.freedesktop.portal.Settings",
+      "SettingChanged",
+    )
+    .unwrap()
+    .append3("org.freedesktop.appearance", "color-scheme", Variant(1u32));
+    match PortalEvent::from_message(&message) {
+      Some(PortalEvent::SettingChanged { namespace, key, .. }) => {
+        assert_eq!(namespace, "org.freedesktop.appearance");
+        assert_eq!(key, "color-scheme");
+      }
+      event => panic!("unexpected event {:?}", event),
+    }
+
+    let message = Message::new_signal(
+      "/org/freedesktop/portal/desktop",
+      "org.freedesktop.portal.MemoryMonitor",
+      "LowMemoryWarning",
+    )
+    .unwrap()
+    .append1("not a level");
+    assert!(PortalEvent::from_message(&message).is_none());
+  }
+}
@@ -0,0 +1,396 @@
+// Copyright 2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Helpers to test code that calls portals, without a desktop environment.
+
+use crate::{request_path, PortalError, DESTINATION, PATH};
+
+use dbus::{
+  arg::{PropMap, RefArg, Variant},
+  blocking::{BlockingSender, Connection},
+  channel::{Channel, MatchingReceiver, Sender},
+  message::MatchRule,
+  Message,
+};
+use std::{
+  collections::HashMap,
+  io::{BufRead, BufReader},
+  process::{Child, Command, Stdio},
+  sync::{
+    atomic::{AtomicBool, AtomicU32, Ordering},
+    mpsc, Arc, Mutex,
+  },
+  thread::{self, JoinHandle},
+  time::Duration,
+};
+
+const OPEN_URI_INTERFACE: &str = "org.freedesktop.portal.OpenURI";
+
+/// The introspection data of the portal object, listing the interfaces the `MockPortal` implements.
+const INTROSPECTION: &str = r#"<node>
+  <interface name="org.freedesktop.DBus.Introspectable">
+    <method name="Introspect"><arg name="xml_data" type="s" direction="out"/></method>
+  </interface>
+  <interface name="org.freedesktop.portal.OpenURI">
+    <property name="version" type="u" access="read"/>
+  </interface>
+</node>"#;
+
+/// A private D-Bus daemon, so tests neither need nor disturb the session bus.
+///
+/// Requires the `dbus-daemon` executable. The daemon is stopped when this is dropped.
+pub struct TestBus {
+  daemon: Child,
+  address: String,
+}
+
+impl TestBus {
+  /// Starts a new `dbus-daemon` with the session bus configuration.
+  pub fn new() -> Result<Self, PortalError> {
+    let mut daemon = Command::new("dbus-daemon")
+      .args(["--session", "--nofork", "--print-address"])
+      .stdout(Stdio::piped())
+      .spawn()?;
+    let mut address = String::new();
+    if let Some(stdout) = daemon.stdout.take() {
+      BufReader::new(stdout).read_line(&mut address)?;
+    }
+    let address = address.trim().to_string();
+    if address.is_empty() {
+      let _ = daemon.kill();
+      return Err(PortalError::UnexpectedResponse(
+        "dbus-daemon did not print its address".to_string(),
+      ));
+    }
+    Ok(Self { daemon, address })
+  }
+
+  /// The address clients connect to.
+  pub fn address(&self) -> &str {
+    &self.address
+  }
+
+  /// Opens a new connection to the bus.
+  pub fn connect(&self) -> Result<Connection, PortalError> {
+    let mut channel = Channel::open_private(&self.address)?;
+    channel.register()?;
+    Ok(channel.into())
+  }
+}
+
+impl Drop for TestBus {
+  fn drop(&mut self) {
+    let _ = self.daemon.kill();
+    let _ = self.daemon.wait();
+  }
+}
+
+/// A method call received by a `MockPortal` or `MockSender`.
+pub struct MockCall {
+  /// The interface the method was called on.
+  pub interface: String,
+  /// The name of the method.
+  pub member: String,
+  /// The call itself, for reading its arguments.
+  pub message: Message,
+}
+
+/// A canned reply of a `MockSender`.
+enum MockReply {
+  Return(Box<dyn Fn(Message) -> Message + Send>),
+  Error(String, String),
+}
+
+/// A `BlockingSender` that answers method calls with canned replies, without any bus.
+///
+/// Create a proxy on it with `new_blocking(timeout, &sender)`, to call the portal methods and check the
+/// messages they send. Replies are keyed by the interface and method name, calls without a reply fail with
+/// `org.freedesktop.DBus.Error.UnknownMethod`, like they would on a portal missing the method.
+///
+/// The `_and_wait` methods need a `Connection` to receive the `Response` signal, use a `MockPortal` for those.
+#[derive(Default)]
+pub struct MockSender {
+  replies: Mutex<HashMap<(String, String), MockReply>>,
+  versions: Mutex<HashMap<String, u32>>,
+  calls: Mutex<Vec<MockCall>>,
+  serial: AtomicU32,
+}
+
+impl MockSender {
+  /// Creates a new `MockSender` without any replies.
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  /// Answers calls of `interface.method` with the method return `build` appends the output arguments to:
+  /// `sender.reply(INTERFACE, "OpenURI", |reply| reply.append1(path.clone()))`.
+  pub fn reply(
+    &self,
+    interface: &str,
+    method: &str,
+    build: impl Fn(Message) -> Message + Send + 'static,
+  ) {
+    self.replies.lock().unwrap().insert(
+      (interface.to_string(), method.to_string()),
+      MockReply::Return(Box::new(build)),
+    );
+  }
+
+  /// Answers calls of `interface.method` with the D-Bus error `name`, such as
+  /// `org.freedesktop.portal.Error.NotAllowed`.
+  pub fn error(&self, interface: &str, method: &str, name: &str, message: &str) {
+    self.replies.lock().unwrap().insert(
+      (interface.to_string(), method.to_string()),
+      MockReply::Error(name.to_string(), message.to_string()),
+    );
+  }
+
+  /// Makes the "version" property of `interface` read as `version`.
+  pub fn version(&self, interface: &str, version: u32) {
+    self
+      .versions
+      .lock()
+      .unwrap()
+      .insert(interface.to_string(), version);
+  }
+
+  /// Takes the calls sent so far, in order.
+  pub fn take_calls(&self) -> Vec<MockCall> {
+    std::mem::take(&mut *self.calls.lock().unwrap())
+  }
+
+  fn answer(&self, interface: &str, member: &str, call: &Message) -> Result<Message, dbus::Error> {
+    if interface == "org.freedesktop.DBus.Properties" && member == "Get" {
+      if let Ok((property_interface, "version")) = call.read2::<&str, &str>() {
+        if let Some(version) = self.versions.lock().unwrap().get(property_interface) {
+          return Ok(call.method_return().append1(Variant(*version)));
+        }
+      }
+    }
+    match self
+      .replies
+      .lock()
+      .unwrap()
+      .get(&(interface.to_string(), member.to_string()))
+    {
+      Some(MockReply::Return(build)) => Ok(build(call.method_return())),
+      Some(MockReply::Error(name, message)) => Err(dbus::Error::new_custom(name, message)),
+      None => Err(dbus::Error::new_custom(
+        "org.freedesktop.DBus.Error.UnknownMethod",
+        &format!("No reply for {}.{}", interface, member),
+      )),
+    }
+  }
+}
+
+impl BlockingSender for MockSender {
+  fn send_with_reply_and_block(
+    &self,
+    mut msg: Message,
+    _timeout: Duration,
+  ) -> Result<Message, dbus::Error> {
+    // A method return refers to the serial of its call, which is normally assigned when sending.
+    msg.set_serial(self.serial.fetch_add(1, Ordering::Relaxed) + 1);
+    let interface = msg.interface().map(|i| i.to_string()).unwrap_or_default();
+    let member = msg.member().map(|m| m.to_string()).unwrap_or_default();
+    let reply = self.answer(&interface, &member, &msg);
+    self.calls.lock().unwrap().push(MockCall {
+      interface,
+      member,
+      message: msg,
+    });
+    reply
+  }
+}
+
+/// An in-process service owning the portal bus name on a `TestBus`, which records the calls it receives.
+///
+/// It implements `org.freedesktop.portal.OpenURI`: every call is answered with the request path derived
+/// from the `handle_token` option, followed by a successful `Response` signal with empty results.
+/// `SchemeSupported` is only implemented from version 5, and supports the `https` scheme.
+/// The "version" property reads as the version passed to `MockPortal::start()`, and introspection lists
+/// the OpenURI interface.
+///
+/// The service runs on its own thread, which is stopped when this is dropped.
+pub struct MockPortal {
+  calls: Arc<Mutex<Vec<MockCall>>>,
+  stop: Arc<AtomicBool>,
+  thread: Option<JoinHandle<()>>,
+}
+
+impl MockPortal {
+  /// Starts the service on `bus`, once it owns the portal bus name.
+  pub fn start(bus: &TestBus, version: u32) -> Result<Self, PortalError> {
+    Self::start_as(bus, DESTINATION, version)
+  }
+
+  /// Starts the service on `bus`, once it owns `name` instead of the portal bus name,
+  /// like a portal reached through `new_blocking_with()`.
+  pub fn start_as(bus: &TestBus, name: &'static str, version: u32) -> Result<Self, PortalError> {
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let stop = Arc::new(AtomicBool::new(false));
+    let (ready_sender, ready) = mpsc::channel();
+    let address = bus.address().to_string();
+    let thread = {
+      let calls = calls.clone();
+      let stop = stop.clone();
+      thread::spawn(move || {
+        let connection = match serve(&address, name, calls, version) {
+          Ok(connection) => {
+            let _ = ready_sender.send(Ok(()));
+            connection
+          }
+          Err(err) => {
+            let _ = ready_sender.send(Err(err));
+            return;
+          }
+        };
+        while !stop.load(Ordering::Relaxed) {
+          if connection.process(Duration::from_millis(20)).is_err() {
+            break;
+          }
+        }
+      })
+    };
+    let started = ready.recv().unwrap_or_else(|_| {
+      Err(PortalError::UnexpectedResponse(
+        "mock portal thread exited".to_string(),
+      ))
+    });
+    let portal = Self {
+      calls,
+      stop,
+      thread: Some(thread),
+    };
+    started.map(|_| portal)
+  }
+
+  /// Takes the calls received so far, in order.
+  pub fn take_calls(&self) -> Vec<MockCall> {
+    std::mem::take(&mut *self.calls.lock().unwrap())
+  }
+}
+
+impl Drop for MockPortal {
+  fn drop(&mut self) {
+    self.stop.store(true, Ordering::Relaxed);
+    if let Some(thread) = self.thread.take() {
+      let _ = thread.join();
+    }
+  }
+}
+
+fn serve(
+  address: &str,
+  name: &str,
+  calls: Arc<Mutex<Vec<MockCall>>>,
+  version: u32,
+) -> Result<Connection, PortalError> {
+  let mut channel = Channel::open_private(address)?;
+  channel.register()?;
+  let connection = Connection::from(channel);
+  connection.request_name(name, false, true, true)?;
+  connection.start_receive(
+    MatchRule::new_method_call().with_path(PATH),
+    Box::new(move |message, connection| {
+      let reply = match (message.interface(), message.member()) {
+        (Some(interface), Some(member)) if &*interface == OPEN_URI_INTERFACE => {
+          // Holding the lock while answering ensures the call is recorded before the client sees the reply.
+          let mut calls = calls.lock().unwrap();
+          if &*member == "SchemeSupported" {
+            answer_scheme_supported(&message, connection, version);
+          } else {
+            answer_request(&message, connection);
+          }
+          calls.push(MockCall {
+            interface: interface.to_string(),
+            member: member.to_string(),
+            message,
+          });
+          return true;
+        }
+        (Some(interface), Some(member))
+          if &*interface == "org.freedesktop.DBus.Introspectable" && &*member == "Introspect" =>
+        {
+          message.method_return().append1(INTROSPECTION)
+        }
+        (Some(interface), Some(member))
+          if &*interface == "org.freedesktop.DBus.Properties" && &*member == "Get" =>
+        {
+          match message.read2::<&str, &str>() {
+            Ok((OPEN_URI_INTERFACE, "version")) => {
+              message.method_return().append1(Variant(version))
+            }
+            _ => message.error(
+              &"org.freedesktop.DBus.Error.UnknownProperty".into(),
+              &std::ffi::CString::new("Unknown property").unwrap(),
+            ),
+          }
+        }
+        _ => message.error(
+          &"org.freedesktop.DBus.Error.UnknownMethod".into(),
+          &std::ffi::CString::new("Unknown method").unwrap(),
+        ),
+      };
+      let _ = connection.send(reply);
+      true
+    }),
+  );
+  Ok(connection)
+}
+
+/// Replies to a portal method call with its request path, and emits the `Response` of that request.
+fn answer_request(message: &Message, connection: &Connection) {
+  let handle_token = options_of(message)
+    .and_then(|options| {
+      options
+        .get("handle_token")
+        .and_then(|token| token.as_str().map(ToString::to_string))
+    })
+    .unwrap_or_else(|| "mock".to_string());
+  let sender = message
+    .sender()
+    .map(|sender| sender.to_string())
+    .unwrap_or_default();
+  if let Ok(path) = request_path(&sender, &handle_token) {
+    let _ = connection.send(message.method_return().append1(path.clone()));
+    let response = Message::signal(
+      &path,
+      &"org.freedesktop.portal.Request".into(),
+      &"Response".into(),
+    );
+    let _ = connection.send(response.append2(0u32, PropMap::new()));
+  } else {
+    let _ = connection.send(message.error(
+      &"org.freedesktop.DBus.Error.InvalidArgs".into(),
+      &std::ffi::CString::new("Invalid handle_token").unwrap(),
+    ));
+  }
+}
+
+/// Replies to a `SchemeSupported` call, or fails like a portal without the method before `version` 5.
+fn answer_scheme_supported(message: &Message, connection: &Connection, version: u32) {
+  let reply = if version >= 5 {
+    let scheme = message.read1::<&str>().unwrap_or_default();
+    message.method_return().append1(scheme == "https")
+  } else {
+    message.error(
+      &"org.freedesktop.DBus.Error.UnknownMethod".into(),
+      &std::ffi::CString::new("Unknown method").unwrap(),
+    )
+  };
+  let _ = connection.send(reply);
+}
+
+/// The options of a portal method call, which are its last argument.
+fn options_of(message: &Message) -> Option<PropMap> {
+  let mut iter = message.iter_init();
+  let mut options = None;
+  loop {
+    options = iter.get::<PropMap>().or(options);
+    if !iter.next() {
+      return options;
+    }
+  }
+}
@@ -0,0 +1,162 @@
+// Copyright 2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Optional `tracing` instrumentation of portal requests, enabled with the `tracing` feature.
+//!
+//! Every method call that makes the portal create a `Request` is covered by a `portal_call` span, in the blocking
+//! traits as well as `AsyncOpenURI`. The span of an `_and_wait` method also covers the wait for the `Response`.
+//!
+//! Without the feature `CallSpan` and `AsyncCallSpan` are zero-sized and every function here is empty,
+//! so the instrumentation compiles away entirely.
+
+#[cfg(feature = "dbus")]
+use dbus::{arg::PropMap, Path};
+#[cfg(feature = "async")]
+use std::future::Future;
+
+/// A `portal_call` span, covering a portal method call and, for the `_and_wait` methods, the wait for its `Response`.
+///
+/// The span is entered when created and exited when dropped, so it must be dropped on the thread that created it.
+/// Events emitted while processing incoming messages, such as the `Response` arriving, are recorded within it.
+#[cfg(feature = "dbus")]
+pub(crate) struct CallSpan {
+  #[cfg(feature = "tracing")]
+  _entered: tracing::span::EnteredSpan,
+}
+
+#[cfg(feature = "dbus")]
+impl CallSpan {
+  /// Enters the span for a call of `interface.method`.
+  ///
+  /// Within another `portal_call` span, such as that of an `_and_wait` method making its request,
+  /// no span is entered, so the call is recorded in the outer one.
+  #[inline]
+  pub(crate) fn enter(
+    interface: &'static str,
+    method: &'static str,
+    parent_window: &str,
+    handle_token: Option<&str>,
+  ) -> Self {
+    #[cfg(feature = "tracing")]
+    {
+      Self {
+        _entered: call_span(interface, method, parent_window, handle_token).entered(),
+      }
+    }
+    #[cfg(not(feature = "tracing"))]
+    {
+      let _ = (interface, method, parent_window, handle_token);
+      Self {}
+    }
+  }
+}
+
+/// The `portal_call` span of an async call, entered each time the future it instruments is polled.
+#[cfg(feature = "async")]
+pub(crate) struct AsyncCallSpan {
+  #[cfg(feature = "tracing")]
+  span: tracing::Span,
+}
+
+#[cfg(feature = "async")]
+impl AsyncCallSpan {
+  /// Creates the span for a call of `interface.method`, see `CallSpan::enter()`.
+  #[inline]
+  pub(crate) fn new(
+    interface: &'static str,
+    method: &'static str,
+    parent_window: &str,
+    handle_token: Option<&str>,
+  ) -> Self {
+    #[cfg(feature = "tracing")]
+    {
+      Self {
+        span: call_span(interface, method, parent_window, handle_token),
+      }
+    }
+    #[cfg(not(feature = "tracing"))]
+    {
+      let _ = (interface, method, parent_window, handle_token);
+      Self {}
+    }
+  }
+
+  /// Runs `future` within the span.
+  #[inline]
+  pub(crate) fn instrument<F: Future>(self, future: F) -> impl Future<Output = F::Output> {
+    #[cfg(feature = "tracing")]
+    {
+      tracing::Instrument::instrument(future, self.span)
+    }
+    #[cfg(not(feature = "tracing"))]
+    {
+      future
+    }
+  }
+}
+
+#[cfg(all(feature = "tracing", feature = "dbus"))]
+fn call_span(
+  interface: &'static str,
+  method: &'static str,
+  parent_window: &str,
+  handle_token: Option<&str>,
+) -> tracing::Span {
+  let current = tracing::Span::current();
+  if current.metadata().map(|metadata| metadata.name()) == Some("portal_call") {
+    return tracing::Span::none();
+  }
+  tracing::debug_span!(
+    "portal_call",
+    interface,
+    method,
+    parent_window,
+    handle_token
+  )
+}
+
+/// The `handle_token` of a call's `options`, to record in its span.
+#[cfg(feature = "dbus")]
+#[inline]
+pub(crate) fn handle_token(options: &PropMap) -> Option<&str> {
+  #[cfg(feature = "tracing")]
+  {
+    options
+      .get("handle_token")
+      .and_then(|handle_token| handle_token.0.as_str())
+  }
+  #[cfg(not(feature = "tracing"))]
+  {
+    let _ = options;
+    None
+  }
+}
+
+/// Records the portal returning the object path of the `Request` it created, and passes it on.
+#[cfg(feature = "dbus")]
+#[inline]
+pub(crate) fn request_created(path: Path<'static>) -> Path<'static> {
+  #[cfg(feature = "tracing")]
+  tracing::debug!(request = %path, "portal request created");
+  path
+}
+
+/// Records the `Response` signal of the request at `path` arriving, with its response `code`.
+#[cfg(feature = "dbus")]
+#[inline]
+pub(crate) fn response_received(path: &Path<'_>, code: u32) {
+  #[cfg(feature = "tracing")]
+  tracing::debug!(request = %path, code, "portal response received");
+  #[cfg(not(feature = "tracing"))]
+  let _ = (path, code);
+}
+
+/// Records an option being unset, as the portal implements an older `version` of the interface than `required`.
+#[inline]
+pub(crate) fn option_unset(option: &'static str, required: u32, version: u32) {
+  #[cfg(feature = "tracing")]
+  tracing::warn!(option, required, version, "unsupported portal option unset");
+  #[cfg(not(feature = "tracing"))]
+  let _ = (option, required, version);
+}
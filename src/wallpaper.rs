@@ -0,0 +1,234 @@
+// Copyright 2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::{
+  insert_activation_token,
+  trace::{self, CallSpan},
+  ActivationToken, IntoPortalFd, ParentWindowArg, PortalError, VersionedOptions,
+};
+
+use dbus::{
+  arg::{PropMap, Variant},
+  blocking::{self, stdintf::org_freedesktop_dbus},
+  Path,
+};
+
+const INTERFACE: &str = "org.freedesktop.portal.Wallpaper";
+
+/// Implementation of the `org.freedesktop.portal.Wallpaper` Portal API.
+/// See also https://flatpak.github.io/xdg-desktop-portal/#gdbus-org.freedesktop.portal.Wallpaper
+pub trait Wallpaper {
+  /// Asks to set a given picture as the desktop background picture.
+  ///
+  /// - `parent_window`: Identifier for the application window, see crate comments for common conventions.
+  /// - `uri`: The picture file uri.
+  fn set_wallpaper_uri(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    uri: &str,
+    options: WallpaperOptions,
+  ) -> Result<Path<'static>, PortalError>;
+
+  /// Asks to set a given local file as the desktop background picture.
+  ///
+  /// - `parent_window`: Identifier for the application window, see crate comments for common conventions.
+  /// - `fd`: File descriptor for the file to open.
+  fn set_wallpaper_file(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    fd: impl IntoPortalFd,
+    options: WallpaperOptions,
+  ) -> Result<Path<'static>, PortalError>;
+
+  /// Reads the "version" property for this D-Bus interface.
+  fn version(&self) -> Result<u32, PortalError>;
+}
+
+/// Where to set the wallpaper.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SetOn {
+  /// The desktop background.
+  Background,
+  /// The lock screen.
+  Lockscreen,
+  /// Both the desktop background and the lock screen.
+  Both,
+}
+
+impl SetOn {
+  fn as_str(&self) -> &'static str {
+    match self {
+      Self::Background => "background",
+      Self::Lockscreen => "lockscreen",
+      Self::Both => "both",
+    }
+  }
+}
+
+/// Optional arguments for the Wallpaper methods.
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WallpaperOptions {
+  handle_token: Option<String>,
+  show_preview: Option<bool>,
+  set_on: Option<SetOn>,
+  activation_token: Option<ActivationToken>,
+}
+
+impl WallpaperOptions {
+  /// Creates a new `WallpaperOptions` struct with no arguments set.
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  /// A string that will be used as the last element of the @handle. Must be a valid
+  /// object path element. See the #org.freedesktop.portal.Request documentation for
+  /// more information about the @handle.
+  pub fn handle_token(mut self, handle_token: impl Into<String>) -> Self {
+    self.handle_token = Some(handle_token.into());
+    self
+  }
+
+  /// Whether to show a preview of the picture. Note that the portal may decide to show a preview
+  /// even if this option is not set.
+  pub fn show_preview(mut self, show_preview: bool) -> Self {
+    self.show_preview = Some(show_preview);
+    self
+  }
+
+  /// Where to set the wallpaper. Default is `SetOn::Both`.
+  pub fn set_on(mut self, set_on: SetOn) -> Self {
+    self.set_on = Some(set_on);
+    self
+  }
+
+  /// A token that can be used to activate the wallpaper dialog.
+  ///
+  /// The activation_token option was introduced in version 4 of the interface.
+  pub fn activation_token(mut self, activation_token: impl Into<ActivationToken>) -> Self {
+    self.activation_token = Some(activation_token.into());
+    self
+  }
+}
+
+impl VersionedOptions for WallpaperOptions {
+  fn versioned_options(&self) -> Vec<(&'static str, u32, bool)> {
+    vec![("activation_token", 4, self.activation_token.is_some())]
+  }
+
+  fn unset_option(&mut self, option: &str) {
+    match option {
+      "activation_token" => self.activation_token = None,
+      _ => unreachable!("`{}` isn't validated", option),
+    }
+  }
+}
+
+impl From<WallpaperOptions> for PropMap {
+  fn from(options: WallpaperOptions) -> Self {
+    let mut map = PropMap::new();
+    if let Some(handle_token) = options.handle_token {
+      map.insert("handle_token".to_string(), Variant(Box::new(handle_token)));
+    }
+    if let Some(show_preview) = options.show_preview {
+      map.insert("show-preview".to_string(), Variant(Box::new(show_preview)));
+    }
+    if let Some(set_on) = options.set_on {
+      map.insert(
+        "set-on".to_string(),
+        Variant(Box::new(set_on.as_str().to_string())),
+      );
+    }
+    insert_activation_token(&mut map, options.activation_token);
+    map
+  }
+}
+
+impl<'a, T: blocking::BlockingSender, C: std::ops::Deref<Target = T>> Wallpaper
+  for blocking::Proxy<'a, C>
+{
+  fn set_wallpaper_uri(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    uri: &str,
+    options: WallpaperOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    let parent_window: ParentWindowArg = parent_window.into();
+    let options = PropMap::from(options);
+    let _span = CallSpan::enter(
+      INTERFACE,
+      "SetWallpaperURI",
+      parent_window.as_str(),
+      trace::handle_token(&options),
+    );
+    self
+      .method_call(
+        INTERFACE,
+        "SetWallpaperURI",
+        (parent_window.as_str(), uri, options),
+      )
+      .map(|r: (Path<'static>,)| trace::request_created(r.0))
+      .map_err(Into::into)
+  }
+
+  fn set_wallpaper_file(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    fd: impl IntoPortalFd,
+    options: WallpaperOptions,
+  ) -> Result<Path<'static>, PortalError> {
+    let parent_window: ParentWindowArg = parent_window.into();
+    let options = PropMap::from(options);
+    let _span = CallSpan::enter(
+      INTERFACE,
+      "SetWallpaperFile",
+      parent_window.as_str(),
+      trace::handle_token(&options),
+    );
+    self
+      .method_call(
+        INTERFACE,
+        "SetWallpaperFile",
+        (parent_window.as_str(), fd.into_portal_fd(), options),
+      )
+      .map(|r: (Path<'static>,)| trace::request_created(r.0))
+      .map_err(Into::into)
+  }
+
+  fn version(&self) -> Result<u32, PortalError> {
+    <Self as org_freedesktop_dbus::Properties>::get(self, INTERFACE, "version").map_err(Into::into)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::{SetOn, WallpaperOptions};
+  use crate::{PortalError, VersionedOptions};
+  use dbus::arg::{prop_cast, PropMap};
+
+  #[test]
+  fn activation_token_requires_version_4() {
+    let options = || {
+      WallpaperOptions::new()
+        .set_on(SetOn::Lockscreen)
+        .activation_token("token")
+    };
+    assert!(matches!(
+      options().validate_against(3),
+      Err(PortalError::UnsupportedOption {
+        option: "activation_token",
+        required: 4,
+        version: 3,
+      })
+    ));
+    assert!(options().validate_against(4).is_ok());
+    let map = PropMap::from(options().supported_by(3));
+    assert_eq!(
+      prop_cast::<String>(&map, "set-on").map(String::as_str),
+      Some("lockscreen")
+    );
+    assert!(!map.contains_key("activation_token"));
+  }
+}
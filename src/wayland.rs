@@ -0,0 +1,102 @@
+// Copyright 2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::{ActivationToken, ParentWindow, PortalError};
+
+use std::{cell::RefCell, rc::Rc};
+use wayland_client::{
+  protocol::{wl_seat::WlSeat, wl_surface::WlSurface},
+  EventQueue, Main,
+};
+use wayland_protocols::{
+  staging::xdg_activation::v1::client::{
+    xdg_activation_token_v1, xdg_activation_v1::XdgActivationV1,
+  },
+  unstable::xdg_foreign::v2::client::{
+    zxdg_exported_v2::{self, ZxdgExportedV2},
+    zxdg_exporter_v2::ZxdgExporterV2,
+  },
+};
+
+/// Keeps a surface exported with the xdg_foreign protocol, the handle stops being valid when this is dropped.
+pub struct WaylandExport {
+  exported: Main<ZxdgExportedV2>,
+}
+
+impl Drop for WaylandExport {
+  fn drop(&mut self) {
+    self.exported.destroy();
+  }
+}
+
+impl ParentWindow {
+  /// Exports a toplevel `surface` with the xdg_foreign protocol, to use it as the parent window.
+  ///
+  /// - `exporter`: The bound `zxdg_exporter_v2` global.
+  /// - `event_queue`: The queue the exporter is assigned to, a roundtrip is made on it to receive the handle.
+  ///
+  /// The returned `WaylandExport` must be kept alive for as long as the handle is in use.
+  pub fn export_wayland(
+    exporter: &Main<ZxdgExporterV2>,
+    surface: &WlSurface,
+    event_queue: &mut EventQueue,
+  ) -> Result<(Self, WaylandExport), PortalError> {
+    let handle = Rc::new(RefCell::new(None));
+    let received = handle.clone();
+    let exported = exporter.export_toplevel(surface);
+    exported.quick_assign(move |_, event, _| {
+      if let zxdg_exported_v2::Event::Handle { handle } = event {
+        *received.borrow_mut() = Some(handle);
+      }
+    });
+    let export = WaylandExport { exported };
+    event_queue.sync_roundtrip(&mut (), |_, _, _| {})?;
+    let handle = handle.borrow_mut().take().ok_or_else(|| {
+      PortalError::UnexpectedResponse("no xdg_foreign handle for the exported surface".to_string())
+    })?;
+    Ok((Self::Wayland(handle), export))
+  }
+}
+
+impl ActivationToken {
+  /// Requests a token with the xdg_activation protocol, to pass the focus on to the application or dialog
+  /// a portal shows, such as with `OpenURIOptions::activation_token()`.
+  ///
+  /// - `activation`: The bound `xdg_activation_v1` global.
+  /// - `surface`: The surface of the parent window, which should have the focus.
+  /// - `serial`: The serial of the user input event that triggered the request, and the seat it was received on.
+  ///   Compositors may refuse to pass on the focus without it.
+  /// - `event_queue`: The queue the activation global is assigned to, it's dispatched until the token is received.
+  pub fn request_wayland(
+    activation: &Main<XdgActivationV1>,
+    surface: Option<&WlSurface>,
+    serial: Option<(u32, &WlSeat)>,
+    event_queue: &mut EventQueue,
+  ) -> Result<Self, PortalError> {
+    let token = Rc::new(RefCell::new(None));
+    let received = token.clone();
+    let request = activation.get_activation_token();
+    request.quick_assign(move |_, event, _| {
+      if let xdg_activation_token_v1::Event::Done { token } = event {
+        *received.borrow_mut() = Some(token);
+      }
+    });
+    if let Some(surface) = surface {
+      request.set_surface(surface);
+    }
+    if let Some((serial, seat)) = serial {
+      request.set_serial(serial, seat);
+    }
+    request.commit();
+    event_queue.sync_roundtrip(&mut (), |_, _, _| {})?;
+    let token = loop {
+      if let Some(token) = token.borrow_mut().take() {
+        break token;
+      }
+      event_queue.dispatch(&mut (), |_, _, _| {})?;
+    };
+    request.destroy();
+    Ok(Self(token))
+  }
+}
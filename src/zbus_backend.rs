@@ -0,0 +1,232 @@
+// Copyright 2022 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! The OpenURI portal on top of `zbus`, for applications that already use it instead of the `dbus` crate.
+//!
+//! The other portals aren't implemented on `zbus` yet. Without the default `dbus` feature, this is the only
+//! backend, and libdbus isn't linked.
+
+use crate::{OpenURIOptions, ParentWindowArg, PortalError, PortalFuture, DESTINATION, PATH};
+
+use std::{collections::HashMap, os::unix::io::RawFd};
+use zbus::{
+  blocking::{Connection, Proxy},
+  zvariant::{Fd, OwnedObjectPath, Value},
+};
+
+const OPEN_URI_INTERFACE: &str = "org.freedesktop.portal.OpenURI";
+
+/// Implementation of the `org.freedesktop.portal.OpenURI` Portal API for a `zbus` connection.
+/// See `OpenURI` for details about each method.
+pub trait ZbusOpenURI {
+  /// Asks to open a uri, see `OpenURI::open_uri()`.
+  fn open_uri(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    uri: &str,
+    options: OpenURIOptions,
+  ) -> Result<OwnedObjectPath, PortalError>;
+
+  /// Asks to open a local file, see `OpenURI::open_file()`.
+  ///
+  /// - `fd`: File descriptor for the file to open, which is only borrowed for the duration of the call.
+  fn open_file(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    fd: RawFd,
+    options: OpenURIOptions,
+  ) -> Result<OwnedObjectPath, PortalError>;
+
+  /// Asks to open the directory containing a local file, see `OpenURI::open_directory()`.
+  ///
+  /// - `fd`: File descriptor a file, which is only borrowed for the duration of the call.
+  fn open_directory(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    fd: RawFd,
+    options: OpenURIOptions,
+  ) -> Result<OwnedObjectPath, PortalError>;
+
+  /// Reads the "version" property for this D-Bus interface.
+  fn version(&self) -> Result<u32, PortalError>;
+}
+
+fn open_uri_proxy(connection: &Connection) -> Result<Proxy<'_>, PortalError> {
+  Proxy::new(connection, DESTINATION, PATH, OPEN_URI_INTERFACE).map_err(Into::into)
+}
+
+type Vardict = HashMap<&'static str, Value<'static>>;
+
+impl ZbusOpenURI for Connection {
+  fn open_uri(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    uri: &str,
+    options: OpenURIOptions,
+  ) -> Result<OwnedObjectPath, PortalError> {
+    open_uri_proxy(self)?
+      .call(
+        "OpenURI",
+        &(parent_window.into().as_str(), uri, Vardict::from(options)),
+      )
+      .map_err(Into::into)
+  }
+
+  fn open_file(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    fd: RawFd,
+    options: OpenURIOptions,
+  ) -> Result<OwnedObjectPath, PortalError> {
+    open_uri_proxy(self)?
+      .call(
+        "OpenFile",
+        &(
+          parent_window.into().as_str(),
+          Fd::from(fd),
+          Vardict::from(options),
+        ),
+      )
+      .map_err(Into::into)
+  }
+
+  fn open_directory(
+    &self,
+    parent_window: impl Into<ParentWindowArg>,
+    fd: RawFd,
+    options: OpenURIOptions,
+  ) -> Result<OwnedObjectPath, PortalError> {
+    open_uri_proxy(self)?
+      .call(
+        "OpenDirectory",
+        &(
+          parent_window.into().as_str(),
+          Fd::from(fd),
+          Vardict::from(options),
+        ),
+      )
+      .map_err(Into::into)
+  }
+
+  fn version(&self) -> Result<u32, PortalError> {
+    open_uri_proxy(self)?
+      .get_property("version")
+      .map_err(|error| PortalError::Zbus(error.into()))
+  }
+}
+
+/// Non-blocking implementation of the `org.freedesktop.portal.OpenURI` Portal API for an async `zbus` connection.
+/// See `ZbusOpenURI` for the blocking version, and `OpenURI` for details about each method.
+pub trait AsyncZbusOpenURI {
+  /// Asks to open a uri, see `OpenURI::open_uri()`.
+  fn open_uri<'a>(
+    &'a self,
+    parent_window: impl Into<ParentWindowArg>,
+    uri: &'a str,
+    options: OpenURIOptions,
+  ) -> PortalFuture<'a, OwnedObjectPath>;
+
+  /// Asks to open a local file, see `OpenURI::open_file()`.
+  ///
+  /// - `fd`: File descriptor for the file to open, which must stay open until the future completes.
+  fn open_file<'a>(
+    &'a self,
+    parent_window: impl Into<ParentWindowArg>,
+    fd: RawFd,
+    options: OpenURIOptions,
+  ) -> PortalFuture<'a, OwnedObjectPath>;
+
+  /// Asks to open the directory containing a local file, see `OpenURI::open_directory()`.
+  ///
+  /// - `fd`: File descriptor a file, which must stay open until the future completes.
+  fn open_directory<'a>(
+    &'a self,
+    parent_window: impl Into<ParentWindowArg>,
+    fd: RawFd,
+    options: OpenURIOptions,
+  ) -> PortalFuture<'a, OwnedObjectPath>;
+
+  /// Reads the "version" property for this D-Bus interface.
+  fn version(&self) -> PortalFuture<'_, u32>;
+}
+
+async fn async_open_uri_proxy(
+  connection: &zbus::Connection,
+) -> Result<zbus::Proxy<'static>, PortalError> {
+  zbus::Proxy::new(connection, DESTINATION, PATH, OPEN_URI_INTERFACE)
+    .await
+    .map_err(Into::into)
+}
+
+impl AsyncZbusOpenURI for zbus::Connection {
+  fn open_uri<'a>(
+    &'a self,
+    parent_window: impl Into<ParentWindowArg>,
+    uri: &'a str,
+    options: OpenURIOptions,
+  ) -> PortalFuture<'a, OwnedObjectPath> {
+    // Converted up front, so the future doesn't depend on the argument type being `Send`.
+    let parent_window: ParentWindowArg = parent_window.into();
+    Box::pin(async move {
+      async_open_uri_proxy(self)
+        .await?
+        .call(
+          "OpenURI",
+          &(parent_window.as_str(), uri, Vardict::from(options)),
+        )
+        .await
+        .map_err(Into::into)
+    })
+  }
+
+  fn open_file<'a>(
+    &'a self,
+    parent_window: impl Into<ParentWindowArg>,
+    fd: RawFd,
+    options: OpenURIOptions,
+  ) -> PortalFuture<'a, OwnedObjectPath> {
+    // Converted up front, so the future doesn't depend on the argument type being `Send`.
+    let parent_window: ParentWindowArg = parent_window.into();
+    Box::pin(async move {
+      async_open_uri_proxy(self)
+        .await?
+        .call(
+          "OpenFile",
+          &(parent_window.as_str(), Fd::from(fd), Vardict::from(options)),
+        )
+        .await
+        .map_err(Into::into)
+    })
+  }
+
+  fn open_directory<'a>(
+    &'a self,
+    parent_window: impl Into<ParentWindowArg>,
+    fd: RawFd,
+    options: OpenURIOptions,
+  ) -> PortalFuture<'a, OwnedObjectPath> {
+    // Converted up front, so the future doesn't depend on the argument type being `Send`.
+    let parent_window: ParentWindowArg = parent_window.into();
+    Box::pin(async move {
+      async_open_uri_proxy(self)
+        .await?
+        .call(
+          "OpenDirectory",
+          &(parent_window.as_str(), Fd::from(fd), Vardict::from(options)),
+        )
+        .await
+        .map_err(Into::into)
+    })
+  }
+
+  fn version(&self) -> PortalFuture<'_, u32> {
+    Box::pin(async move {
+      async_open_uri_proxy(self)
+        .await?
+        .get_property("version")
+        .await
+        .map_err(|error| PortalError::Zbus(error.into()))
+    })
+  }
+}